@@ -1,4 +1,4 @@
-use lrvm::board::Bus;
+use lrvm::board::{Bus, ResetKind};
 use lrvm_tools::exceptions::AuxHwException;
 use lrvm_tools::metadata::{DeviceCategory, DeviceMetadata};
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
@@ -67,7 +67,7 @@ impl Bus for AsyncCounter {
     }
 
     // Reset the component
-    fn reset(&mut self) {
+    fn reset(&mut self, _kind: ResetKind) {
         // Stop the existing thread
         self.stop();
 
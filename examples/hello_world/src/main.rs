@@ -1,4 +1,4 @@
-use lrvm::board::{Bus, MotherBoard};
+use lrvm::board::{Bus, MotherBoard, ResetKind};
 use lrvm_aux::display::BufferedDisplay;
 use lrvm_aux::storage::BootRom;
 use lrvm_aux::volatile_mem::Ram;
@@ -36,7 +36,7 @@ fn main() {
         mem.map_contiguous(0x0000_0000, [0, 1, 2]).mapping.unwrap();
     });
 
-    motherboard.reset();
+    motherboard.reset(ResetKind::Cold);
 
     println!("> Running the program...");
 
@@ -1,13 +1,16 @@
 //! The virtual machine is centered around a single motherboard which contains the CPU as well as a memory,
 //! which allows to connect components through memory mapping (MMIO) using the [`mem`] function.
 //!
-//! The motherboard can also emulate a reset button through the [`reset`] function which propagates the even through all connected [`Bus`].
+//! The motherboard can also emulate a power-on or a reset button through the [`reset`] function which propagates the event through all connected [`Bus`].
 
 use std::{cell::RefCell, rc::Rc};
 
-use crate::{cpu::Cpu, mem::MappedMemory};
+use crate::{
+    cpu::Cpu,
+    mem::{MappedMemory, MappingError},
+};
 
-use super::{Bus, HardwareBridge};
+use super::{Bus, DeviceTable, DeviceTableEntry, HardwareBridge, ResetKind};
 
 /// Virtual motherboard
 pub struct MotherBoard {
@@ -15,11 +18,41 @@ pub struct MotherBoard {
     aux: Vec<Rc<RefCell<Box<dyn Bus>>>>,
     /// Central Processing Unit (CPU)
     cpu: Cpu,
+    /// Content of the device table, if the motherboard has one
+    device_table: Option<Rc<RefCell<Vec<u32>>>>,
 }
 
 impl MotherBoard {
     /// Create a new motherboard with a set of components
     pub fn new(components: impl IntoIterator<Item = Box<dyn Bus>>) -> Self {
+        Self::connect(components.into_iter().collect(), None)
+    }
+
+    /// Create a new motherboard with a set of components, plus a read-only device table describing all of them,
+    /// so firmwares can discover the hardware without looping over `HWD` instructions.
+    ///
+    /// The table is connected as the last component and mapped at the provided address (usually [`DEVICE_TABLE_ADDR`](super::DEVICE_TABLE_ADDR)).
+    /// Its content is updated each time components are mapped through [`MotherBoard::map`] and when the motherboard is reset.
+    /// See the hardware documentation for the table's binary layout.
+    pub fn with_device_table(
+        components: impl IntoIterator<Item = Box<dyn Bus>>,
+        addr: u32,
+    ) -> Result<Self, MappingError> {
+        let mut components: Vec<_> = components.into_iter().collect();
+
+        let (table, words) = DeviceTable::new(components.len() + 1);
+        components.push(Box::new(table));
+
+        let mut motherboard = Self::connect(components, Some(words));
+        let table_id = motherboard.count() - 1;
+
+        motherboard.map(|mem| mem.map(addr, table_id))?;
+
+        Ok(motherboard)
+    }
+
+    /// (Internal) Connect components to a new motherboard
+    fn connect(components: Vec<Box<dyn Bus>>, device_table: Option<Rc<RefCell<Vec<u32>>>>) -> Self {
         let aux = components
             .into_iter()
             .map(|cp| Rc::new(RefCell::new(cp)))
@@ -33,10 +66,14 @@ impl MotherBoard {
         // Instanciate the memory
         let mem = MappedMemory::new(HardwareBridge::new(aux.clone()));
 
-        Self {
+        let mut motherboard = Self {
             cpu: Cpu::new(HardwareBridge::new(aux.clone()), mem),
             aux,
-        }
+            device_table,
+        };
+
+        motherboard.update_device_table();
+        motherboard
     }
 
     /// Perform operations on memory through a handler, example:
@@ -47,7 +84,9 @@ impl MotherBoard {
     /// motherboard.map(|mut mem| mem.map(0x10000000, 0).unwrap()); // Map first component (ID 0) to address 0x10000000
     /// ```
     pub fn map<T>(&mut self, mut mapper: impl FnMut(&mut MappedMemory) -> T) -> T {
-        mapper(&mut self.cpu.mem)
+        let result = mapper(&mut self.cpu.mem);
+        self.update_device_table();
+        result
     }
 
     /// Get a mutable reference to the CPU (required to make the CPU advance)
@@ -55,15 +94,17 @@ impl MotherBoard {
         &mut self.cpu
     }
 
-    /// Emulate a hard reset button on the motherboard.
+    /// Emulate a power-on ([`ResetKind::Cold`]) or a hard reset button on the motherboard ([`ResetKind::Warm`]).
     /// All components will receive a reset signal through their [`Bus`] interface.
     /// The CPU will also be reset, before every other component. Check [`CPU::reset`] for more informations.
-    pub fn reset(&mut self) {
-        self.cpu.reset();
+    pub fn reset(&mut self, kind: ResetKind) {
+        self.cpu.reset(kind);
 
         for aux in self.aux.iter() {
-            aux.borrow_mut().reset();
+            aux.borrow_mut().reset(kind);
         }
+
+        self.update_device_table();
     }
 
     /// Get the number of connected components
@@ -71,6 +112,41 @@ impl MotherBoard {
         self.aux.len()
     }
 
+    /// Get the generic name of a connected component
+    pub fn aux_name(&self, aux_id: usize) -> Option<&'static str> {
+        self.aux.get(aux_id).map(|aux| aux.borrow().name())
+    }
+
+    /// Get the metadata of a connected component
+    pub fn aux_metadata(&self, aux_id: usize) -> Option<[u32; 8]> {
+        self.aux.get(aux_id).map(|aux| aux.borrow().metadata())
+    }
+
+    /// (Internal) Write the current components' informations and mappings into the device table, if any
+    fn update_device_table(&mut self) {
+        let device_table = match &self.device_table {
+            Some(device_table) => device_table,
+            None => return,
+        };
+
+        let mem = &self.cpu.mem;
+
+        let content = DeviceTable::encode(self.aux.iter().enumerate().map(|(aux_id, aux)| {
+            let aux = aux.borrow();
+
+            DeviceTableEntry {
+                aux_id,
+                name: aux.name(),
+                metadata: aux.metadata(),
+                mapping: mem
+                    .get_mapping(aux_id)
+                    .map(|mapping| (mapping.addr, mapping.end_addr())),
+            }
+        }));
+
+        *device_table.borrow_mut() = content;
+    }
+
     /// Drop the motherboard
     pub fn drop(self) {
         std::mem::drop(self)
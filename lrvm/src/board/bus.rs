@@ -1,6 +1,9 @@
 //! In order to be able to connect to the motherboard, auxiliary components must implement the [`Bus`] trait.
 //!
-//! This trait describes how the component handles NAME, METADATA, READ, WRITE and RESET requests from the motherboard.
+//! This trait describes how the component handles NAME, METADATA, READ, WRITE and RESET requests from the motherboard,
+//! as well as the optional PEEK and POKE requests used by debugging tools and the optional TICK, DMA and REQUEST requests sent by the CPU on each cycle.
+
+use super::{BoardRequest, DmaPort, ResetKind};
 
 /// Bus of an auxiliary component.
 /// All components must implement this type in order to be connected to the motherboard.
@@ -23,7 +26,46 @@ pub trait Bus {
     /// May raise ane xception by assigning a non-zero exception code and data to the provided reference.
     fn write(&mut self, addr: u32, word: u32, ex: &mut u16);
 
-    /// Handle a RESET signal sent by the motherboard.
-    /// All volatile data from the component must be reset.
-    fn reset(&mut self);
+    /// Handle a RESET signal sent by the motherboard or the CPU.
+    /// All volatile data from the component must be reset ; the provided kind tells if the machine was just powered on (cold reset),
+    /// reset by the host (warm reset) or by the guest itself.
+    fn reset(&mut self, kind: ResetKind);
+
+    /// Inspect the word at an address without any side effect, for debugging tools.
+    /// The provided address is guaranteed to be aligned (multiple of 4) and strictly lower than the provided size.
+    /// Returns `None` if the component has no state to inspect at this address (default behaviour).
+    fn peek(&self, _addr: u32) -> Option<u32> {
+        None
+    }
+
+    /// Modify the word at an address without any side effect (no action is triggered), for debugging tools.
+    /// The provided address is guaranteed to be aligned (multiple of 4) and strictly lower than the provided size.
+    /// Returns `false` if the component has no state to modify at this address (default behaviour).
+    fn poke(&mut self, _addr: u32, _word: u32) -> bool {
+        false
+    }
+
+    /// Check if the component must receive a TICK request on each CPU cycle.
+    /// This is only checked once, when the component is connected to the motherboard.
+    /// Disabled by default, as ticking components slow down the VM.
+    fn ticks(&self) -> bool {
+        false
+    }
+
+    /// Answer a TICK request, sent by the CPU on each cycle if [`Bus::ticks`] returned `true`.
+    /// Returns an interruption code to raise a hardware interruption in the CPU.
+    fn tick(&mut self) -> Option<u8> {
+        None
+    }
+
+    /// Answer a DMA request, sent by the CPU on each cycle right after the TICK request if [`Bus::ticks`] returned `true`.
+    /// The component may transfer data from or to the mapped memory through the provided port (default behaviour: do nothing).
+    fn dma(&mut self, _port: &mut DmaPort) {}
+
+    /// Answer a REQUEST poll, sent by the CPU on each cycle right after the DMA request if [`Bus::ticks`] returned `true`.
+    /// Returns a request for the motherboard, for instance to reset the whole machine (default behaviour: no request).
+    /// Once a component made a request, the following ones are not polled during this cycle, so they should keep their request until polled.
+    fn request(&mut self) -> Option<BoardRequest> {
+        None
+    }
 }
@@ -0,0 +1,130 @@
+use std::{cell::RefCell, rc::Rc};
+
+use super::{Bus, ResetKind};
+
+/// Address the device table is usually mapped at (see [`MotherBoard::with_device_table`](super::MotherBoard::with_device_table))
+pub const DEVICE_TABLE_ADDR: u32 = 0xFFFF_0000;
+
+/// Magic word at the beginning of the device table ("LRDT" in ASCII)
+pub const DEVICE_TABLE_MAGIC: u32 = 0x4C52_4454;
+
+/// Version of the device table's layout
+pub const DEVICE_TABLE_VERSION: u32 = 1;
+
+/// Size of the device table's header, in bytes
+pub const DEVICE_TABLE_HEADER_SIZE: u32 = 0x10;
+
+/// Size of a single entry of the device table, in bytes
+pub const DEVICE_TABLE_ENTRY_SIZE: u32 = 0x40;
+
+/// Value of the mapping start and end addresses of components that are not mapped
+pub const DEVICE_TABLE_NOT_MAPPED: u32 = 0xFFFF_FFFF;
+
+/// Read-only component exposing the device table, whose content is maintained by the motherboard
+pub(crate) struct DeviceTable {
+    words: Rc<RefCell<Vec<u32>>>,
+    size: u32,
+}
+
+/// Description of a component, as written in the device table
+pub(crate) struct DeviceTableEntry<'a> {
+    pub aux_id: usize,
+    pub name: &'a str,
+    pub metadata: [u32; 8],
+    pub mapping: Option<(u32, u32)>,
+}
+
+impl DeviceTable {
+    /// Create a device table for a given number of components (including the table itself)
+    pub fn new(count: usize) -> (Self, Rc<RefCell<Vec<u32>>>) {
+        let size = DEVICE_TABLE_HEADER_SIZE + count as u32 * DEVICE_TABLE_ENTRY_SIZE;
+        let words = Rc::new(RefCell::new(vec![0; size as usize / 4]));
+
+        (
+            Self {
+                words: Rc::clone(&words),
+                size,
+            },
+            words,
+        )
+    }
+
+    /// Encode the table's content
+    pub fn encode<'a>(entries: impl ExactSizeIterator<Item = DeviceTableEntry<'a>>) -> Vec<u32> {
+        let mut words = vec![
+            DEVICE_TABLE_MAGIC,
+            DEVICE_TABLE_VERSION,
+            entries.len() as u32,
+            DEVICE_TABLE_ENTRY_SIZE,
+        ];
+
+        for entry in entries {
+            let (start_addr, end_addr) = entry
+                .mapping
+                .unwrap_or((DEVICE_TABLE_NOT_MAPPED, DEVICE_TABLE_NOT_MAPPED));
+
+            words.extend([
+                entry.aux_id as u32,
+                entry.metadata[0],
+                entry.metadata[1],
+                entry.metadata[3],
+                entry.metadata[4],
+                entry.metadata[5],
+                start_addr,
+                end_addr,
+            ]);
+
+            // Names are cut to 32 bytes, the same way the hardware bridge does
+            let mut name_len = entry.name.len().min(32);
+
+            while !entry.name.is_char_boundary(name_len) {
+                name_len -= 1;
+            }
+
+            let mut name = [0; 32];
+            name[..name_len].copy_from_slice(&entry.name.as_bytes()[..name_len]);
+
+            words.extend(
+                name.chunks(4)
+                    .map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+            );
+        }
+
+        words
+    }
+}
+
+impl Bus for DeviceTable {
+    fn name(&self) -> &'static str {
+        "Device Table"
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        // Category: storage (0x00022000), type: device table (0x00000200)
+        [
+            DEVICE_TABLE_MAGIC,
+            DEVICE_TABLE_VERSION,
+            self.size,
+            0x0002_2000,
+            0x0000_0200,
+            0,
+            0,
+            0,
+        ]
+    }
+
+    fn read(&mut self, addr: u32, _ex: &mut u16) -> u32 {
+        self.words.borrow()[addr as usize / 4]
+    }
+
+    fn write(&mut self, _addr: u32, _word: u32, ex: &mut u16) {
+        // Memory is not writable
+        *ex = 0x31 << 8;
+    }
+
+    fn reset(&mut self, _kind: ResetKind) {}
+
+    fn peek(&self, addr: u32) -> Option<u32> {
+        Some(self.words.borrow()[addr as usize / 4])
+    }
+}
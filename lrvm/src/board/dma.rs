@@ -0,0 +1,47 @@
+use crate::mem::MappedMemory;
+
+/// Access to the mapped memory given to a component answering a DMA request (see [`Bus::dma`](super::Bus::dma)),
+/// so it can transfer data without going through the CPU.
+///
+/// Transfers are made with physical addresses, and cannot target the requesting component itself:
+/// such transfers fail with a generic physical read or write error.
+pub struct DmaPort<'a> {
+    mem: &'a mut MappedMemory,
+    aux_id: usize,
+}
+
+impl<'a> DmaPort<'a> {
+    /// (Internal) Create a port for the provided component
+    pub(crate) fn new(mem: &'a mut MappedMemory, aux_id: usize) -> Self {
+        Self { mem, aux_id }
+    }
+
+    /// Read a word from the mapped memory (see [`MappedMemory::read`]).
+    /// If the value of `ex` is not zero when this function returns, a hardware exception occurred with the exception code and data in it.
+    pub fn read(&mut self, addr: u32, ex: &mut u16) -> u32 {
+        if self.targets_self(addr) {
+            *ex = 0x2000;
+            return 0;
+        }
+
+        self.mem.read(addr, ex)
+    }
+
+    /// Write a word to the mapped memory (see [`MappedMemory::write`]).
+    /// If the value of `ex` is not zero when this function returns, a hardware exception occurred with the exception code and data in it.
+    pub fn write(&mut self, addr: u32, word: u32, ex: &mut u16) {
+        if self.targets_self(addr) {
+            *ex = 0x3000;
+            return;
+        }
+
+        self.mem.write(addr, word, ex)
+    }
+
+    /// (Internal) Check if an address is mapped to the requesting component
+    fn targets_self(&self, addr: u32) -> bool {
+        self.mem
+            .mapping_at(addr)
+            .is_some_and(|mapping| mapping.aux_id == self.aux_id)
+    }
+}
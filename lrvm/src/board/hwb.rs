@@ -1,6 +1,8 @@
 use std::{cell::RefCell, rc::Rc};
 
-use super::Bus;
+use crate::mem::MappedMemory;
+
+use super::{BoardRequest, Bus, DmaPort, ResetKind};
 
 /// Auxiliary component's bus and internal data cache
 struct AuxWithCache {
@@ -60,24 +62,31 @@ impl AuxWithCache {
 /// Multiple hardware bridges can co-exit on the motheboard, but their cache is not shared.
 pub struct HardwareBridge {
     aux: Vec<AuxWithCache>,
+    ticking: Vec<usize>,
 }
 
 impl HardwareBridge {
     pub fn new(aux: impl IntoIterator<Item = Rc<RefCell<Box<dyn Bus>>>>) -> Self {
-        Self {
-            aux: aux
-                .into_iter()
-                .enumerate()
-                .map(|(id, shared_bus)| {
-                    assert!(
-                        id < u32::MAX as usize,
-                        "Hardware bridge cannot handle more than 2^32 components!"
-                    );
-
-                    AuxWithCache::create_from_aux(id, shared_bus)
-                })
-                .collect(),
-        }
+        let aux: Vec<_> = aux
+            .into_iter()
+            .enumerate()
+            .map(|(id, shared_bus)| {
+                assert!(
+                    id < u32::MAX as usize,
+                    "Hardware bridge cannot handle more than 2^32 components!"
+                );
+
+                AuxWithCache::create_from_aux(id, shared_bus)
+            })
+            .collect();
+
+        let ticking = aux
+            .iter()
+            .filter(|aux| aux.shared_bus.borrow().ticks())
+            .map(|aux| aux.cache.id)
+            .collect();
+
+        Self { aux, ticking }
     }
 
     /// Count the number of connected compoents
@@ -139,9 +148,66 @@ impl HardwareBridge {
     }
 
     /// Send a RESET signal to a component
-    pub fn reset(&mut self, aux_id: usize) -> Option<()> {
+    pub fn reset(&mut self, aux_id: usize, kind: ResetKind) -> Option<()> {
+        self.aux
+            .get(aux_id)
+            .map(|aux| aux.shared_bus.borrow_mut().reset(kind))
+    }
+
+    /// Send a TICK request to all the components requiring it, in order.
+    /// The interruption codes they return are pushed to the provided list, unless they are already pending:
+    /// like the pending bits of an interruption controller, each code is only kept once, so the list holds at most 256 codes.
+    pub fn tick(&mut self, interruptions: &mut Vec<u8>) {
+        for id in &self.ticking {
+            if let Some(code) = self.aux[*id].shared_bus.borrow_mut().tick() {
+                if !interruptions.contains(&code) {
+                    interruptions.push(code);
+                }
+            }
+        }
+    }
+
+    /// Send a DMA request to all the components requiring TICK requests, in order, so they can access the provided memory
+    pub fn dma(&mut self, mem: &mut MappedMemory) {
+        for id in &self.ticking {
+            self.aux[*id]
+                .shared_bus
+                .borrow_mut()
+                .dma(&mut DmaPort::new(mem, *id));
+        }
+    }
+
+    /// Send a REQUEST poll to the components requiring TICK requests, in order, until one of them makes a request.
+    /// Components after it are not polled, so their own requests stay pending until a next cycle.
+    pub fn request(&mut self) -> Option<BoardRequest> {
+        self.ticking
+            .iter()
+            .find_map(|id| self.aux[*id].shared_bus.borrow_mut().request())
+    }
+
+    /// Send a PEEK request to a component.
+    /// Returns `None` if the component doesn't exist or has no state to inspect at this address.
+    pub fn peek(&self, aux_id: usize, addr: u32) -> Option<u32> {
+        assert!(
+            addr % 4 == 0,
+            "Hardware bridge does not support peeking unaligned addresses"
+        );
+
+        self.aux
+            .get(aux_id)
+            .and_then(|aux| aux.shared_bus.borrow().peek(addr))
+    }
+
+    /// Send a POKE request to a component.
+    /// Returns `false` if the component doesn't exist or has no state to modify at this address.
+    pub fn poke(&mut self, aux_id: usize, addr: u32, word: u32) -> bool {
+        assert!(
+            addr % 4 == 0,
+            "Hardware bridge does not support poking unaligned addresses"
+        );
+
         self.aux
             .get(aux_id)
-            .map(|aux| aux.shared_bus.borrow_mut().reset())
+            .is_some_and(|aux| aux.shared_bus.borrow_mut().poke(addr, word))
     }
 }
@@ -1,7 +1,11 @@
 /// Virtual motherboard-related structures.
 mod board;
 mod bus;
+mod device_table;
+mod dma;
 mod hwb;
+mod request;
+mod reset;
 
 pub(crate) use self::hwb::*;
-pub use self::{board::*, bus::*};
+pub use self::{board::*, bus::*, device_table::*, dma::*, request::*, reset::*};
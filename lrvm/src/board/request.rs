@@ -0,0 +1,21 @@
+/// Request sent by a component to the motherboard (see [`Bus::request`](super::Bus::request))
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BoardRequest {
+    /// Reset the CPU and all components, with the [`ResetKind::Hardware`](super::ResetKind::Hardware) kind
+    Reset,
+    /// Halt the CPU
+    Halt,
+    /// Halt the CPU, telling the host why the machine was shut down (see [`Cpu::shutdown_reason`](crate::cpu::Cpu::shutdown_reason))
+    Shutdown(ShutdownReason),
+}
+
+/// Reason of a shutdown requested by a component with [`BoardRequest::Shutdown`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShutdownReason {
+    /// The guest powered the machine off with an exit code
+    Exit(u32),
+    /// The guest's test suite passed
+    TestPassed,
+    /// The guest's test suite failed, with a failure code
+    TestFailed(u32),
+}
@@ -0,0 +1,40 @@
+/// Kind of reset sent to the CPU and the components, so they can tell a power-on from a reboot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResetKind {
+    /// Power-on reset, when the machine starts (cold reset)
+    Cold,
+    /// Reset triggered by the host, like pressing the motherboard's reset button (warm reset)
+    Warm,
+    /// Reset requested by the guest through the `RESET` instruction
+    Guest {
+        /// Was the instruction run in supervisor mode?
+        supervisor: bool,
+    },
+    /// Reset requested by a component through [`BoardRequest::Reset`](super::BoardRequest::Reset), like a watchdog timer
+    Hardware,
+}
+
+impl ResetKind {
+    /// Encode the reset kind, as returned to the guest by the `HWD_RESET_REASON` hardware information
+    pub fn encode(self) -> u32 {
+        match self {
+            Self::Cold => 0x00,
+            Self::Warm => 0x01,
+            Self::Guest { supervisor: false } => 0x10,
+            Self::Guest { supervisor: true } => 0x11,
+            Self::Hardware => 0x20,
+        }
+    }
+
+    /// Decode a reset kind
+    pub fn decode(code: u32) -> Option<Self> {
+        match code {
+            0x00 => Some(Self::Cold),
+            0x01 => Some(Self::Warm),
+            0x10 => Some(Self::Guest { supervisor: false }),
+            0x11 => Some(Self::Guest { supervisor: true }),
+            0x20 => Some(Self::Hardware),
+            _ => None,
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    board::HardwareBridge,
+    board::{BoardRequest, HardwareBridge, ResetKind, ShutdownReason},
     mem::MappedMemory,
     mmu::{MemAction, Mmu},
 };
@@ -20,6 +20,14 @@ pub struct Cpu {
     cycles: u128,
     /// Is the CPU halted?
     halted: bool,
+    /// Reason of the shutdown requested by a component since the last reset
+    shutdown: Option<ShutdownReason>,
+    /// Last instruction fetched by the CPU
+    last_instr: Option<u32>,
+    /// Kind of the last reset
+    last_reset: ResetKind,
+    /// Hardware interruptions waiting for the CPU to go back to userland, in order of arrival
+    pending_itr: Vec<u8>,
     /// (Internal) Did the current cycle change the PC register?
     _cycle_changed_pc: bool,
 }
@@ -34,6 +42,10 @@ impl Cpu {
             hwb,
             cycles: 0,
             halted: true,
+            shutdown: None,
+            last_instr: None,
+            last_reset: ResetKind::Cold,
+            pending_itr: vec![],
             _cycle_changed_pc: false,
         };
 
@@ -43,33 +55,84 @@ impl Cpu {
         cpu
     }
 
-    /// Hanldle a RESET signal from the motherboard
-    pub fn reset(&mut self) {
+    /// Hanldle a RESET signal from the motherboard or from a `RESET` instruction.
+    /// The kind of reset is remembered and can be retrieved by the guest through the `HWD` instruction.
+    pub fn reset(&mut self, kind: ResetKind) {
+        self.last_reset = kind;
         self.regs.reset();
         self.regs.smt = 1;
         self.cycles = 0;
         self.halted = false;
+        self.shutdown = None;
+        self.last_instr = None;
+        self.pending_itr.clear();
         self._cycle_changed_pc = true;
     }
 
-    /// Run the next instruction. Returns:
-    /// * `Ok(true)` if the instruction run correctly
-    /// * `Ok(false)` if the CPU is currently halted
-    /// * `Err()` if an exception occurred *except for interruptions*
+    /// Run the next instruction.
+    /// Returns `true` if a hardware interruption was delivered instead, which raised a `0xF0` exception
+    /// that is not a failure of the running program.
     #[allow(clippy::should_implement_trait)]
-    pub fn next(&mut self) {
+    pub fn next(&mut self) -> bool {
         // Do not run if the CPU is halted
         if self.halted {
-            return;
+            return false;
         }
 
         // Cycle goes back to 0 when overflowing
         self.cycles = self.cycles.wrapping_add(1);
 
+        // Let the ticking components know a new cycle started, then let them access the memory
+        self.hwb.tick(&mut self.pending_itr);
+        self.hwb.dma(&mut self.mem);
+
+        // Components may request the whole machine to be reset or the CPU to be halted (or shut down), instead of running the current cycle's instruction
+        let request = self.hwb.request();
+
+        if request.is_some() {
+            self.last_instr = None;
+        }
+
+        match request {
+            Some(BoardRequest::Reset) => {
+                for id in 0..self.hwb.count() {
+                    self.hwb.reset(id, ResetKind::Hardware).unwrap();
+                }
+
+                self.reset(ResetKind::Hardware);
+                return false;
+            }
+            Some(BoardRequest::Halt) => {
+                self.halted = true;
+                return false;
+            }
+            Some(BoardRequest::Shutdown(reason)) => {
+                self.halted = true;
+                self.shutdown = Some(reason);
+                return false;
+            }
+            None => {}
+        }
+
+        // Hardware interruptions are only handled in userland, so they never interrupt an exception handler.
+        // The interruption replaces the current cycle's instruction, which will be run when the handler returns to `era`.
+        if !self.pending_itr.is_empty() && !self.sv_mode() {
+            let code = self.pending_itr.remove(0);
+            self.last_instr = None;
+            self.exception(0xF0, Some(code.into()));
+            return true;
+        }
+
         // Get the instruction to run
         let instr = match self.mem_exec(self.regs.pc) {
-            Err(_) => return,
-            Ok(bytes) => bytes.to_be_bytes(),
+            Err(_) => {
+                self.last_instr = None;
+                return false;
+            }
+            Ok(word) => {
+                self.last_instr = Some(word);
+                word.to_be_bytes()
+            }
         };
 
         // Get its opcode (5 first bits of the first byte)
@@ -88,7 +151,7 @@ impl Cpu {
 
         // Run the decoded instruction
         if self.run_instr(opcode, opregs, params).is_err() {
-            return;
+            return false;
         }
 
         // By default, the program counter (located in the PC register) is incremented of 4 bytes to make the CPU retrieve the next instruction
@@ -98,6 +161,8 @@ impl Cpu {
         if !self._cycle_changed_pc {
             self.regs.pc = self.regs.pc.wrapping_add(4);
         }
+
+        false
     }
 
     /// Check if the CPU is halted
@@ -105,6 +170,29 @@ impl Cpu {
         self.halted
     }
 
+    /// Get the reason of the shutdown requested by a component, if the CPU was halted this way since the last reset
+    pub fn shutdown_reason(&self) -> Option<ShutdownReason> {
+        self.shutdown
+    }
+
+    /// Get the kind of the last reset
+    pub fn last_reset(&self) -> ResetKind {
+        self.last_reset
+    }
+
+    /// Get the hardware interruptions that were raised by components but not handled yet, in order of arrival.
+    /// An interruption raised again while it is still pending is only delivered once.
+    pub fn pending_interruptions(&self) -> &[u8] {
+        &self.pending_itr
+    }
+
+    /// Get the last instruction word fetched by the CPU.
+    /// Returns `None` if no instruction was fetched since the last reset, if the last fetch failed,
+    /// or if the last cycle was replaced by a hardware interruption or a component's request.
+    pub fn last_instr(&self) -> Option<u32> {
+        self.last_instr
+    }
+
     /// Get the number of cycles the CPU run so far
     /// Note that this number goes back to 0 after reaching its maximum (overflow).
     pub fn cycles(&self) -> u128 {
@@ -473,6 +561,11 @@ impl Cpu {
                     return self.write_reg(reg_dest, self.hwb.count() as u32);
                 }
 
+                // The reset reason belongs to the processor, so the component ID is ignored
+                if hw_info == 0x30 {
+                    return self.write_reg(reg_dest, self.last_reset.encode());
+                }
+
                 let aux_id = usize::try_from(aux_id)
                     .map_err(|_| self.exception(0x10, Some(aux_id as u16)))?;
 
@@ -500,12 +593,15 @@ impl Cpu {
                 // Get the two modes (one per byte)
                 let (cpu_mode, aux_mode) = ((mode & 0xF0) as u8, (mode & 0x0F) as u8);
 
+                // Remember the mode the reset was issued from, as the processor's reset will enable supervisor mode
+                let kind = ResetKind::Guest { supervisor: self.sv_mode() };
+
                 // Determine which components should be reset
                 match aux_mode {
                     // Reset all components
                     0x0 => {
                         for id in 0..self.hwb.count() {
-                            self.hwb.reset(id).unwrap();
+                            self.hwb.reset(id, kind).unwrap();
                         }
                     },
 
@@ -514,7 +610,7 @@ impl Cpu {
                         let id = usize::try_from(self.regs.avr)
                             .map_err(|_| self.exception(0x10, Some(self.regs.avr as u16)))?;
 
-                            self.hwb.reset(id)
+                            self.hwb.reset(id, kind)
                                 .ok_or_else(|| self.exception(0x10, Some(self.regs.avr as u16)))?;
                     },
 
@@ -535,7 +631,7 @@ impl Cpu {
 
                         for id in 0..self.hwb.count() {
                             if test(id) {
-                                self.hwb.reset(id).unwrap();
+                                self.hwb.reset(id, kind).unwrap();
                             }
                         }
                     },
@@ -546,7 +642,7 @@ impl Cpu {
 
                 // Reset the processor
                 if cpu_mode == 0 {
-                    self.reset();
+                    self.reset(kind);
                 }
 
                 Ok(())
@@ -782,6 +878,9 @@ impl Cpu {
             + (u32::from(code) << 16)
             + u32::from(associated.unwrap_or(0));
 
+        // Remember the address that raised the exception
+        self.regs.era = self.regs.pc;
+
         // Jump to the Exception Vector address
         self.regs.pc = self.regs.ev;
 
@@ -143,6 +143,111 @@ impl MappedMemory {
         }
     }
 
+    /// Inspect an arbitrary address in the mapped memory without triggering any side effect (see [`Bus::peek`]).
+    /// Returns `None` if no component is mapped at this address or if it has no state to inspect there.
+    pub fn peek(&self, addr: u32) -> Option<u32> {
+        assert!(
+            addr % 4 == 0,
+            "Memory does not support peeking unaligned addresses"
+        );
+
+        let mapping = self.mapping_at(addr)?;
+        self.bridge.peek(mapping.aux_id, addr - mapping.addr)
+    }
+
+    /// Modify an arbitrary address in the mapped memory without triggering any side effect (see [`Bus::poke`]).
+    /// Returns `false` if no component is mapped at this address or if it has no state to modify there.
+    pub fn poke(&mut self, addr: u32, word: u32) -> bool {
+        assert!(
+            addr % 4 == 0,
+            "Memory does not support poking unaligned addresses"
+        );
+
+        match self.mapping_at(addr) {
+            Some(mapping) => self.bridge.poke(mapping.aux_id, addr - mapping.addr, word),
+            None => false,
+        }
+    }
+
+    /// Inspect a range of bytes, which may be unaligned and span multiple components, without triggering any side effect.
+    /// Returns the (aligned) address of the first word that could not be inspected in case of failure,
+    /// or the address of the first word of the range if it extends beyond the end of the address space.
+    pub fn peek_bytes(&self, addr: u32, len: u32) -> Result<Vec<u8>, u32> {
+        let mut bytes = vec![];
+
+        if len == 0 {
+            return Ok(bytes);
+        }
+
+        let mut word_addr = addr - addr % 4;
+
+        let end_addr = addr.checked_add(len - 1).ok_or(word_addr)?;
+
+        loop {
+            let word = self.peek(word_addr).ok_or(word_addr)?.to_be_bytes();
+
+            for (i, byte) in word.into_iter().enumerate() {
+                let byte_addr = word_addr + i as u32;
+
+                if byte_addr >= addr && byte_addr <= end_addr {
+                    bytes.push(byte);
+                }
+            }
+
+            if end_addr - word_addr < 4 {
+                return Ok(bytes);
+            }
+
+            word_addr += 4;
+        }
+    }
+
+    /// Modify a range of bytes, which may be unaligned and span multiple components, without triggering any side effect.
+    /// Partially-modified words are inspected first to keep their other bytes.
+    /// Returns the (aligned) address of the first word that could not be modified in case of failure ;
+    /// words before this address were already modified.
+    /// If the range extends beyond the end of the address space, nothing is modified and the address of its first word is returned.
+    pub fn poke_bytes(&mut self, addr: u32, bytes: &[u8]) -> Result<(), u32> {
+        if bytes.is_empty() {
+            return Ok(());
+        }
+
+        let mut word_addr = addr - addr % 4;
+
+        let end_addr = u32::try_from(bytes.len() - 1)
+            .ok()
+            .and_then(|len| addr.checked_add(len))
+            .ok_or(word_addr)?;
+
+        loop {
+            let partial = word_addr < addr || end_addr - word_addr < 3;
+
+            let mut word = if partial {
+                self.peek(word_addr).ok_or(word_addr)?.to_be_bytes()
+            } else {
+                [0; 4]
+            };
+
+            for (i, byte) in word.iter_mut().enumerate() {
+                let byte_addr = word_addr + i as u32;
+
+                if byte_addr >= addr && byte_addr <= end_addr {
+                    *byte = bytes[(byte_addr - addr) as usize];
+                }
+            }
+
+            if !self.poke(word_addr, u32::from_be_bytes(word)) {
+                return Err(word_addr);
+            }
+
+            if end_addr - word_addr < 4 {
+                return Ok(());
+            }
+
+            word_addr += 4;
+        }
+    }
+
     /// Get the mapping of a given component
     pub fn get_mapping(&self, aux_id: usize) -> Option<&Mapping> {
         self.mappings
@@ -150,6 +255,14 @@ impl MappedMemory {
             .find(|mapping| mapping.aux_id == aux_id)
     }
 
+    /// (Internal) get the mapping containing an address
+    pub(crate) fn mapping_at(&self, addr: u32) -> Option<Mapping> {
+        self.mappings
+            .iter()
+            .find(|mapping| mapping.addr <= addr && addr <= mapping.end_addr())
+            .copied()
+    }
+
     /// (Internal) map an auxiliary component to the memory
     fn internal_map(
         &mut self,
@@ -0,0 +1,243 @@
+use std::{
+    cell::RefCell,
+    fs::File,
+    io::{BufWriter, Result as IOResult, Seek, SeekFrom, Write},
+    path::PathBuf,
+    rc::Rc,
+};
+
+use super::AudioFormat;
+
+/// Host side of a [`Pcm`](super::Pcm) component, playing or storing the samples
+pub trait AudioBackend {
+    /// Start a playback with the provided format
+    fn start(&mut self, format: AudioFormat);
+
+    /// Play a frame, made of one sample per channel.
+    /// Samples are signed and 16-bit, whatever the format's number of bits.
+    fn play(&mut self, frame: &[i16]);
+
+    /// Stop the playback
+    fn stop(&mut self);
+}
+
+/// A playback captured by a [`SampleCapture`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recording {
+    /// Format of the playback
+    pub format: AudioFormat,
+    /// Interleaved samples
+    pub samples: Vec<i16>,
+    /// Was the playback stopped?
+    pub stopped: bool,
+}
+
+impl Recording {
+    /// Encode the recording as a WAV file
+    pub fn to_wav(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(self.samples.len() * usize::from(self.format.bits / 8));
+
+        for sample in &self.samples {
+            data.extend_from_slice(&self.format.encode_sample(*sample));
+        }
+
+        let mut wav = self.format.wav_header(data.len() as u32).to_vec();
+        wav.extend_from_slice(&data);
+        wav
+    }
+}
+
+/// In-memory backend keeping all playbacks, shared between the component and the code inspecting them (e.g. tests).
+/// Clones of a capture all share the same recordings.
+#[derive(Debug, Clone, Default)]
+pub struct SampleCapture(Rc<RefCell<Vec<Recording>>>);
+
+impl SampleCapture {
+    /// Create an empty capture
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the number of captured playbacks
+    pub fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    /// Check if no playback was captured yet
+    pub fn is_empty(&self) -> bool {
+        self.0.borrow().is_empty()
+    }
+
+    /// Get all captured playbacks
+    pub fn recordings(&self) -> Vec<Recording> {
+        self.0.borrow().clone()
+    }
+
+    /// Get the last captured playback
+    pub fn last_recording(&self) -> Option<Recording> {
+        self.0.borrow().last().cloned()
+    }
+}
+
+impl AudioBackend for SampleCapture {
+    fn start(&mut self, format: AudioFormat) {
+        self.0.borrow_mut().push(Recording {
+            format,
+            samples: vec![],
+            stopped: false,
+        });
+    }
+
+    fn play(&mut self, frame: &[i16]) {
+        if let Some(recording) = self.0.borrow_mut().last_mut() {
+            recording.samples.extend_from_slice(frame);
+        }
+    }
+
+    fn stop(&mut self) {
+        if let Some(recording) = self.0.borrow_mut().last_mut() {
+            recording.stopped = true;
+        }
+    }
+}
+
+/// Headless backend writing each playback to a WAV file.
+///
+/// The first `{}` in the path pattern is replaced by the playback's number (starting at `0`) ;
+/// if there is none, the same file is overwritten by each playback.
+/// The file is completed when the playback stops or when the backend is dropped.
+/// As WAV files are limited to 4 GB, the file is completed early if the playback gets longer, and the rest of the playback is dropped.
+/// Write failures are reported on the standard error output, and do not stop the VM.
+pub struct WavFileBackend {
+    pattern: String,
+    count: u64,
+    current: Option<WavFile>,
+}
+
+/// (Internal) Maximum length of a WAV file's data, so the size of its RIFF chunk fits in 32 bits
+const WAV_MAX_DATA_LEN: u32 = u32::MAX - 36;
+
+/// (Internal) WAV file being written
+struct WavFile {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    format: AudioFormat,
+    data_len: u32,
+}
+
+impl WavFileBackend {
+    /// Create a backend writing playbacks to files matching the provided pattern
+    pub fn new(pattern: impl Into<String>) -> Self {
+        Self {
+            pattern: pattern.into(),
+            count: 0,
+            current: None,
+        }
+    }
+
+    /// Get the path of a playback's file
+    pub fn playback_path(&self, number: u64) -> PathBuf {
+        PathBuf::from(self.pattern.replacen("{}", &number.to_string(), 1))
+    }
+
+    /// (Internal) Write the final header of the current file
+    fn finish(&mut self) {
+        let Some(mut wav) = self.current.take() else {
+            return;
+        };
+
+        let header = wav.format.wav_header(wav.data_len);
+
+        let result: IOResult<()> = (|| {
+            wav.writer.seek(SeekFrom::Start(0))?;
+            wav.writer.write_all(&header)?;
+            wav.writer.flush()
+        })();
+
+        if let Err(err) = result {
+            eprintln!(
+                "Warning: failed to write audio to '{}': {}",
+                wav.path.display(),
+                err
+            );
+        }
+    }
+}
+
+impl AudioBackend for WavFileBackend {
+    fn start(&mut self, format: AudioFormat) {
+        self.finish();
+
+        let path = self.playback_path(self.count);
+        self.count += 1;
+
+        let result = File::create(&path).and_then(|file| {
+            let mut writer = BufWriter::new(file);
+            writer.write_all(&format.wav_header(0))?;
+            Ok(writer)
+        });
+
+        match result {
+            Ok(writer) => {
+                self.current = Some(WavFile {
+                    path,
+                    writer,
+                    format,
+                    data_len: 0,
+                })
+            }
+            Err(err) => eprintln!(
+                "Warning: failed to write audio to '{}': {}",
+                path.display(),
+                err
+            ),
+        }
+    }
+
+    fn play(&mut self, frame: &[i16]) {
+        let Some(wav) = &mut self.current else {
+            return;
+        };
+
+        // Only complete frames are written
+        let frame_len = (frame.len() * usize::from(wav.format.bits / 8)) as u32;
+
+        match wav.data_len.checked_add(frame_len) {
+            Some(data_len) if data_len <= WAV_MAX_DATA_LEN => wav.data_len = data_len,
+            _ => {
+                eprintln!(
+                    "Warning: '{}' reached the maximum size of WAV files, the rest of the playback is dropped",
+                    wav.path.display()
+                );
+
+                self.finish();
+                return;
+            }
+        }
+
+        for sample in frame {
+            let bytes = wav.format.encode_sample(*sample);
+
+            if let Err(err) = wav.writer.write_all(&bytes) {
+                eprintln!(
+                    "Warning: failed to write audio to '{}': {}",
+                    wav.path.display(),
+                    err
+                );
+
+                self.current = None;
+                return;
+            }
+        }
+    }
+
+    fn stop(&mut self) {
+        self.finish();
+    }
+}
+
+impl Drop for WavFileBackend {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
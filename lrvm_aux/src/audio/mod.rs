@@ -0,0 +1,4 @@
+mod backend;
+mod pcm;
+
+pub use self::{backend::*, pcm::*};
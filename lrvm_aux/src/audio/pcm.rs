@@ -0,0 +1,338 @@
+//! The PCM audio component plays samples sent by the guest through a host backend.
+//! See [`Pcm`] for more details.
+
+use std::collections::VecDeque;
+
+use lrvm::board::{Bus, ResetKind};
+use lrvm_tools::{
+    exceptions::AuxHwException,
+    metadata::{AudioType, DeviceMetadata},
+};
+
+use super::AudioBackend;
+
+/// Control flag: play the samples of the FIFO (clearing it stops the playback once the FIFO's complete frames are played)
+pub const PCM_PLAY: u32 = 0x01;
+/// Control flag: raise a hardware interruption when the FIFO gets half empty
+pub const PCM_FIFO_INTERRUPT: u32 = 0x02;
+
+/// Status flag: a playback is in progress
+pub const PCM_PLAYING: u32 = 0x01;
+/// Status flag: the FIFO lacked a complete frame during a playback
+pub const PCM_UNDERRUN: u32 = 0x02;
+/// Status flag: a sample was pushed while the FIFO was full, and was lost
+pub const PCM_OVERRUN: u32 = 0x04;
+
+/// Number of samples the FIFO can contain
+pub const PCM_FIFO_SIZE: usize = 1024;
+
+/// Format of the played samples
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioFormat {
+    /// Number of frames per second
+    pub sample_rate: u32,
+    /// Number of channels (samples per frame)
+    pub channels: u16,
+    /// Number of bits per sample (`8` or `16`)
+    pub bits: u16,
+}
+
+impl AudioFormat {
+    /// Create a format
+    /// Returns an error message if the sample rate is not between 1 and 192 kHz, the number of channels between 1 and 8,
+    /// or if the number of bits is neither 8 nor 16.
+    pub fn new(sample_rate: u32, channels: u16, bits: u16) -> Result<Self, &'static str> {
+        if !(1..=192_000).contains(&sample_rate) {
+            return Err("Sample rate must be between 1 Hz and 192 kHz");
+        }
+
+        if !(1..=8).contains(&channels) {
+            return Err("Number of channels must be between 1 and 8");
+        }
+
+        if bits != 8 && bits != 16 {
+            return Err("Samples must be 8 or 16-bit");
+        }
+
+        Ok(Self {
+            sample_rate,
+            channels,
+            bits,
+        })
+    }
+
+    /// Decode a sample written by the guest (unsigned for 8-bit samples, signed for 16-bit ones) to a signed 16-bit sample
+    pub fn decode_sample(&self, word: u32) -> i16 {
+        if self.bits == 8 {
+            i16::from((word as u8 ^ 0x80) as i8) << 8
+        } else {
+            word as u16 as i16
+        }
+    }
+
+    /// Encode a signed 16-bit sample in the format's number of bits, as stored in WAV files
+    pub fn encode_sample(&self, sample: i16) -> Vec<u8> {
+        if self.bits == 8 {
+            vec![((sample >> 8) as u8) ^ 0x80]
+        } else {
+            sample.to_le_bytes().to_vec()
+        }
+    }
+
+    /// Get the header of a WAV file containing the provided number of bytes of samples
+    pub fn wav_header(&self, data_len: u32) -> [u8; 44] {
+        let block_align = self.channels * self.bits / 8;
+
+        let mut header = [0; 44];
+        header[0..4].copy_from_slice(b"RIFF");
+        header[4..8].copy_from_slice(&(36 + data_len).to_le_bytes());
+        header[8..12].copy_from_slice(b"WAVE");
+        header[12..16].copy_from_slice(b"fmt ");
+        header[16..20].copy_from_slice(&16u32.to_le_bytes());
+        header[20..22].copy_from_slice(&1u16.to_le_bytes());
+        header[22..24].copy_from_slice(&self.channels.to_le_bytes());
+        header[24..28].copy_from_slice(&self.sample_rate.to_le_bytes());
+        header[28..32].copy_from_slice(&(self.sample_rate * u32::from(block_align)).to_le_bytes());
+        header[32..34].copy_from_slice(&block_align.to_le_bytes());
+        header[34..36].copy_from_slice(&self.bits.to_le_bytes());
+        header[36..40].copy_from_slice(b"data");
+        header[40..44].copy_from_slice(&data_len.to_le_bytes());
+        header
+    }
+}
+
+impl Default for AudioFormat {
+    /// 44.1 kHz stereo with 16-bit samples
+    fn default() -> Self {
+        Self {
+            sample_rate: 44_100,
+            channels: 2,
+            bits: 16,
+        }
+    }
+}
+
+/// The PCM audio component plays frames of samples pushed by the guest to a FIFO of [`PCM_FIFO_SIZE`] samples.
+/// It is made of 9 words:
+///
+/// * Word 1: control flags (`PCM_PLAY` and `PCM_FIFO_INTERRUPT`)
+/// * Word 2: status flags (`PCM_PLAYING`, `PCM_UNDERRUN` and `PCM_OVERRUN`) ; writing to it clears the error flags set in the written word
+/// * Word 3: sample rate, in Hz (default: 44100)
+/// * Word 4: number of channels, between 1 and 8 (default: 2)
+/// * Word 5: number of bits per sample, 8 or 16 (default: 16)
+/// * Word 6: number of samples in the FIFO (read-only)
+/// * Word 7: FIFO (write-only), taking one sample per word with the channels interleaved
+/// * Word 8: interruption code (only the weakest byte is kept)
+/// * Word 9: prescaler, number of CPU cycles per frame (`0` behaves like `1`)
+///
+/// 16-bit samples are signed, while 8-bit ones are unsigned (like in WAV files), and are taken from the word's weakest bits.
+/// The format can only be changed while the playback is stopped, invalid values raising an unsupported operation exception.
+///
+/// During a playback, one frame (a sample for each channel) is taken from the FIFO and sent to the backend every `prescaler` CPU cycles,
+/// starting with the first cycle after the playback started. The guest should set the prescaler to the CPU's frequency
+/// divided by the sample rate, so the FIFO is played at the pace it can be refilled: by default, a frame is taken on each cycle,
+/// which is faster than the guest can push samples and makes the playback underrun.
+/// When the FIFO gets half empty and `PCM_FIFO_INTERRUPT` is set, a hardware interruption is raised so the guest can refill it.
+pub struct Pcm {
+    backend: Box<dyn AudioBackend>,
+    format: AudioFormat,
+    fifo: VecDeque<i16>,
+    control: u32,
+    errors: u32,
+    itr_code: u8,
+    prescaler: u32,
+    elapsed: u32,
+    hw_id: u64,
+}
+
+impl Pcm {
+    /// Create a PCM audio component
+    pub fn new(backend: Box<dyn AudioBackend>, hw_id: u64) -> Self {
+        Self {
+            backend,
+            format: AudioFormat::default(),
+            fifo: VecDeque::with_capacity(PCM_FIFO_SIZE),
+            control: 0,
+            errors: 0,
+            itr_code: 0,
+            prescaler: 0,
+            elapsed: 0,
+            hw_id,
+        }
+    }
+
+    /// Get the current format
+    pub fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    /// (Internal) Check if a playback is in progress
+    fn playing(&self) -> bool {
+        self.control & PCM_PLAY != 0
+    }
+
+    /// (Internal) Send the next frame of the FIFO to the backend, if there is a complete one
+    fn play_frame(&mut self) -> bool {
+        let channels = usize::from(self.format.channels);
+
+        if self.fifo.len() < channels {
+            return false;
+        }
+
+        let frame = self.fifo.drain(..channels).collect::<Vec<_>>();
+        self.backend.play(&frame);
+        true
+    }
+
+    /// (Internal) Change the format if the playback is stopped
+    fn set_format(&mut self, sample_rate: u32, channels: u32, bits: u32, ex: &mut u16) {
+        if self.playing() {
+            *ex = AuxHwException::UnsupportedOperation.into();
+            return;
+        }
+
+        let format = match (u16::try_from(channels), u16::try_from(bits)) {
+            (Ok(channels), Ok(bits)) => AudioFormat::new(sample_rate, channels, bits),
+            _ => Err("Invalid format"),
+        };
+
+        match format {
+            Ok(format) => self.format = format,
+            Err(_) => *ex = AuxHwException::UnsupportedOperation.into(),
+        }
+    }
+}
+
+impl Bus for Pcm {
+    fn name(&self) -> &'static str {
+        "PCM Audio"
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        DeviceMetadata::new(
+            self.hw_id,
+            9 * 4,
+            AudioType::Pcm.into(),
+            None,
+            Some(PCM_FIFO_SIZE as u64),
+        )
+        .encode()
+    }
+
+    fn read(&mut self, addr: u32, ex: &mut u16) -> u32 {
+        if addr == 0x18 {
+            *ex = AuxHwException::MemoryNotReadable.into();
+            return 0;
+        }
+
+        self.peek(addr).unwrap()
+    }
+
+    fn write(&mut self, addr: u32, word: u32, ex: &mut u16) {
+        let format = self.format;
+
+        match addr {
+            0x00 => {
+                let word = word & (PCM_PLAY | PCM_FIFO_INTERRUPT);
+
+                match (self.playing(), word & PCM_PLAY != 0) {
+                    (false, true) => {
+                        self.elapsed = 0;
+                        self.backend.start(format);
+                    }
+                    (true, false) => {
+                        while self.play_frame() {}
+                        self.fifo.clear();
+                        self.backend.stop();
+                    }
+                    _ => {}
+                }
+
+                self.control = word;
+            }
+            0x04 => self.errors &= !word,
+            0x08 => self.set_format(word, format.channels.into(), format.bits.into(), ex),
+            0x0C => self.set_format(format.sample_rate, word, format.bits.into(), ex),
+            0x10 => self.set_format(format.sample_rate, format.channels.into(), word, ex),
+            0x14 => *ex = AuxHwException::MemoryNotWritable.into(),
+            0x18 => {
+                if self.fifo.len() == PCM_FIFO_SIZE {
+                    self.errors |= PCM_OVERRUN;
+                } else {
+                    self.fifo.push_back(format.decode_sample(word));
+                }
+            }
+            0x1C => self.itr_code = word as u8,
+            0x20 => self.prescaler = word,
+            _ => unreachable!(),
+        }
+    }
+
+    fn reset(&mut self, _kind: ResetKind) {
+        if self.playing() {
+            self.backend.stop();
+        }
+
+        self.format = AudioFormat::default();
+        self.fifo.clear();
+        self.control = 0;
+        self.errors = 0;
+        self.itr_code = 0;
+        self.prescaler = 0;
+        self.elapsed = 0;
+    }
+
+    fn peek(&self, addr: u32) -> Option<u32> {
+        Some(match addr {
+            0x00 => self.control,
+            0x04 => {
+                if self.playing() {
+                    self.errors | PCM_PLAYING
+                } else {
+                    self.errors
+                }
+            }
+            0x08 => self.format.sample_rate,
+            0x0C => self.format.channels.into(),
+            0x10 => self.format.bits.into(),
+            0x14 => self.fifo.len() as u32,
+            0x18 => 0,
+            0x1C => self.itr_code.into(),
+            0x20 => self.prescaler,
+            _ => unreachable!(),
+        })
+    }
+
+    fn ticks(&self) -> bool {
+        true
+    }
+
+    fn tick(&mut self) -> Option<u8> {
+        if !self.playing() {
+            return None;
+        }
+
+        self.elapsed += 1;
+
+        if self.elapsed < self.prescaler.max(1) {
+            return None;
+        }
+
+        self.elapsed = 0;
+
+        let before = self.fifo.len();
+
+        if !self.play_frame() {
+            self.errors |= PCM_UNDERRUN;
+            return None;
+        }
+
+        let half = PCM_FIFO_SIZE / 2;
+
+        if self.control & PCM_FIFO_INTERRUPT != 0 && before > half && self.fifo.len() <= half {
+            Some(self.itr_code)
+        } else {
+            None
+        }
+    }
+}
@@ -1,6 +1,8 @@
 //! The basic debug component offers a simple debugging system.
 //! See [`BasicDebug`] for more details.
-use lrvm::board::Bus;
+use std::fmt;
+
+use lrvm::board::{Bus, ResetKind};
 use lrvm_tools::{
     exceptions::AuxHwException,
     metadata::{DebugType, DeviceCategory, DeviceMetadata},
@@ -58,55 +60,55 @@ impl BasicDebug {
 
     /// Create a new Basic Debug Interface (BDI) with a println!-backed debugger
     pub fn new_println(hw_id: u64) -> Self {
-        Self::new(
-            hw_id,
-            Box::new(|info| {
-                println!(
-                    "[debug:basic] {}",
-                    match info {
-                        DebugInfo::UnsignedByteHex(n) => format!("{:#004X}", n),
-                        DebugInfo::UnsignedHalfWordHex(n) => format!("{:#006X}", n),
-                        DebugInfo::UnsignedWordHex(n) => format!("{:#010X}", n),
-                        DebugInfo::SignedByteHex(n) => {
-                            if n < 0 {
-                                format!("-{:#004X}", -n)
-                            } else {
-                                format!("{:#004X}", n)
-                            }
-                        }
-                        DebugInfo::SignedHalfWordHex(n) => {
-                            if n < 0 {
-                                format!("-{:#006X}", -n)
-                            } else {
-                                format!("{:#006X}", n)
-                            }
-                        }
-                        DebugInfo::SignedWordHex(n) => {
-                            if n < 0 {
-                                format!("-{:#010X}", -n)
-                            } else {
-                                format!("{:#010X}", n)
-                            }
-                        }
-                        DebugInfo::UnsignedByteDec(n) => format!("{}", n),
-                        DebugInfo::UnsignedHalfWordDec(n) => format!("{}", n),
-                        DebugInfo::UnsignedWordDec(n) => format!("{}", n),
-                        DebugInfo::SignedByteDec(n) => format!("{}", n),
-                        DebugInfo::SignedHalfWordDec(n) => format!("{}", n),
-                        DebugInfo::SignedWordDec(n) => format!("{}", n),
-                        DebugInfo::Boolean(b) => format!("{}", b),
-                        DebugInfo::Utf8Char(c) => match c {
-                            Ok(c) => format!("{}", c),
-                            Err(code) => format!("<Invalid UTF-8 character: {:#010X}>", code),
-                        },
-                        DebugInfo::EncodingAgnosticChar(c) => {
-                            format!("<Encoding-agnostic character: {:#010X}>", c)
-                        }
-                        DebugInfo::DebugMessage => String::from("debug point"),
-                    }
-                )
-            }),
-        )
+        Self::new(hw_id, Box::new(|info| println!("[debug:basic] {}", info)))
+    }
+}
+
+impl fmt::Display for DebugInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let info = match *self {
+            Self::UnsignedByteHex(n) => format!("{:#004X}", n),
+            Self::UnsignedHalfWordHex(n) => format!("{:#006X}", n),
+            Self::UnsignedWordHex(n) => format!("{:#010X}", n),
+            Self::SignedByteHex(n) => {
+                if n < 0 {
+                    format!("-{:#004X}", -n)
+                } else {
+                    format!("{:#004X}", n)
+                }
+            }
+            Self::SignedHalfWordHex(n) => {
+                if n < 0 {
+                    format!("-{:#006X}", -n)
+                } else {
+                    format!("{:#006X}", n)
+                }
+            }
+            Self::SignedWordHex(n) => {
+                if n < 0 {
+                    format!("-{:#010X}", -n)
+                } else {
+                    format!("{:#010X}", n)
+                }
+            }
+            Self::UnsignedByteDec(n) => format!("{}", n),
+            Self::UnsignedHalfWordDec(n) => format!("{}", n),
+            Self::UnsignedWordDec(n) => format!("{}", n),
+            Self::SignedByteDec(n) => format!("{}", n),
+            Self::SignedHalfWordDec(n) => format!("{}", n),
+            Self::SignedWordDec(n) => format!("{}", n),
+            Self::Boolean(b) => format!("{}", b),
+            Self::Utf8Char(c) => match c {
+                Ok(c) => format!("{}", c),
+                Err(code) => format!("<Invalid UTF-8 character: {:#010X}>", code),
+            },
+            Self::EncodingAgnosticChar(c) => {
+                format!("<Encoding-agnostic character: {:#010X}>", c)
+            }
+            Self::DebugMessage => String::from("debug point"),
+        };
+
+        write!(f, "{}", info)
     }
 }
 
@@ -155,5 +157,5 @@ impl Bus for BasicDebug {
         (self.debugger)(info);
     }
 
-    fn reset(&mut self) {}
+    fn reset(&mut self, _kind: ResetKind) {}
 }
@@ -1,11 +1,17 @@
 //! The buffered display component offers a simple UTF-8 display system.
 //! See [`BufferedDisplay`] for more details.
 
-use std::str::{from_utf8, Utf8Error};
+use std::{
+    cell::RefCell,
+    io::Write,
+    rc::Rc,
+    str::{from_utf8, Utf8Error},
+};
 
-use lrvm::board::Bus;
+use lrvm::board::{Bus, ResetKind};
 use lrvm_tools::{
     bytes::words_to_bytes,
+    config::OutputCapture,
     exceptions::AuxHwException,
     metadata::{DeviceMetadata, DisplayType},
 };
@@ -74,6 +80,86 @@ impl BufferedDisplay {
             hw_id,
         )
     }
+
+    /// Create a buffered display component writing its messages to a capture, returned along with it.
+    /// Invalid UTF-8 strings are converted to lossy ones, and the NUL characters ending the messages are removed.
+    pub fn new_capture(capacity: u32, hw_id: u64) -> Result<(Self, OutputCapture), &'static str> {
+        let capture = OutputCapture::new();
+        let mut output = capture.clone();
+
+        let display = Self::new(
+            capacity,
+            Box::new(move |message| {
+                let message = match message {
+                    Ok(message) => message.into(),
+                    Err((_, bytes)) => String::from_utf8_lossy(bytes),
+                };
+
+                let _ = output.write_all(message.trim_end_matches('\0').as_bytes());
+            }),
+            hw_id,
+        )?;
+
+        Ok((display, capture))
+    }
+
+    /// Create a buffered display component writing its valid UTF-8 messages to a capture, returned along with it
+    /// and with a log of all the received messages. Invalid UTF-8 strings are not written to the capture ; the log
+    /// records their decoding error instead. The NUL characters ending the messages are removed.
+    pub fn new_capture_strict(
+        capacity: u32,
+        hw_id: u64,
+    ) -> Result<(Self, OutputCapture, MessageLog), &'static str> {
+        let capture = OutputCapture::new();
+        let log = MessageLog::default();
+
+        let mut output = capture.clone();
+        let messages = log.clone();
+
+        let display = Self::new(
+            capacity,
+            Box::new(move |message| {
+                let message = match message {
+                    Ok(message) => {
+                        let message = message.trim_end_matches('\0');
+                        let _ = output.write_all(message.as_bytes());
+                        Ok(message.to_string())
+                    }
+                    Err((err, bytes)) => Err((err, bytes.to_vec())),
+                };
+
+                messages.0.borrow_mut().push(message);
+            }),
+            hw_id,
+        )?;
+
+        Ok((display, capture, log))
+    }
+}
+
+/// Message received by a display created with [`BufferedDisplay::new_capture_strict`]: either the decoded string,
+/// or the decoding error along with the raw content of the buffer
+pub type LoggedMessage = Result<String, (Utf8Error, Vec<u8>)>;
+
+/// Host-side log of the messages received by a display created with [`BufferedDisplay::new_capture_strict`].
+/// Clones of a log all share the same messages.
+#[derive(Debug, Clone, Default)]
+pub struct MessageLog(Rc<RefCell<Vec<LoggedMessage>>>);
+
+impl MessageLog {
+    /// Get the messages received so far, in order
+    pub fn messages(&self) -> Vec<LoggedMessage> {
+        self.0.borrow().clone()
+    }
+
+    /// Get the decoding errors of the invalid UTF-8 messages received so far, in order
+    pub fn errors(&self) -> Vec<Utf8Error> {
+        self.0
+            .borrow()
+            .iter()
+            .filter_map(|message| message.as_ref().err().map(|(err, _)| *err))
+            .collect()
+    }
 }
 
 impl Bus for BufferedDisplay {
@@ -116,13 +202,27 @@ impl Bus for BufferedDisplay {
                 (self.handler)(Ok(&String::from_utf8_lossy(&bytes)))
             }
 
-            0xFF => self.reset(),
+            0xFF => self.buffer.fill(0),
 
             code => *ex = AuxHwException::UnknownOperation(code as u8).into(),
         }
     }
 
-    fn reset(&mut self) {
+    fn reset(&mut self, _kind: ResetKind) {
         self.buffer = vec![0; self.buffer.len()];
     }
+
+    fn peek(&self, addr: u32) -> Option<u32> {
+        self.buffer.get(addr as usize / 4).copied()
+    }
+
+    fn poke(&mut self, addr: u32, word: u32) -> bool {
+        match self.buffer.get_mut(addr as usize / 4) {
+            Some(stored) => {
+                *stored = word;
+                true
+            }
+            None => false,
+        }
+    }
 }
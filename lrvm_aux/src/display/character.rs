@@ -3,8 +3,9 @@
 
 use std::io::{stdout, Write};
 
-use lrvm::board::Bus;
+use lrvm::board::{Bus, ResetKind};
 use lrvm_tools::{
+    config::OutputCapture,
     exceptions::AuxHwException,
     metadata::{DeviceMetadata, DisplayType},
 };
@@ -33,6 +34,23 @@ impl CharDisplay {
             hw_id,
         )
     }
+
+    /// Create a character display component writing its characters to a capture, returned along with it.
+    /// Invalid UTF-8 characters are written as '�'.
+    pub fn new_capture(hw_id: u64) -> (Self, OutputCapture) {
+        let capture = OutputCapture::new();
+        let mut output = capture.clone();
+
+        let display = Self::new(
+            Box::new(move |result| {
+                let mut buf = [0; 4];
+                let _ = output.write_all(result.unwrap_or('�').encode_utf8(&mut buf).as_bytes());
+            }),
+            hw_id,
+        );
+
+        (display, capture)
+    }
 }
 
 impl Bus for CharDisplay {
@@ -53,5 +71,5 @@ impl Bus for CharDisplay {
         (self.handler)(std::char::from_u32(word).ok_or(word))
     }
 
-    fn reset(&mut self) {}
+    fn reset(&mut self, _kind: ResetKind) {}
 }
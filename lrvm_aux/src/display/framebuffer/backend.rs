@@ -0,0 +1,181 @@
+use std::{
+    cell::RefCell,
+    fs,
+    io::{stdout, Write},
+    path::PathBuf,
+    rc::Rc,
+};
+
+use super::Frame;
+
+/// Host side of a [`Framebuffer`](super::Framebuffer), showing or storing the presented frames
+pub trait FramebufferBackend {
+    /// Handle a frame presented by the guest
+    fn present(&mut self, frame: &Frame);
+}
+
+/// In-memory backend keeping all presented frames, shared between the framebuffer and the code inspecting them (e.g. tests).
+/// Clones of a capture all share the same frames.
+#[derive(Debug, Clone, Default)]
+pub struct CaptureBackend(Rc<RefCell<Vec<Frame>>>);
+
+impl CaptureBackend {
+    /// Create an empty capture
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the number of captured frames
+    pub fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    /// Check if no frame was captured yet
+    pub fn is_empty(&self) -> bool {
+        self.0.borrow().is_empty()
+    }
+
+    /// Get all captured frames
+    pub fn frames(&self) -> Vec<Frame> {
+        self.0.borrow().clone()
+    }
+
+    /// Get the last captured frame
+    pub fn last_frame(&self) -> Option<Frame> {
+        self.0.borrow().last().cloned()
+    }
+
+    /// Get all captured frames and clear them
+    pub fn take_frames(&self) -> Vec<Frame> {
+        std::mem::take(&mut self.0.borrow_mut())
+    }
+}
+
+impl FramebufferBackend for CaptureBackend {
+    fn present(&mut self, frame: &Frame) {
+        self.0.borrow_mut().push(frame.clone());
+    }
+}
+
+/// Image format written by an [`ImageFileBackend`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// Binary PPM (no alpha channel)
+    Ppm,
+    /// Uncompressed RGBA PNG
+    Png,
+}
+
+impl ImageFormat {
+    /// Guess the image format from a path's extension
+    pub fn from_extension(path: &str) -> Option<Self> {
+        let (_, ext) = path.rsplit_once('.')?;
+
+        match ext.to_ascii_lowercase().as_str() {
+            "ppm" => Some(Self::Ppm),
+            "png" => Some(Self::Png),
+            _ => None,
+        }
+    }
+}
+
+/// Headless backend writing each presented frame to an image file.
+///
+/// The first `{}` in the path pattern is replaced by the frame's number (starting at `0`) ;
+/// if there is none, the same file is overwritten by each frame.
+/// Write failures are reported on the standard error output, and do not stop the VM.
+pub struct ImageFileBackend {
+    pattern: String,
+    format: ImageFormat,
+    count: u64,
+}
+
+impl ImageFileBackend {
+    /// Create a backend writing frames to files matching the provided pattern
+    pub fn new(pattern: impl Into<String>, format: ImageFormat) -> Self {
+        Self {
+            pattern: pattern.into(),
+            format,
+            count: 0,
+        }
+    }
+
+    /// Get the path of a frame's file
+    pub fn frame_path(&self, number: u64) -> PathBuf {
+        PathBuf::from(self.pattern.replacen("{}", &number.to_string(), 1))
+    }
+}
+
+impl FramebufferBackend for ImageFileBackend {
+    fn present(&mut self, frame: &Frame) {
+        let path = self.frame_path(self.count);
+        self.count += 1;
+
+        let image = match self.format {
+            ImageFormat::Ppm => frame.to_ppm(),
+            ImageFormat::Png => frame.to_png(),
+        };
+
+        if let Err(err) = fs::write(&path, image) {
+            eprintln!(
+                "Warning: failed to write frame to '{}': {}",
+                path.display(),
+                err
+            );
+        }
+    }
+}
+
+/// Backend drawing frames in a truecolor terminal, using half-block characters so each character cell shows two pixels.
+/// Each frame is drawn from the top-left corner of the terminal.
+pub struct TerminalBackend {
+    output: Box<dyn Write>,
+}
+
+impl TerminalBackend {
+    /// Create a backend drawing to the standard output
+    pub fn new() -> Self {
+        Self::with_output(Box::new(stdout()))
+    }
+
+    /// Create a backend drawing to the provided output
+    pub fn with_output(output: Box<dyn Write>) -> Self {
+        Self { output }
+    }
+}
+
+impl Default for TerminalBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FramebufferBackend for TerminalBackend {
+    fn present(&mut self, frame: &Frame) {
+        let mut out = String::from("\x1b[H");
+
+        for y in (0..frame.height()).step_by(2) {
+            for x in 0..frame.width() {
+                let [tr, tg, tb, _] = frame.pixel(x, y);
+
+                // The bottom half of the last row is left black for frames with an odd height
+                let [br, bg, bb, _] = if y + 1 < frame.height() {
+                    frame.pixel(x, y + 1)
+                } else {
+                    [0; 4]
+                };
+
+                out.push_str(&format!(
+                    "\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m▀",
+                    tr, tg, tb, br, bg, bb
+                ));
+            }
+
+            out.push_str("\x1b[0m\r\n");
+        }
+
+        // Ignore failures so the VM keeps running
+        let _ = self.output.write_all(out.as_bytes());
+        let _ = self.output.flush();
+    }
+}
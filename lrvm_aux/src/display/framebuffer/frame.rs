@@ -0,0 +1,158 @@
+/// A frame presented by a [`Framebuffer`](super::Framebuffer), converted to 8-bit RGBA pixels
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+}
+
+impl Frame {
+    /// Create a frame from its RGBA pixels, row by row (4 bytes per pixel)
+    /// Returns an error message if the number of bytes does not match the dimensions.
+    pub fn new(width: u32, height: u32, rgba: Vec<u8>) -> Result<Self, &'static str> {
+        if rgba.len() as u64 != u64::from(width) * u64::from(height) * 4 {
+            return Err("Frame's pixels do not match its dimensions");
+        }
+
+        Ok(Self {
+            width,
+            height,
+            rgba,
+        })
+    }
+
+    /// Get the frame's width, in pixels
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Get the frame's height, in pixels
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Get the frame's RGBA pixels, row by row (4 bytes per pixel)
+    pub fn rgba(&self) -> &[u8] {
+        &self.rgba
+    }
+
+    /// Get a pixel's RGBA components (panics if the coordinates are out of the frame)
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        assert!(
+            x < self.width && y < self.height,
+            "Pixel is out of the frame"
+        );
+
+        let offset = ((y * self.width + x) * 4) as usize;
+        [
+            self.rgba[offset],
+            self.rgba[offset + 1],
+            self.rgba[offset + 2],
+            self.rgba[offset + 3],
+        ]
+    }
+
+    /// Encode the frame as a binary PPM (P6) image (the alpha channel is dropped)
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut ppm = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        ppm.reserve(self.rgba.len() / 4 * 3);
+
+        for pixel in self.rgba.chunks_exact(4) {
+            ppm.extend_from_slice(&pixel[..3]);
+        }
+
+        ppm
+    }
+
+    /// Encode the frame as an RGBA PNG image
+    /// The image data is stored without compression.
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut png = vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&self.width.to_be_bytes());
+        header.extend_from_slice(&self.height.to_be_bytes());
+        // 8 bits per channel, RGBA, default compression, filtering and no interlacing
+        header.extend_from_slice(&[8, 6, 0, 0, 0]);
+        png_chunk(&mut png, b"IHDR", &header);
+
+        // Each row is prefixed by its filter type (none)
+        let row_len = self.width as usize * 4;
+        let mut raw = Vec::with_capacity(self.rgba.len() + self.height as usize);
+
+        for row in self.rgba.chunks_exact(row_len.max(1)) {
+            raw.push(0);
+            raw.extend_from_slice(row);
+        }
+
+        png_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+        png_chunk(&mut png, b"IEND", &[]);
+
+        png
+    }
+}
+
+/// (Internal) Append a chunk to a PNG image
+fn png_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// (Internal) Wrap data in a zlib stream made of uncompressed deflate blocks
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut zlib = vec![0x78, 0x01];
+    let mut blocks = data.chunks(0xFFFF).peekable();
+
+    if blocks.peek().is_none() {
+        zlib.extend_from_slice(&[0x01, 0x00, 0x00, 0xFF, 0xFF]);
+    }
+
+    while let Some(block) = blocks.next() {
+        let len = block.len() as u16;
+
+        zlib.push(if blocks.peek().is_none() { 0x01 } else { 0x00 });
+        zlib.extend_from_slice(&len.to_le_bytes());
+        zlib.extend_from_slice(&(!len).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+
+    zlib.extend_from_slice(&adler32(data).to_be_bytes());
+    zlib
+}
+
+/// (Internal) Compute the CRC-32 checksum used by PNG chunks
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFF_u32;
+
+    for byte in data {
+        crc ^= u32::from(*byte);
+
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                0xEDB8_8320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
+
+/// (Internal) Compute the Adler-32 checksum used by zlib streams
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1_u32, 0_u32);
+
+    for byte in data {
+        a = (a + u32::from(*byte)) % 65521;
+        b = (b + a) % 65521;
+    }
+
+    (b << 16) | a
+}
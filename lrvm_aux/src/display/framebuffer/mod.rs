@@ -0,0 +1,264 @@
+//! The framebuffer component offers a pixel display, with host backends to show or store the presented frames.
+//! See [`Framebuffer`] for more details.
+
+mod backend;
+mod frame;
+
+pub use self::{backend::*, frame::Frame};
+
+use lrvm::board::{Bus, ResetKind};
+use lrvm_tools::{
+    exceptions::AuxHwException,
+    metadata::{DeviceMetadata, DisplayType},
+};
+
+/// Action: present the drawing buffer
+pub const FB_PRESENT: u32 = 0x01;
+/// Action: present the drawing buffer, then draw in the other buffer (double buffering)
+pub const FB_FLIP: u32 = 0x02;
+/// Action: clear the drawing buffer
+pub const FB_CLEAR: u32 = 0xFF;
+
+/// Size of the framebuffer's header, in bytes
+pub const FB_HEADER_SIZE: u32 = 0x20;
+
+/// Format of the framebuffer's pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// 16-bit pixels (5 bits of red, 6 of green, 5 of blue), two per word with the first one in the strongest half
+    Rgb565 = 0,
+    /// 32-bit pixels, one per word (`0xRRGGBBAA`)
+    Rgba8888 = 1,
+}
+
+impl PixelFormat {
+    /// Get the format's name
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Rgb565 => "rgb565",
+            Self::Rgba8888 => "rgba8888",
+        }
+    }
+
+    /// Get a format from its name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "rgb565" => Some(Self::Rgb565),
+            "rgba8888" => Some(Self::Rgba8888),
+            _ => None,
+        }
+    }
+
+    /// (Internal) Number of words needed to store the provided number of pixels
+    fn words(self, pixels: u64) -> u64 {
+        match self {
+            Self::Rgb565 => pixels.div_ceil(2),
+            Self::Rgba8888 => pixels,
+        }
+    }
+}
+
+/// The framebuffer is a pixel display made of a header followed by the pixels of its drawing buffer, row by row.
+///
+/// The header is 8-word-long:
+///
+/// * Word 1: action (write-only), `FB_PRESENT` to present the drawing buffer, `FB_FLIP` to present it and then draw in the other buffer,
+///   `FB_CLEAR` to clear the drawing buffer
+/// * Word 2: width, in pixels (read-only)
+/// * Word 3: height, in pixels (read-only)
+/// * Word 4: pixel format, `0` for RGB565 or `1` for RGBA8888 (read-only)
+/// * Word 5: number of presented frames (read-only)
+/// * Words 6 to 8: reserved (read-only)
+///
+/// The framebuffer has two buffers: after a flip, the guest draws in the buffer which was presented before it.
+/// Presented frames are converted to RGBA pixels and sent to a [`FramebufferBackend`].
+///
+/// The metadata's additional data contains the width (strongest 32 bits), the height (next 16 bits) and the pixel format (weakest 16 bits).
+pub struct Framebuffer {
+    width: u32,
+    height: u32,
+    format: PixelFormat,
+    buffers: [Vec<u32>; 2],
+    drawing: usize,
+    presented: u32,
+    backend: Box<dyn FramebufferBackend>,
+    hw_id: u64,
+}
+
+impl Framebuffer {
+    /// Create a framebuffer
+    /// Returns an error message if a dimension is 0 or larger than 65535 pixels, or if the framebuffer does not fit in the address space.
+    pub fn new(
+        width: u32,
+        height: u32,
+        format: PixelFormat,
+        backend: Box<dyn FramebufferBackend>,
+        hw_id: u64,
+    ) -> Result<Self, &'static str> {
+        if width == 0 || height == 0 {
+            return Err("Framebuffer's dimensions cannot be 0");
+        }
+
+        if width > 0xFFFF || height > 0xFFFF {
+            return Err("Framebuffer's dimensions cannot exceed 65535 pixels");
+        }
+
+        let words = format.words(u64::from(width) * u64::from(height));
+
+        if u64::from(FB_HEADER_SIZE) + words * 4 > u64::from(u32::MAX) {
+            return Err("Framebuffer is too large for the address space");
+        }
+
+        let buffer = vec![0; words as usize];
+
+        Ok(Self {
+            width,
+            height,
+            format,
+            buffers: [buffer.clone(), buffer],
+            drawing: 0,
+            presented: 0,
+            backend,
+            hw_id,
+        })
+    }
+
+    /// Get the framebuffer's width, in pixels
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Get the framebuffer's height, in pixels
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Get the framebuffer's pixel format
+    pub fn format(&self) -> PixelFormat {
+        self.format
+    }
+
+    /// Get the drawing buffer's content as a frame
+    pub fn frame(&self) -> Frame {
+        let buffer = &self.buffers[self.drawing];
+        let pixels = (self.width * self.height) as usize;
+        let mut rgba = Vec::with_capacity(pixels * 4);
+
+        for i in 0..pixels {
+            let pixel = match self.format {
+                PixelFormat::Rgba8888 => buffer[i].to_be_bytes(),
+                PixelFormat::Rgb565 => {
+                    let word = buffer[i / 2];
+                    let pixel = if i % 2 == 0 {
+                        word >> 16
+                    } else {
+                        word & 0xFFFF
+                    };
+
+                    let (r, g, b) = ((pixel >> 11) & 0x1F, (pixel >> 5) & 0x3F, pixel & 0x1F);
+
+                    [
+                        ((r << 3) | (r >> 2)) as u8,
+                        ((g << 2) | (g >> 4)) as u8,
+                        ((b << 3) | (b >> 2)) as u8,
+                        0xFF,
+                    ]
+                }
+            };
+
+            rgba.extend_from_slice(&pixel);
+        }
+
+        Frame::new(self.width, self.height, rgba).unwrap()
+    }
+
+    /// (Internal) Present the drawing buffer
+    fn present(&mut self) {
+        let frame = self.frame();
+        self.backend.present(&frame);
+        self.presented = self.presented.wrapping_add(1);
+    }
+}
+
+impl Bus for Framebuffer {
+    fn name(&self) -> &'static str {
+        "Framebuffer"
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        DeviceMetadata::new(
+            self.hw_id,
+            FB_HEADER_SIZE + self.buffers[0].len() as u32 * 4,
+            DisplayType::Framebuffer.into(),
+            None,
+            Some(
+                (u64::from(self.width) << 32) | (u64::from(self.height) << 16) | self.format as u64,
+            ),
+        )
+        .encode()
+    }
+
+    fn read(&mut self, addr: u32, ex: &mut u16) -> u32 {
+        if addr == 0x00 {
+            *ex = AuxHwException::MemoryNotReadable.into();
+            return 0;
+        }
+
+        self.peek(addr).unwrap()
+    }
+
+    fn write(&mut self, addr: u32, word: u32, ex: &mut u16) {
+        if addr >= FB_HEADER_SIZE {
+            self.buffers[self.drawing][((addr - FB_HEADER_SIZE) / 4) as usize] = word;
+            return;
+        }
+
+        if addr != 0x00 {
+            *ex = AuxHwException::MemoryNotWritable.into();
+            return;
+        }
+
+        match word {
+            FB_PRESENT => self.present(),
+
+            FB_FLIP => {
+                self.present();
+                self.drawing ^= 1;
+            }
+
+            FB_CLEAR => self.buffers[self.drawing].fill(0),
+
+            code => *ex = AuxHwException::UnknownOperation(code as u8).into(),
+        }
+    }
+
+    fn reset(&mut self, _kind: ResetKind) {
+        for buffer in &mut self.buffers {
+            buffer.fill(0);
+        }
+
+        self.drawing = 0;
+        self.presented = 0;
+    }
+
+    fn peek(&self, addr: u32) -> Option<u32> {
+        Some(match addr {
+            0x00 => 0,
+            0x04 => self.width,
+            0x08 => self.height,
+            0x0C => self.format as u32,
+            0x10 => self.presented,
+            0x14..=0x1C => 0,
+            _ => self.buffers[self.drawing][((addr - FB_HEADER_SIZE) / 4) as usize],
+        })
+    }
+
+    fn poke(&mut self, addr: u32, word: u32) -> bool {
+        if addr < FB_HEADER_SIZE {
+            return false;
+        }
+
+        self.buffers[self.drawing][((addr - FB_HEADER_SIZE) / 4) as usize] = word;
+        true
+    }
+}
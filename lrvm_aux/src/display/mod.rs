@@ -1,9 +1,13 @@
 mod buffered;
 mod character;
+mod framebuffer;
 mod number;
+mod text;
 
 pub use self::{
-    buffered::BufferedDisplay,
+    buffered::{BufferedDisplay, LoggedMessage, MessageLog},
     character::CharDisplay,
+    framebuffer::*,
     number::{NumberDisplay, NumberDisplayFormat},
+    text::*,
 };
@@ -3,8 +3,9 @@
 
 use std::io::{stdout, Write};
 
-use lrvm::board::Bus;
+use lrvm::board::{Bus, ResetKind};
 use lrvm_tools::{
+    config::OutputCapture,
     exceptions::AuxHwException,
     metadata::{DeviceMetadata, DisplayType},
 };
@@ -36,6 +37,18 @@ pub struct NumberDisplay {
     handler: Box<dyn FnMut(u32, NumberDisplayFormat, bool)>,
 }
 
+impl NumberDisplayFormat {
+    /// Format a number
+    pub fn format(self, num: u32) -> String {
+        match self {
+            Self::Hex => format!("{:#X}", num),
+            Self::HexLong => format!("{:#010X}", num),
+            Self::Dec => format!("{}", num),
+            Self::DecLong => format!("{:#010}", num),
+        }
+    }
+}
+
 impl NumberDisplay {
     /// Create a number display.  
     /// The handler can is supposed to display the provided number, but this is not required.  
@@ -48,12 +61,7 @@ impl NumberDisplay {
     pub fn new_print(hw_id: u64) -> Self {
         Self::new(
             Box::new(|num, format, newline| {
-                match format {
-                    NumberDisplayFormat::Hex => print!("{:#X}", num),
-                    NumberDisplayFormat::HexLong => print!("{:#010X}", num),
-                    NumberDisplayFormat::Dec => print!("{}", num),
-                    NumberDisplayFormat::DecLong => print!("{:#010}", num),
-                }
+                print!("{}", format.format(num));
 
                 if newline {
                     println!();
@@ -64,6 +72,25 @@ impl NumberDisplay {
             hw_id,
         )
     }
+
+    /// Create a number display writing the formatted numbers to a capture, returned along with it
+    pub fn new_capture(hw_id: u64) -> (Self, OutputCapture) {
+        let capture = OutputCapture::new();
+        let mut output = capture.clone();
+
+        let display = Self::new(
+            Box::new(move |num, format, newline| {
+                let _ = write!(output, "{}", format.format(num));
+
+                if newline {
+                    let _ = writeln!(output);
+                }
+            }),
+            hw_id,
+        );
+
+        (display, capture)
+    }
 }
 
 impl Bus for NumberDisplay {
@@ -94,5 +121,5 @@ impl Bus for NumberDisplay {
         );
     }
 
-    fn reset(&mut self) {}
+    fn reset(&mut self, _kind: ResetKind) {}
 }
@@ -0,0 +1,154 @@
+use std::{
+    cell::RefCell,
+    io::{stdout, Write},
+    rc::Rc,
+};
+
+use super::{
+    TextCell, TextScreen, TEXT_BOLD, TEXT_CUSTOM_BG, TEXT_CUSTOM_FG, TEXT_REVERSE, TEXT_UNDERLINE,
+};
+
+/// Host side of a [`TextDisplay`](super::TextDisplay), showing or storing its screen
+pub trait TextBackend {
+    /// Handle a refresh of the screen
+    fn render(&mut self, screen: &TextScreen);
+}
+
+/// In-memory backend keeping the last rendered screen, shared between the display and the code inspecting it (e.g. tests).
+/// Clones of a snapshot all share the same screen.
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotBackend(Rc<RefCell<Option<TextScreen>>>);
+
+impl SnapshotBackend {
+    /// Create an empty snapshot
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the last rendered screen
+    pub fn screen(&self) -> Option<TextScreen> {
+        self.0.borrow().clone()
+    }
+
+    /// Get the last rendered screen as plain text (see [`TextScreen::to_plain_text`])
+    pub fn text(&self) -> Option<String> {
+        self.0.borrow().as_ref().map(TextScreen::to_plain_text)
+    }
+}
+
+impl TextBackend for SnapshotBackend {
+    fn render(&mut self, screen: &TextScreen) {
+        *self.0.borrow_mut() = Some(screen.clone());
+    }
+}
+
+/// Backend drawing the screen in an ANSI terminal, from its top-left corner.
+/// Only the cells which changed since the previous refresh are redrawn.
+/// Control characters (including `ESC`) are drawn as `�`, so the guest can't send its own sequences to the terminal.
+pub struct AnsiTerminalBackend {
+    output: Box<dyn Write>,
+    previous: Option<TextScreen>,
+}
+
+impl AnsiTerminalBackend {
+    /// Create a backend drawing to the standard output
+    pub fn new() -> Self {
+        Self::with_output(Box::new(stdout()))
+    }
+
+    /// Create a backend drawing to the provided output
+    pub fn with_output(output: Box<dyn Write>) -> Self {
+        Self {
+            output,
+            previous: None,
+        }
+    }
+}
+
+impl Default for AnsiTerminalBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TextBackend for AnsiTerminalBackend {
+    fn render(&mut self, screen: &TextScreen) {
+        // Redraw everything if the screen was never drawn
+        let previous = self.previous.as_ref().filter(|previous| {
+            previous.columns() == screen.columns() && previous.rows() == screen.rows()
+        });
+
+        let mut out = String::new();
+
+        if previous.is_none() {
+            out.push_str("\x1b[0m\x1b[2J");
+        }
+
+        // Position right after the last drawn cell, to avoid moving the cursor for consecutive cells
+        let mut position = None;
+
+        for row in 0..screen.rows() {
+            for column in 0..screen.columns() {
+                let cell = screen.cell(column, row);
+
+                if previous.is_some_and(|previous| previous.cell(column, row) == cell) {
+                    continue;
+                }
+
+                if position != Some((column, row)) {
+                    out.push_str(&format!("\x1b[{};{}H", row + 1, column + 1));
+                }
+
+                out.push_str(&sgr(cell));
+                out.push(if cell.ch.is_control() { '�' } else { cell.ch });
+
+                position = Some((column + 1, row));
+            }
+        }
+
+        out.push_str("\x1b[0m");
+
+        match screen.cursor() {
+            Some((column, row)) => {
+                out.push_str(&format!("\x1b[{};{}H\x1b[?25h", row + 1, column + 1))
+            }
+            None => out.push_str("\x1b[?25l"),
+        }
+
+        // Ignore failures so the VM keeps running
+        let _ = self.output.write_all(out.as_bytes());
+        let _ = self.output.flush();
+
+        self.previous = Some(screen.clone());
+    }
+}
+
+/// (Internal) Get the escape sequence setting a cell's colors and attributes
+fn sgr(cell: TextCell) -> String {
+    let mut codes = vec!["0".to_string()];
+
+    for (attr, code) in [(TEXT_BOLD, "1"), (TEXT_UNDERLINE, "4"), (TEXT_REVERSE, "7")] {
+        if cell.attrs & attr != 0 {
+            codes.push(code.to_string());
+        }
+    }
+
+    if cell.attrs & TEXT_CUSTOM_FG != 0 {
+        codes.push(color_code(cell.fg, 30, 90));
+    }
+
+    if cell.attrs & TEXT_CUSTOM_BG != 0 {
+        codes.push(color_code(cell.bg, 40, 100));
+    }
+
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+/// (Internal) Get the SGR code of a color of the 16-color palette
+fn color_code(color: u8, normal: u8, bright: u8) -> String {
+    if color < 8 {
+        (normal + color).to_string()
+    } else {
+        (bright + color - 8).to_string()
+    }
+}
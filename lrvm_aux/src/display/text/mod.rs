@@ -0,0 +1,210 @@
+//! The text display component offers a cell-based terminal display, with host backends to show or store its screen.
+//! See [`TextDisplay`] for more details.
+
+mod backend;
+mod screen;
+
+pub use self::{backend::*, screen::*};
+
+use lrvm::board::{Bus, ResetKind};
+use lrvm_tools::{
+    exceptions::AuxHwException,
+    metadata::{DeviceMetadata, DisplayType},
+};
+
+/// Action: render the screen
+pub const TEXT_REFRESH: u32 = 0x01;
+/// Action: scroll the screen up by one row, clearing its new last row
+pub const TEXT_SCROLL: u32 = 0x02;
+/// Action: clear all cells
+pub const TEXT_CLEAR: u32 = 0xFF;
+
+/// Cursor flag: the cursor is visible
+pub const TEXT_CURSOR_VISIBLE: u32 = 0x01;
+
+/// Size of the text display's header, in bytes
+pub const TEXT_HEADER_SIZE: u32 = 0x20;
+
+/// The text display is a grid of character cells made of a header followed by the cells, row by row (see [`TextCell`] for their encoding).
+///
+/// The header is 8-word-long:
+///
+/// * Word 1: action (write-only), `TEXT_REFRESH` to render the screen, `TEXT_SCROLL` to scroll it up by one row, `TEXT_CLEAR` to clear it
+/// * Word 2: number of columns (read-only)
+/// * Word 3: number of rows (read-only)
+/// * Word 4: cursor's column
+/// * Word 5: cursor's row
+/// * Word 6: cursor flags (`TEXT_CURSOR_VISIBLE`)
+/// * Word 7: scroll offset, the row of cells shown at the top of the screen (the following ones wrap around)
+/// * Word 8: reserved (read-only)
+///
+/// Scrolling only increments the scroll offset and clears one row, so guests can scroll without moving all the cells.
+/// The screen is only sent to its [`TextBackend`] when refreshed.
+///
+/// The metadata's additional data contains the number of columns (strongest 32 bits) and rows (weakest 32 bits).
+pub struct TextDisplay {
+    columns: u32,
+    rows: u32,
+    cells: Vec<u32>,
+    cursor: (u32, u32),
+    cursor_flags: u32,
+    scroll: u32,
+    backend: Box<dyn TextBackend>,
+    hw_id: u64,
+}
+
+impl TextDisplay {
+    /// Create a text display
+    /// Returns an error message if a dimension is 0 or larger than 65535 cells, or if the display does not fit in the address space.
+    pub fn new(
+        columns: u32,
+        rows: u32,
+        backend: Box<dyn TextBackend>,
+        hw_id: u64,
+    ) -> Result<Self, &'static str> {
+        if columns == 0 || rows == 0 {
+            return Err("Text display's dimensions cannot be 0");
+        }
+
+        if columns > 0xFFFF || rows > 0xFFFF {
+            return Err("Text display's dimensions cannot exceed 65535 cells");
+        }
+
+        let cells = u64::from(columns) * u64::from(rows);
+
+        if u64::from(TEXT_HEADER_SIZE) + cells * 4 > u64::from(u32::MAX) {
+            return Err("Text display is too large for the address space");
+        }
+
+        Ok(Self {
+            columns,
+            rows,
+            cells: vec![0; cells as usize],
+            cursor: (0, 0),
+            cursor_flags: 0,
+            scroll: 0,
+            backend,
+            hw_id,
+        })
+    }
+
+    /// Get the number of columns
+    pub fn columns(&self) -> u32 {
+        self.columns
+    }
+
+    /// Get the number of rows
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    /// Get the screen as it would be rendered
+    pub fn screen(&self) -> TextScreen {
+        let mut cells = Vec::with_capacity(self.cells.len());
+
+        for row in 0..self.rows {
+            let start = (((self.scroll + row) % self.rows) * self.columns) as usize;
+
+            cells.extend(
+                self.cells[start..start + self.columns as usize]
+                    .iter()
+                    .map(|word| TextCell::decode(*word)),
+            );
+        }
+
+        let (column, row) = self.cursor;
+        let cursor = if self.cursor_flags & TEXT_CURSOR_VISIBLE != 0
+            && column < self.columns
+            && row < self.rows
+        {
+            Some(self.cursor)
+        } else {
+            None
+        };
+
+        TextScreen::new(self.columns, self.rows, cells, cursor).unwrap()
+    }
+
+    /// (Internal) Scroll the screen up by one row
+    fn scroll(&mut self) {
+        // The top row becomes the last one
+        let start = (self.scroll * self.columns) as usize;
+        self.cells[start..start + self.columns as usize].fill(0);
+        self.scroll = (self.scroll + 1) % self.rows;
+    }
+}
+
+impl Bus for TextDisplay {
+    fn name(&self) -> &'static str {
+        "Text Display"
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        DeviceMetadata::new(
+            self.hw_id,
+            TEXT_HEADER_SIZE + self.cells.len() as u32 * 4,
+            DisplayType::Text.into(),
+            None,
+            Some((u64::from(self.columns) << 32) | u64::from(self.rows)),
+        )
+        .encode()
+    }
+
+    fn read(&mut self, addr: u32, ex: &mut u16) -> u32 {
+        if addr == 0x00 {
+            *ex = AuxHwException::MemoryNotReadable.into();
+            return 0;
+        }
+
+        self.peek(addr).unwrap()
+    }
+
+    fn write(&mut self, addr: u32, word: u32, ex: &mut u16) {
+        match addr {
+            0x00 => match word {
+                TEXT_REFRESH => {
+                    let screen = self.screen();
+                    self.backend.render(&screen);
+                }
+                TEXT_SCROLL => self.scroll(),
+                TEXT_CLEAR => self.cells.fill(0),
+                code => *ex = AuxHwException::UnknownOperation(code as u8).into(),
+            },
+            0x0C => self.cursor.0 = word,
+            0x10 => self.cursor.1 = word,
+            0x14 => self.cursor_flags = word & TEXT_CURSOR_VISIBLE,
+            0x18 => self.scroll = word % self.rows,
+            0x04 | 0x08 | 0x1C => *ex = AuxHwException::MemoryNotWritable.into(),
+            _ => self.cells[((addr - TEXT_HEADER_SIZE) / 4) as usize] = word,
+        }
+    }
+
+    fn reset(&mut self, _kind: ResetKind) {
+        self.cells.fill(0);
+        self.cursor = (0, 0);
+        self.cursor_flags = 0;
+        self.scroll = 0;
+    }
+
+    fn peek(&self, addr: u32) -> Option<u32> {
+        Some(match addr {
+            0x00 | 0x1C => 0,
+            0x04 => self.columns,
+            0x08 => self.rows,
+            0x0C => self.cursor.0,
+            0x10 => self.cursor.1,
+            0x14 => self.cursor_flags,
+            0x18 => self.scroll,
+            _ => self.cells[((addr - TEXT_HEADER_SIZE) / 4) as usize],
+        })
+    }
+
+    fn poke(&mut self, addr: u32, word: u32) -> bool {
+        if addr < TEXT_HEADER_SIZE {
+            return false;
+        }
+
+        self.cells[((addr - TEXT_HEADER_SIZE) / 4) as usize] = word;
+        true
+    }
+}
@@ -0,0 +1,137 @@
+/// Attribute: bold (or bright) text
+pub const TEXT_BOLD: u8 = 0x01;
+/// Attribute: underlined text
+pub const TEXT_UNDERLINE: u8 = 0x02;
+/// Attribute: swap the foreground and background colors
+pub const TEXT_REVERSE: u8 = 0x04;
+/// Attribute: use the cell's foreground color (otherwise the terminal's default one is used)
+pub const TEXT_CUSTOM_FG: u8 = 0x10;
+/// Attribute: use the cell's background color (otherwise the terminal's default one is used)
+pub const TEXT_CUSTOM_BG: u8 = 0x20;
+
+/// A cell of a [`TextDisplay`](super::TextDisplay), encoded in a single word:
+///
+/// * Bits 0 to 15: character (code point of the Basic Multilingual Plane, `0` is shown as a space)
+/// * Bits 16 to 19: foreground color (ANSI 16-color palette, `0` to `7` for normal colors and `8` to `15` for bright ones)
+/// * Bits 20 to 23: background color
+/// * Bits 24 to 31: attributes (`TEXT_BOLD`, `TEXT_UNDERLINE`, `TEXT_REVERSE`, `TEXT_CUSTOM_FG` and `TEXT_CUSTOM_BG`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TextCell {
+    pub ch: char,
+    pub fg: u8,
+    pub bg: u8,
+    pub attrs: u8,
+}
+
+impl TextCell {
+    /// Decode a cell (invalid characters are decoded as '�')
+    pub fn decode(word: u32) -> Self {
+        let ch = match word & 0xFFFF {
+            0 => ' ',
+            code => char::from_u32(code).unwrap_or('�'),
+        };
+
+        Self {
+            ch,
+            fg: ((word >> 16) & 0xF) as u8,
+            bg: ((word >> 20) & 0xF) as u8,
+            attrs: (word >> 24) as u8,
+        }
+    }
+
+    /// Encode the cell (characters outside of the Basic Multilingual Plane are encoded as '�')
+    pub fn encode(&self) -> u32 {
+        let ch = if (self.ch as u32) > 0xFFFF {
+            '�'
+        } else {
+            self.ch
+        };
+
+        (ch as u32)
+            | (u32::from(self.fg & 0xF) << 16)
+            | (u32::from(self.bg & 0xF) << 20)
+            | (u32::from(self.attrs) << 24)
+    }
+}
+
+/// Content of a [`TextDisplay`](super::TextDisplay)'s screen when it was refreshed, with its rows in display order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextScreen {
+    columns: u32,
+    rows: u32,
+    cells: Vec<TextCell>,
+    cursor: Option<(u32, u32)>,
+}
+
+impl TextScreen {
+    /// Create a screen from its cells, row by row
+    /// The cursor's coordinates are its column and row, if it is visible.
+    /// Returns an error message if the number of cells does not match the dimensions.
+    pub fn new(
+        columns: u32,
+        rows: u32,
+        cells: Vec<TextCell>,
+        cursor: Option<(u32, u32)>,
+    ) -> Result<Self, &'static str> {
+        if cells.len() as u64 != u64::from(columns) * u64::from(rows) {
+            return Err("Screen's cells do not match its dimensions");
+        }
+
+        Ok(Self {
+            columns,
+            rows,
+            cells,
+            cursor,
+        })
+    }
+
+    /// Get the number of columns
+    pub fn columns(&self) -> u32 {
+        self.columns
+    }
+
+    /// Get the number of rows
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    /// Get the cells, row by row
+    pub fn cells(&self) -> &[TextCell] {
+        &self.cells
+    }
+
+    /// Get a cell (panics if the coordinates are out of the screen)
+    pub fn cell(&self, column: u32, row: u32) -> TextCell {
+        assert!(
+            column < self.columns && row < self.rows,
+            "Cell is out of the screen"
+        );
+
+        self.cells[(row * self.columns + column) as usize]
+    }
+
+    /// Get the cursor's column and row, if it is visible
+    pub fn cursor(&self) -> Option<(u32, u32)> {
+        self.cursor
+    }
+
+    /// Get a row's characters, without trailing spaces
+    pub fn row_text(&self, row: u32) -> String {
+        let start = (row * self.columns) as usize;
+
+        self.cells[start..start + self.columns as usize]
+            .iter()
+            .map(|cell| cell.ch)
+            .collect::<String>()
+            .trim_end_matches(' ')
+            .to_string()
+    }
+
+    /// Get the screen's characters as plain text, one line per row (without trailing spaces)
+    pub fn to_plain_text(&self) -> String {
+        (0..self.rows)
+            .map(|row| self.row_text(row))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
@@ -0,0 +1,184 @@
+//! The random number generator component offers guests a source of random numbers.
+//! See [`Rng`] for more details.
+
+use std::{
+    collections::hash_map::RandomState,
+    fs::File,
+    hash::{BuildHasher, Hasher},
+    io::Read,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use lrvm::board::{Bus, ResetKind};
+use lrvm_tools::{
+    exceptions::AuxHwException,
+    metadata::{DeviceMetadata, EntropyType},
+};
+
+/// Mode: numbers are generated by a seeded pseudo-random generator, so the same seed always gives the same numbers
+pub const RNG_DETERMINISTIC: u32 = 0x00;
+/// Mode: numbers come from the host's entropy source
+pub const RNG_HOST_ENTROPY: u32 = 0x01;
+
+/// The random number generator is a 4-word-long component:
+///
+/// * Word 1: data (read-only) ; reading it returns the next random number
+/// * Word 2: mode (`RNG_DETERMINISTIC` or `RNG_HOST_ENTROPY`)
+/// * Word 3: seed ; writing to it restarts the pseudo-random generator from the written seed
+/// * Word 4: reseed (write-only) ; writing to it mixes the written word into the pseudo-random generator's state,
+///   along with numbers from the host's entropy source in host entropy mode
+///
+/// In deterministic mode, the numbers only depend on the seed and on the words written to the reseed register,
+/// so programs can be replayed exactly. In host entropy mode, they are read from `/dev/urandom` (when available)
+/// or derived from the standard library's random hashing keys and the current time.
+///
+/// The mode and the seed are kept across warm and guest resets, which restart the generator from the seed ;
+/// cold resets set them back to the values the component was created with.
+pub struct Rng {
+    mode: u32,
+    seed: u32,
+    state: u64,
+    initial: (u32, u32),
+    host: HostEntropy,
+    hw_id: u64,
+}
+
+impl Rng {
+    /// Create a deterministic generator with the provided seed
+    pub fn seeded(seed: u32, hw_id: u64) -> Self {
+        Self::with_mode(RNG_DETERMINISTIC, seed, hw_id)
+    }
+
+    /// Create a generator using the host's entropy source (its seed is only used if the guest switches to the deterministic mode)
+    pub fn host_entropy(hw_id: u64) -> Self {
+        Self::with_mode(RNG_HOST_ENTROPY, 0, hw_id)
+    }
+
+    /// (Internal) Create a generator
+    fn with_mode(mode: u32, seed: u32, hw_id: u64) -> Self {
+        Self {
+            mode,
+            seed,
+            state: u64::from(seed),
+            initial: (mode, seed),
+            host: HostEntropy::new(),
+            hw_id,
+        }
+    }
+
+    /// Get the next random number
+    pub fn next_u32(&mut self) -> u32 {
+        match self.mode {
+            RNG_HOST_ENTROPY => self.host.next_u32(),
+            _ => (splitmix64(&mut self.state) >> 32) as u32,
+        }
+    }
+}
+
+impl Bus for Rng {
+    fn name(&self) -> &'static str {
+        "Random Number Generator"
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        DeviceMetadata::new(self.hw_id, 16, EntropyType::Rng.into(), None, None).encode()
+    }
+
+    fn read(&mut self, addr: u32, ex: &mut u16) -> u32 {
+        match addr {
+            0x00 => self.next_u32(),
+            0x0C => {
+                *ex = AuxHwException::MemoryNotReadable.into();
+                0
+            }
+            _ => self.peek(addr).unwrap(),
+        }
+    }
+
+    fn write(&mut self, addr: u32, word: u32, ex: &mut u16) {
+        match addr {
+            0x00 => *ex = AuxHwException::MemoryNotWritable.into(),
+            0x04 => match word {
+                RNG_DETERMINISTIC | RNG_HOST_ENTROPY => self.mode = word,
+                _ => *ex = AuxHwException::UnknownOperation(word as u8).into(),
+            },
+            0x08 => {
+                self.seed = word;
+                self.state = u64::from(word);
+            }
+            0x0C => {
+                self.state ^= u64::from(word);
+
+                if self.mode == RNG_HOST_ENTROPY {
+                    self.state ^=
+                        (u64::from(self.host.next_u32()) << 32) | u64::from(self.host.next_u32());
+                }
+
+                splitmix64(&mut self.state);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn reset(&mut self, kind: ResetKind) {
+        if kind == ResetKind::Cold {
+            (self.mode, self.seed) = self.initial;
+        }
+
+        self.state = u64::from(self.seed);
+    }
+
+    fn peek(&self, addr: u32) -> Option<u32> {
+        match addr {
+            0x04 => Some(self.mode),
+            0x08 => Some(self.seed),
+            _ => None,
+        }
+    }
+}
+
+/// (Internal) Advance a SplitMix64 generator and get its next output
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// (Internal) Host entropy source
+struct HostEntropy {
+    urandom: Option<File>,
+    fallback: u64,
+}
+
+impl HostEntropy {
+    fn new() -> Self {
+        Self {
+            urandom: File::open("/dev/urandom").ok(),
+            fallback: 0,
+        }
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        if let Some(urandom) = &mut self.urandom {
+            let mut bytes = [0; 4];
+
+            if urandom.read_exact(&mut bytes).is_ok() {
+                return u32::from_be_bytes(bytes);
+            }
+        }
+
+        // Each `RandomState` gets different keys, which are randomly initialized by the standard library
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u64(self.fallback);
+
+        if let Ok(time) = SystemTime::now().duration_since(UNIX_EPOCH) {
+            hasher.write_u128(time.as_nanos());
+        }
+
+        self.fallback = self.fallback.wrapping_add(1);
+        (hasher.finish() >> 32) as u32
+    }
+}
@@ -0,0 +1,366 @@
+//! The asynchronous keyboard component receives key events from the host without ever blocking the VM.
+//! See [`AsyncKeyboard`] for more details.
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use lrvm::board::{Bus, ResetKind};
+use lrvm_tools::{
+    exceptions::AuxHwException,
+    metadata::{DeviceMetadata, KeyboardType},
+};
+
+use super::InputSource;
+
+/// Key code: backspace
+pub const KEY_BACKSPACE: u16 = 0x08;
+/// Key code: tab
+pub const KEY_TAB: u16 = 0x09;
+/// Key code: enter
+pub const KEY_ENTER: u16 = 0x0D;
+/// Key code: escape
+pub const KEY_ESCAPE: u16 = 0x1B;
+/// Key code: up arrow
+pub const KEY_UP: u16 = 0xE000;
+/// Key code: down arrow
+pub const KEY_DOWN: u16 = 0xE001;
+/// Key code: left arrow
+pub const KEY_LEFT: u16 = 0xE002;
+/// Key code: right arrow
+pub const KEY_RIGHT: u16 = 0xE003;
+/// Key code: home
+pub const KEY_HOME: u16 = 0xE004;
+/// Key code: end
+pub const KEY_END: u16 = 0xE005;
+/// Key code: page up
+pub const KEY_PAGE_UP: u16 = 0xE006;
+/// Key code: page down
+pub const KEY_PAGE_DOWN: u16 = 0xE007;
+/// Key code: insert
+pub const KEY_INSERT: u16 = 0xE008;
+/// Key code: delete
+pub const KEY_DELETE: u16 = 0xE009;
+/// Key code of the first function key (F1), the following ones (up to F12) have the next codes
+pub const KEY_F1: u16 = 0xE100;
+
+/// Modifier: shift
+pub const MOD_SHIFT: u8 = 0x01;
+/// Modifier: control
+pub const MOD_CTRL: u8 = 0x02;
+/// Modifier: alt
+pub const MOD_ALT: u8 = 0x04;
+/// Modifier: meta (e.g. Windows or Command key)
+pub const MOD_META: u8 = 0x08;
+
+/// Status flag: the queue contains at least one event
+pub const KBD_DATA_READY: u32 = 0x01;
+/// Status flag: an event was received while the queue was full, and was lost
+pub const KBD_OVERFLOW: u32 = 0x02;
+
+/// Control flag: raise a hardware interruption when events are received
+pub const KBD_INTERRUPT: u32 = 0x01;
+
+/// Default size of the event queue
+pub const KBD_DEFAULT_QUEUE_SIZE: usize = 16;
+
+/// A key event, encoded in a single word:
+///
+/// * Bits 0 to 15: key code (Unicode code point of the Basic Multilingual Plane for characters, or a `KEY_*` constant)
+/// * Bits 16 to 23: modifiers (`MOD_SHIFT`, `MOD_CTRL`, `MOD_ALT` and `MOD_META`)
+/// * Bit 31: set if the key was released, clear if it was pressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyEvent {
+    pub code: u16,
+    pub modifiers: u8,
+    pub pressed: bool,
+}
+
+impl KeyEvent {
+    /// Create a key press event
+    pub fn press(code: u16, modifiers: u8) -> Self {
+        Self {
+            code,
+            modifiers,
+            pressed: true,
+        }
+    }
+
+    /// Create a key release event
+    pub fn release(code: u16, modifiers: u8) -> Self {
+        Self {
+            code,
+            modifiers,
+            pressed: false,
+        }
+    }
+
+    /// Encode the event
+    pub fn encode(&self) -> u32 {
+        let released = if self.pressed { 0 } else { 0x8000_0000 };
+        u32::from(self.code) | (u32::from(self.modifiers) << 16) | released
+    }
+
+    /// Decode an event
+    pub fn decode(word: u32) -> Self {
+        Self {
+            code: word as u16,
+            modifiers: (word >> 16) as u8,
+            pressed: word & 0x8000_0000 == 0,
+        }
+    }
+}
+
+/// (Internal) Event queue shared between the keyboard and its producers
+#[derive(Debug)]
+struct EventQueue {
+    events: VecDeque<KeyEvent>,
+    capacity: usize,
+    overflow: bool,
+    /// Events were received since the last notification
+    arrived: bool,
+}
+
+/// Host-side handle pushing events to an [`AsyncKeyboard`].
+/// It can be cloned and sent to other threads.
+#[derive(Debug, Clone)]
+pub struct KeyboardProducer(Arc<Mutex<EventQueue>>);
+
+impl KeyboardProducer {
+    /// Push an event to the keyboard's queue
+    /// If the queue is full, the event is lost and the keyboard's overflow flag is set (this returns `false`).
+    pub fn push(&self, event: KeyEvent) -> bool {
+        let mut queue = self.0.lock().unwrap();
+
+        if queue.events.len() == queue.capacity {
+            queue.overflow = true;
+            return false;
+        }
+
+        queue.events.push_back(event);
+        queue.arrived = true;
+        true
+    }
+
+    /// Push a press and a release event for each character of a text (newlines are sent as [`KEY_ENTER`])
+    /// Characters outside of the Basic Multilingual Plane are ignored.
+    /// Returns `false` if some events were lost because the queue was full.
+    pub fn type_text(&self, text: &str) -> bool {
+        let mut all_pushed = true;
+
+        for c in text.chars() {
+            let code = match c {
+                '\n' => KEY_ENTER,
+                _ => match u16::try_from(c as u32) {
+                    Ok(code) => code,
+                    Err(_) => continue,
+                },
+            };
+
+            all_pushed &= self.push(KeyEvent::press(code, 0));
+            all_pushed &= self.push(KeyEvent::release(code, 0));
+        }
+
+        all_pushed
+    }
+
+    /// Get the number of events waiting in the queue
+    pub fn pending(&self) -> usize {
+        self.0.lock().unwrap().events.len()
+    }
+
+    /// Check if the queue has room for more events
+    pub fn has_room(&self) -> bool {
+        self.room() > 0
+    }
+
+    /// Get the number of events the queue has room for
+    pub fn room(&self) -> usize {
+        let queue = self.0.lock().unwrap();
+        queue.capacity - queue.events.len()
+    }
+}
+
+/// The asynchronous keyboard is a 5-word-long keyboard with a bounded queue of key events (see [`KeyEvent`] for their encoding),
+/// filled by host-side [`KeyboardProducer`]s without ever blocking the VM.
+///
+/// * Word 1: data (read-only) ; reading it pops the next event from the queue (`0` if it is empty)
+/// * Word 2: status flags (`KBD_DATA_READY` and `KBD_OVERFLOW`) ; writing `KBD_OVERFLOW` to it clears this flag
+/// * Word 3: number of events in the queue (read-only)
+/// * Word 4: control flags (`KBD_INTERRUPT`)
+/// * Word 5: interruption code (only the weakest byte is kept)
+///
+/// If the `KBD_INTERRUPT` control flag is set, a hardware interruption is raised on the first CPU cycle after events were received
+/// (or when the flag is set, if events were received in the meantime and are still in the queue).
+/// The size of the queue is provided in the metadata's additional data.
+///
+/// The keyboard can also type the characters of an [`InputSource`] by itself (see [`AsyncKeyboard::with_input`]).
+pub struct AsyncKeyboard {
+    queue: Arc<Mutex<EventQueue>>,
+    capacity: usize,
+    input: Option<InputSource>,
+    control: u32,
+    itr_code: u8,
+    hw_id: u64,
+}
+
+impl AsyncKeyboard {
+    /// Create a new asynchronous keyboard with a queue of [`KBD_DEFAULT_QUEUE_SIZE`] events
+    pub fn new(hw_id: u64) -> Self {
+        Self::with_queue_size(KBD_DEFAULT_QUEUE_SIZE, hw_id).unwrap()
+    }
+
+    /// Create a new asynchronous keyboard with a queue of the provided size
+    /// Returns an error message if the size is 0.
+    pub fn with_queue_size(capacity: usize, hw_id: u64) -> Result<Self, &'static str> {
+        if capacity == 0 {
+            return Err("Keyboard's queue size cannot be 0");
+        }
+
+        Ok(Self {
+            queue: Arc::new(Mutex::new(EventQueue {
+                events: VecDeque::with_capacity(capacity),
+                capacity,
+                overflow: false,
+                arrived: false,
+            })),
+            capacity,
+            input: None,
+            control: 0,
+            itr_code: 0,
+            hw_id,
+        })
+    }
+
+    /// Type the characters of an input source, like [`KeyboardProducer::type_text`] does.
+    /// Characters are taken on each CPU cycle, as long as the queue has room for both their press and release events,
+    /// so no character is lost if the guest reads the queue too slowly. Input provided by the source after its end is typed as well.
+    pub fn with_input(mut self, input: InputSource) -> Self {
+        self.input = Some(input);
+        self
+    }
+
+    /// Get a handle to push events to this keyboard
+    pub fn producer(&self) -> KeyboardProducer {
+        KeyboardProducer(Arc::clone(&self.queue))
+    }
+
+    /// (Internal) Type the input source's characters which fit in the queue
+    fn type_input(&mut self) {
+        let Some(input) = &mut self.input else {
+            return;
+        };
+
+        let producer = KeyboardProducer(Arc::clone(&self.queue));
+        let mut buf = [0; 4];
+
+        // A queue of a single event can only take a character's press event
+        while producer.room() >= self.capacity.min(2) {
+            match input.next_char() {
+                Some(c) => producer.type_text(c.encode_utf8(&mut buf)),
+                None => break,
+            };
+        }
+    }
+}
+
+impl Bus for AsyncKeyboard {
+    fn name(&self) -> &'static str {
+        "Asynchronous Keyboard"
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        DeviceMetadata::new(
+            self.hw_id,
+            20,
+            KeyboardType::Asynchronous.into(),
+            None,
+            Some(self.capacity as u64),
+        )
+        .encode()
+    }
+
+    fn read(&mut self, addr: u32, _ex: &mut u16) -> u32 {
+        match addr {
+            0x00 => self
+                .queue
+                .lock()
+                .unwrap()
+                .events
+                .pop_front()
+                .map_or(0, |event| event.encode()),
+            _ => self.peek(addr).unwrap(),
+        }
+    }
+
+    fn write(&mut self, addr: u32, word: u32, ex: &mut u16) {
+        match addr {
+            0x00 | 0x08 => *ex = AuxHwException::MemoryNotWritable.into(),
+            0x04 => {
+                if word & KBD_OVERFLOW != 0 {
+                    self.queue.lock().unwrap().overflow = false;
+                }
+            }
+            0x0C => self.control = word & KBD_INTERRUPT,
+            0x10 => self.itr_code = word as u8,
+            _ => unreachable!(),
+        }
+    }
+
+    fn reset(&mut self, _kind: ResetKind) {
+        let mut queue = self.queue.lock().unwrap();
+        queue.events.clear();
+        queue.overflow = false;
+        queue.arrived = false;
+
+        self.control = 0;
+        self.itr_code = 0;
+    }
+
+    fn peek(&self, addr: u32) -> Option<u32> {
+        let queue = self.queue.lock().unwrap();
+
+        Some(match addr {
+            0x00 => queue.events.front().map_or(0, |event| event.encode()),
+            0x04 => {
+                let mut status = 0;
+
+                if !queue.events.is_empty() {
+                    status |= KBD_DATA_READY;
+                }
+
+                if queue.overflow {
+                    status |= KBD_OVERFLOW;
+                }
+
+                status
+            }
+            0x08 => queue.events.len() as u32,
+            0x0C => self.control,
+            0x10 => self.itr_code.into(),
+            _ => unreachable!(),
+        })
+    }
+
+    fn ticks(&self) -> bool {
+        true
+    }
+
+    fn tick(&mut self) -> Option<u8> {
+        self.type_input();
+
+        if self.control & KBD_INTERRUPT == 0 {
+            return None;
+        }
+
+        let mut queue = self.queue.lock().unwrap();
+
+        // Events received while interruptions were disabled are notified once they are enabled, unless they were all read
+        if std::mem::replace(&mut queue.arrived, false) && !queue.events.is_empty() {
+            Some(self.itr_code)
+        } else {
+            None
+        }
+    }
+}
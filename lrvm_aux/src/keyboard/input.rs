@@ -0,0 +1,353 @@
+use std::{
+    cell::Cell,
+    collections::VecDeque,
+    fs::File,
+    io::{BufRead, BufReader, Error, ErrorKind, LineWriter, Result as IOResult, Write},
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use lrvm::board::BoardRequest;
+use lrvm_tools::{config::InputScript, exceptions::AuxHwException};
+
+/// Behaviour of a keyboard when its [`InputSource`] has no more input to provide
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndOfInput {
+    /// Provide the marker character instead (a line made of it for line keyboards)
+    Marker(char),
+    /// Raise an `EndOfInput` hardware exception, and clear the keyboard's buffer
+    Exception,
+    /// Halt the CPU, leaving the keyboard's buffer untouched
+    Halt,
+}
+
+impl Default for EndOfInput {
+    /// The NUL character, so an exhausted source looks like an empty input
+    fn default() -> Self {
+        Self::Marker('\0')
+    }
+}
+
+/// Ready-made input for the [`SyncLineKeyboard`](super::SyncLineKeyboard), [`SyncCharKeyboard`](super::SyncCharKeyboard)
+/// and [`AsyncKeyboard`](super::AsyncKeyboard) components, taken from an [`InputScript`], a text, lines, characters, a file or a record log.
+/// Texts, lines, characters and record logs are provided through an input script.
+///
+/// The source is a stream of text: line keyboards take everything up to the next newline (which is not part of the input),
+/// while character keyboards and the asynchronous keyboard take a single character (newlines included).
+///
+/// Record logs contain one input per line, escaped like Rust's [`char::escape_default`] (e.g. `hello\n` for a line,
+/// `\u{e9}` for a character). They are written by sources created with [`InputSource::record_to`], and replayed
+/// with [`InputSource::from_record_log`].
+pub struct InputSource {
+    chunks: Box<dyn FnMut() -> Option<String>>,
+    pending: VecDeque<char>,
+    end: EndOfInput,
+    progress: InputProgress,
+    log: Option<(PathBuf, LineWriter<File>)>,
+}
+
+impl InputSource {
+    /// (Internal) Create a source from a function providing the next chunk of text (`None` if there is none for now)
+    fn from_chunks(chunks: impl FnMut() -> Option<String> + 'static) -> Self {
+        Self {
+            chunks: Box::new(chunks),
+            pending: VecDeque::new(),
+            end: EndOfInput::default(),
+            progress: InputProgress::default(),
+            log: None,
+        }
+    }
+
+    /// Create a source from an input script, shared with the host.
+    /// Input pushed to the script later on is provided as well, even after the end of the input was reached.
+    pub fn from_script(script: InputScript) -> Self {
+        Self::from_chunks(move || script.pop_line())
+    }
+
+    /// Create a source from a text
+    pub fn from_text(text: impl AsRef<str>) -> Self {
+        let script = InputScript::new();
+        script.push_str(text.as_ref());
+        Self::from_script(script)
+    }
+
+    /// Create a source from lines (a newline is added at the end of each one)
+    pub fn from_lines<I, S>(lines: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let script = InputScript::new();
+
+        for line in lines {
+            script.push_line(line.as_ref());
+        }
+
+        Self::from_script(script)
+    }
+
+    /// Create a source from characters
+    pub fn from_chars(chars: impl IntoIterator<Item = char>) -> Self {
+        Self::from_text(chars.into_iter().collect::<String>())
+    }
+
+    /// Create a source reading lines from a reader when they are needed.
+    /// Reading errors are reported on the standard error output, and are considered as the end of the input.
+    pub fn from_reader(mut reader: impl BufRead + 'static) -> Self {
+        Self::from_chunks(move || {
+            let mut line = String::new();
+
+            match reader.read_line(&mut line) {
+                Ok(0) => None,
+                Ok(_) => Some(line),
+                Err(err) => {
+                    eprintln!("Warning: failed to read keyboard input: {}", err);
+                    None
+                }
+            }
+        })
+    }
+
+    /// Create a source reading a file
+    pub fn from_file(path: impl AsRef<Path>) -> IOResult<Self> {
+        Ok(Self::from_reader(BufReader::new(File::open(path)?)))
+    }
+
+    /// Create a source replaying a record log.
+    /// Returns an error if the file cannot be read or contains an invalid record.
+    pub fn from_record_log(path: impl AsRef<Path>) -> IOResult<Self> {
+        let mut text = String::new();
+
+        for (i, record) in std::fs::read_to_string(path)?.lines().enumerate() {
+            let input = unescape(record).ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("invalid record on line {}", i + 1),
+                )
+            })?;
+
+            text.push_str(&input);
+        }
+
+        Ok(Self::from_text(text))
+    }
+
+    /// Set the behaviour at the end of the input (default: the NUL character as a marker)
+    pub fn on_end(mut self, end: EndOfInput) -> Self {
+        self.end = end;
+        self
+    }
+
+    /// Write each provided input to a record log (the file is truncated first)
+    pub fn record_to(mut self, path: impl Into<PathBuf>) -> IOResult<Self> {
+        let path = path.into();
+        let file = File::create(&path)?;
+
+        self.log = Some((path, LineWriter::new(file)));
+        Ok(self)
+    }
+
+    /// Get the behaviour at the end of the input
+    pub fn end_of_input(&self) -> EndOfInput {
+        self.end
+    }
+
+    /// Get a handle to the source's progress, which can still be used once the keyboard is given to the motherboard
+    pub fn progress(&self) -> InputProgress {
+        self.progress.clone()
+    }
+
+    /// Get the next line, without its newline symbol (`None` at the end of the input)
+    pub fn next_line(&mut self) -> Option<String> {
+        while !self.pending.contains(&'\n') && self.pull() {}
+
+        if self.pending.is_empty() {
+            return self.exhausted();
+        }
+
+        let len = match self.pending.iter().position(|c| *c == '\n') {
+            Some(pos) => pos + 1,
+            None => self.pending.len(),
+        };
+
+        let consumed = self.pending.drain(..len).collect::<String>();
+        self.provided(&consumed);
+
+        let line = consumed.strip_suffix('\n').unwrap_or(&consumed);
+        Some(line.strip_suffix('\r').unwrap_or(line).to_string())
+    }
+
+    /// Get the next character (`None` at the end of the input)
+    pub fn next_char(&mut self) -> Option<char> {
+        while self.pending.is_empty() {
+            if !self.pull() {
+                return self.exhausted();
+            }
+        }
+
+        let c = self.pending.pop_front().unwrap();
+        self.provided(&c.to_string());
+        Some(c)
+    }
+
+    /// (Internal) Pull the next chunk of text, returning `false` if there is none
+    fn pull(&mut self) -> bool {
+        match (self.chunks)() {
+            Some(chunk) => {
+                self.pending.extend(chunk.chars());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// (Internal) Account for an input that was provided, with the text it consumed
+    fn provided(&mut self, consumed: &str) {
+        let counters = &self.progress.0;
+        counters.consumed.set(counters.consumed.get() + 1);
+        counters.exhausted.set(false);
+
+        if let Some((path, log)) = &mut self.log {
+            let record = consumed
+                .chars()
+                .flat_map(char::escape_default)
+                .collect::<String>();
+
+            if let Err(err) = writeln!(log, "{}", record) {
+                eprintln!(
+                    "Warning: failed to write keyboard input record to '{}': {}",
+                    path.display(),
+                    err
+                );
+
+                self.log = None;
+            }
+        }
+    }
+
+    /// (Internal) Account for the end of the input
+    fn exhausted<T>(&mut self) -> Option<T> {
+        self.progress.0.exhausted.set(true);
+        None
+    }
+}
+
+/// Host-side handle to the progress of an [`InputSource`], obtained with [`InputSource::progress`].
+/// Clones of a handle all share the same counters.
+#[derive(Debug, Clone, Default)]
+pub struct InputProgress(Rc<ProgressCounters>);
+
+/// (Internal) Counters shared by the [`InputProgress`] handles
+#[derive(Debug, Default)]
+struct ProgressCounters {
+    consumed: Cell<u64>,
+    exhausted: Cell<bool>,
+}
+
+impl InputProgress {
+    /// Get the number of inputs (lines or characters) provided so far
+    pub fn consumed(&self) -> u64 {
+        self.0.consumed.get()
+    }
+
+    /// Check if the last input request reached the end of the input
+    pub fn exhausted(&self) -> bool {
+        self.0.exhausted.get()
+    }
+}
+
+/// (Internal) Input of a synchronous keyboard: either a handler provided by the host, or an input source
+pub(super) enum KeyboardInput<T> {
+    Handler(Box<dyn FnMut() -> T>),
+    Source(InputSource),
+}
+
+impl<T> KeyboardInput<T> {
+    /// Check if reaching the end of the input halts the CPU, which requires the keyboard to receive TICK requests
+    pub(super) fn halts(&self) -> bool {
+        matches!(self, Self::Source(source) if source.end_of_input() == EndOfInput::Halt)
+    }
+
+    /// (Internal) Handle the end of the input, returning the marker to use as input if there is one
+    fn end(end: EndOfInput, ex: &mut u16, request: &mut Option<BoardRequest>) -> Option<char> {
+        match end {
+            EndOfInput::Marker(marker) => Some(marker),
+            EndOfInput::Exception => {
+                *ex = AuxHwException::EndOfInput.into();
+                None
+            }
+            EndOfInput::Halt => {
+                *request = Some(BoardRequest::Halt);
+                None
+            }
+        }
+    }
+}
+
+impl KeyboardInput<String> {
+    /// Get the next line. At the end of the input, returns the marker line or `None` after raising an exception or
+    /// requesting the CPU to halt.
+    pub(super) fn next(
+        &mut self,
+        ex: &mut u16,
+        request: &mut Option<BoardRequest>,
+    ) -> Option<String> {
+        match self {
+            Self::Handler(handler) => Some(handler()),
+            Self::Source(source) => match source.next_line() {
+                Some(line) => Some(line),
+                None => Self::end(source.end_of_input(), ex, request).map(String::from),
+            },
+        }
+    }
+}
+
+impl KeyboardInput<char> {
+    /// Get the next character. At the end of the input, returns the marker or `None` after raising an exception or
+    /// requesting the CPU to halt.
+    pub(super) fn next(
+        &mut self,
+        ex: &mut u16,
+        request: &mut Option<BoardRequest>,
+    ) -> Option<char> {
+        match self {
+            Self::Handler(handler) => Some(handler()),
+            Self::Source(source) => match source.next_char() {
+                Some(c) => Some(c),
+                None => Self::end(source.end_of_input(), ex, request),
+            },
+        }
+    }
+}
+
+/// (Internal) Decode a record escaped with `char::escape_default`
+fn unescape(record: &str) -> Option<String> {
+    let mut chars = record.chars();
+    let mut input = String::new();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            input.push(c);
+            continue;
+        }
+
+        input.push(match chars.next()? {
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            '0' => '\0',
+            'u' => {
+                if chars.next()? != '{' {
+                    return None;
+                }
+
+                let code = chars.by_ref().take_while(|c| *c != '}').collect::<String>();
+                char::from_u32(u32::from_str_radix(&code, 16).ok()?)?
+            }
+            c @ ('\\' | '\'' | '"') => c,
+            _ => return None,
+        });
+    }
+
+    Some(input)
+}
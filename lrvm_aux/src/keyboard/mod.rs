@@ -1,4 +1,11 @@
+mod async_queue;
+mod input;
 mod sync_char;
 mod sync_line;
 
-pub use self::{sync_char::SyncCharKeyboard, sync_line::SyncLineKeyboard};
+pub use self::{
+    async_queue::*,
+    input::{EndOfInput, InputProgress, InputSource},
+    sync_char::SyncCharKeyboard,
+    sync_line::SyncLineKeyboard,
+};
@@ -1,12 +1,14 @@
 //! The synchronous character keyboard component offers a simple one-character reading system.
 //! See [`SyncLineKeyboard`] for more details.
 
-use lrvm::board::Bus;
+use lrvm::board::{BoardRequest, Bus, ResetKind};
 use lrvm_tools::{
     exceptions::AuxHwException,
     metadata::{DeviceMetadata, KeyboardType},
 };
 
+use super::{input::KeyboardInput, InputSource};
+
 /// The keyboard works with a 1-word buffer and a handler.
 ///
 /// When it receives a read request, the data is read from the buffer.
@@ -17,18 +19,32 @@ use lrvm_tools::{
 /// * `0x02`: clear the buffer's content
 ///
 /// The buffer is guaranteed to contain a valid UTF-8 character.
+///
+/// Inputs are provided either by a handler, or by an [`InputSource`] (see [`SyncCharKeyboard::with_source`]).
 pub struct SyncCharKeyboard {
     buffer: char,
-    handler: Box<dyn FnMut() -> char>,
+    input: KeyboardInput<char>,
+    request: Option<BoardRequest>,
     hw_id: u64,
 }
 
 impl SyncCharKeyboard {
     /// Create a synchronous character keyboard component.
     pub fn new(handler: Box<dyn FnMut() -> char>, hw_id: u64) -> Self {
+        Self::with_input(KeyboardInput::Handler(handler), hw_id)
+    }
+
+    /// Create a synchronous character keyboard component taking its characters from an input source.
+    pub fn with_source(source: InputSource, hw_id: u64) -> Self {
+        Self::with_input(KeyboardInput::Source(source), hw_id)
+    }
+
+    /// (Internal) Create a synchronous character keyboard component with the provided input
+    fn with_input(input: KeyboardInput<char>, hw_id: u64) -> Self {
         Self {
             buffer: 0 as char,
-            handler,
+            input,
+            request: None,
             hw_id,
         }
     }
@@ -66,8 +82,12 @@ impl Bus for SyncCharKeyboard {
             *ex = 0x31 << 8;
         } else if addr == 4 {
             match word {
-                0x01 => self.buffer = (self.handler)(),
-                0x02 => self.reset(),
+                0x01 => match self.input.next(ex, &mut self.request) {
+                    Some(c) => self.buffer = c,
+                    None if *ex != 0 => self.buffer = 0 as char,
+                    None => {}
+                },
+                0x02 => self.buffer = 0 as char,
                 code => *ex = AuxHwException::UnknownOperation(code as u8).into(),
             }
         } else {
@@ -75,7 +95,34 @@ impl Bus for SyncCharKeyboard {
         }
     }
 
-    fn reset(&mut self) {
+    fn reset(&mut self, _kind: ResetKind) {
         self.buffer = 0 as char;
+        self.request = None;
+    }
+
+    fn peek(&self, addr: u32) -> Option<u32> {
+        if addr == 0 {
+            Some(self.buffer as u32)
+        } else {
+            None
+        }
+    }
+
+    fn poke(&mut self, addr: u32, word: u32) -> bool {
+        match std::char::from_u32(word) {
+            Some(c) if addr == 0 => {
+                self.buffer = c;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn ticks(&self) -> bool {
+        self.input.halts()
+    }
+
+    fn request(&mut self) -> Option<BoardRequest> {
+        self.request.take()
     }
 }
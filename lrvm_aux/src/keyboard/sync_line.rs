@@ -1,12 +1,14 @@
 //! The synchronous line keyboard component offers a simple UTF-8 line reading system.
 //! See [`SyncLineKeyboard`] for more details.
 
-use lrvm::board::Bus;
+use lrvm::board::{BoardRequest, Bus, ResetKind};
 use lrvm_tools::{
     exceptions::AuxHwException,
     metadata::{DeviceMetadata, KeyboardType},
 };
 
+use super::{input::KeyboardInput, InputSource};
+
 /// The keyboard works with a buffer and a handler.
 ///
 /// When it receives a read request, the data is read from the buffer.
@@ -17,10 +19,13 @@ use lrvm_tools::{
 /// * `0xFF`: clear the buffer's content
 ///
 /// The buffer is guaranteed to contain valid UTF-8 data.
+///
+/// Inputs are provided either by a handler, or by an [`InputSource`] (see [`SyncLineKeyboard::with_source`]).
 pub struct SyncLineKeyboard {
     buffer: Vec<u32>,
     capacity: u32,
-    handler: Box<dyn FnMut() -> String>,
+    input: KeyboardInput<String>,
+    request: Option<BoardRequest>,
     hw_id: u64,
 }
 
@@ -33,6 +38,25 @@ impl SyncLineKeyboard {
         capacity: u32,
         handler: Box<dyn FnMut() -> String>,
         hw_id: u64,
+    ) -> Result<Self, &'static str> {
+        Self::with_input(capacity, KeyboardInput::Handler(handler), hw_id)
+    }
+
+    /// Create a synchronous keyboard component taking its lines from an input source.
+    /// The capacity follows the same rules as for [`SyncLineKeyboard::new`].
+    pub fn with_source(
+        capacity: u32,
+        source: InputSource,
+        hw_id: u64,
+    ) -> Result<Self, &'static str> {
+        Self::with_input(capacity, KeyboardInput::Source(source), hw_id)
+    }
+
+    /// (Internal) Create a synchronous keyboard component with the provided input
+    fn with_input(
+        capacity: u32,
+        input: KeyboardInput<String>,
+        hw_id: u64,
     ) -> Result<Self, &'static str> {
         let _: usize = capacity.try_into().map_err(|_| {
             "Display's buffer's capacity must not exceed your CPU architecture (e.g. 32-bit size)"
@@ -51,7 +75,8 @@ impl SyncLineKeyboard {
         Ok(Self {
             buffer: vec![0; (capacity - 1) as usize],
             capacity: capacity - 1,
-            handler,
+            input,
+            request: None,
             hw_id,
         })
     }
@@ -89,11 +114,19 @@ impl Bus for SyncLineKeyboard {
         } else {
             match word {
                 0xAA => {
+                    let Some(line) = self.input.next(ex, &mut self.request) else {
+                        if *ex != 0 {
+                            self.buffer.fill(0);
+                        }
+
+                        return;
+                    };
+
                     let mut word = 0;
                     let mut byte_index = 0;
                     let mut pos = 0;
 
-                    for byte in (self.handler)().bytes() {
+                    for byte in line.bytes() {
                         word += (byte as u32) << ((3 - byte_index) * 8);
 
                         if byte_index == 3 {
@@ -121,14 +154,37 @@ impl Bus for SyncLineKeyboard {
                     }
                 }
 
-                0xFF => self.reset(),
+                0xFF => self.buffer.fill(0),
 
                 code => *ex = AuxHwException::UnknownOperation(code as u8).into(),
             }
         }
     }
 
-    fn reset(&mut self) {
+    fn reset(&mut self, _kind: ResetKind) {
         self.buffer = vec![0; self.buffer.len()];
+        self.request = None;
+    }
+
+    fn peek(&self, addr: u32) -> Option<u32> {
+        self.buffer.get(addr as usize / 4).copied()
+    }
+
+    fn poke(&mut self, addr: u32, word: u32) -> bool {
+        match self.buffer.get_mut(addr as usize / 4) {
+            Some(stored) => {
+                *stored = word;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn ticks(&self) -> bool {
+        self.input.halts()
+    }
+
+    fn request(&mut self) -> Option<BoardRequest> {
+        self.request.take()
     }
 }
@@ -7,9 +7,15 @@
 // Re-export the LRVM crate
 pub use lrvm;
 
+pub mod audio;
 pub mod debug;
 pub mod display;
+pub mod entropy;
 pub mod keyboard;
+pub mod network;
+pub mod power;
+pub mod registry;
+pub mod serial;
 pub mod storage;
 pub mod time;
 pub mod volatile_mem;
@@ -0,0 +1,64 @@
+#[cfg(unix)]
+use std::{
+    io::Result as IOResult,
+    os::unix::net::UnixDatagram,
+    path::{Path, PathBuf},
+};
+
+/// Host side of a [`Nic`](super::Nic), carrying its packets
+pub trait NicBackend {
+    /// Send a packet transmitted by the guest
+    fn send(&mut self, packet: &[u8]);
+
+    /// Get the next packet to deliver to the guest, if any.
+    /// This is called on CPU cycles, so it must never block.
+    fn receive(&mut self) -> Option<Vec<u8>>;
+
+    /// Check if the backend keeps its packets until the NIC has room to queue them (default: `true`).
+    /// Without flow control, a packet is received on each cycle even if the NIC's queue is full, in which case it is lost.
+    fn flow_control(&self) -> bool {
+        true
+    }
+}
+
+/// Backend exchanging packets with another process through Unix datagram sockets, each packet being a single datagram.
+///
+/// Packets sent while the peer's socket does not exist are lost.
+#[cfg(unix)]
+pub struct UnixDatagramBackend {
+    socket: UnixDatagram,
+    peer: PathBuf,
+    buf: Vec<u8>,
+}
+
+#[cfg(unix)]
+impl UnixDatagramBackend {
+    /// Create a socket at the provided path, exchanging packets with the socket at the peer's path
+    pub fn bind(path: impl AsRef<Path>, peer: impl AsRef<Path>) -> IOResult<Self> {
+        let socket = UnixDatagram::bind(path)?;
+        socket.set_nonblocking(true)?;
+
+        Ok(Self {
+            socket,
+            peer: peer.as_ref().to_path_buf(),
+            // Large enough for any datagram
+            buf: vec![0; 0x10000],
+        })
+    }
+}
+
+#[cfg(unix)]
+impl NicBackend for UnixDatagramBackend {
+    fn send(&mut self, packet: &[u8]) {
+        // Packets are lost if the peer is not listening, like on a disconnected cable
+        let _ = self.socket.send_to(packet, &self.peer);
+    }
+
+    fn receive(&mut self) -> Option<Vec<u8>> {
+        match self.socket.recv(&mut self.buf) {
+            Ok(len) => Some(self.buf[..len].to_vec()),
+            // Nothing was received, or the socket failed (which is handled like a disconnected cable)
+            Err(_) => None,
+        }
+    }
+}
@@ -0,0 +1,5 @@
+mod backend;
+mod nic;
+mod switch;
+
+pub use self::{backend::*, nic::*, switch::*};
@@ -0,0 +1,280 @@
+//! The network interface controller component sends and receives packets through a host backend.
+//! See [`Nic`] for more details.
+
+use std::collections::VecDeque;
+
+use lrvm::board::{Bus, ResetKind};
+use lrvm_tools::{
+    bytes::{bytes_to_words, words_to_bytes},
+    exceptions::AuxHwException,
+    metadata::{DeviceMetadata, NetworkType},
+};
+
+use super::NicBackend;
+
+/// Command: send the packet in the TX buffer
+pub const NIC_SEND: u32 = 0x01;
+/// Command: move the next received packet to the RX buffer (the RX length is set to `0` if there is none)
+pub const NIC_RECEIVE: u32 = 0x02;
+
+/// Status flag: received packets are waiting in the queue
+pub const NIC_RX_READY: u32 = 0x01;
+/// Status flag: a packet was received while the queue was full, and was lost (only for backends without flow control)
+pub const NIC_RX_OVERFLOW: u32 = 0x02;
+/// Status flag: a packet could not be sent because the TX length was `0` or larger than the MTU
+pub const NIC_TX_ERROR: u32 = 0x04;
+
+/// Control flag: raise a hardware interruption when a packet is received
+pub const NIC_RX_INTERRUPT: u32 = 0x01;
+
+/// Default MTU, in bytes (an Ethernet frame without its checksum)
+pub const NIC_DEFAULT_MTU: u32 = 1514;
+
+/// Number of received packets the NIC can queue
+pub const NIC_RX_QUEUE_SIZE: usize = 16;
+
+/// Size of the NIC's header, in bytes
+pub const NIC_HEADER_SIZE: u32 = 0x20;
+
+/// Get a locally-administered address derived from a hardware identifier, to use as a NIC's address
+pub fn local_mac(hw_id: u64) -> [u8; 6] {
+    let bytes = hw_id.to_be_bytes();
+    [0x02, bytes[3], bytes[4], bytes[5], bytes[6], bytes[7]]
+}
+
+/// The network interface controller (NIC) is made of a header, followed by a TX buffer and an RX buffer which can both contain a packet of MTU bytes
+/// (rounded up to a multiple of 4 bytes). Packets are stored in the buffers as big-endian words.
+///
+/// The header is 8-word-long:
+///
+/// * Word 1: command (write-only), `NIC_SEND` or `NIC_RECEIVE`
+/// * Word 2: status flags (`NIC_RX_READY`, `NIC_RX_OVERFLOW` and `NIC_TX_ERROR`) ; writing to it clears the error flags set in the written word
+/// * Word 3: TX length, in bytes
+/// * Word 4: RX length, in bytes (read-only)
+/// * Word 5: number of received packets waiting in the queue (read-only)
+/// * Word 6: control flags (`NIC_RX_INTERRUPT`)
+/// * Word 7: interruption code (only the weakest byte is kept)
+/// * Word 8: MTU, in bytes (read-only)
+///
+/// On each CPU cycle, at most one packet is received from the backend and queued.
+/// Packets are only received while the queue has room, so the backend keeps them until the guest receives the queued ones,
+/// unless it has no [flow control](NicBackend::flow_control).
+/// The NIC's 48-bit address is provided in the metadata's additional data, but packets are not filtered by destination.
+pub struct Nic {
+    backend: Box<dyn NicBackend>,
+    mac: [u8; 6],
+    mtu: u32,
+    tx: Vec<u32>,
+    rx: Vec<u32>,
+    tx_len: u32,
+    rx_len: u32,
+    queue: VecDeque<Vec<u8>>,
+    errors: u32,
+    control: u32,
+    itr_code: u8,
+    hw_id: u64,
+}
+
+impl Nic {
+    /// Create a NIC with an MTU of [`NIC_DEFAULT_MTU`] bytes
+    pub fn new(backend: Box<dyn NicBackend>, mac: [u8; 6], hw_id: u64) -> Self {
+        Self::with_mtu(backend, mac, NIC_DEFAULT_MTU, hw_id).unwrap()
+    }
+
+    /// Create a NIC with the provided MTU, in bytes
+    /// Returns an error message if the MTU is 0 or larger than 64 KB.
+    pub fn with_mtu(
+        backend: Box<dyn NicBackend>,
+        mac: [u8; 6],
+        mtu: u32,
+        hw_id: u64,
+    ) -> Result<Self, &'static str> {
+        if mtu == 0 || mtu > 0x10000 {
+            return Err("NIC's MTU must be between 1 byte and 64 KB");
+        }
+
+        let words = mtu.div_ceil(4) as usize;
+
+        Ok(Self {
+            backend,
+            mac,
+            mtu,
+            tx: vec![0; words],
+            rx: vec![0; words],
+            tx_len: 0,
+            rx_len: 0,
+            queue: VecDeque::with_capacity(NIC_RX_QUEUE_SIZE),
+            errors: 0,
+            control: 0,
+            itr_code: 0,
+            hw_id,
+        })
+    }
+
+    /// Get the NIC's address
+    pub fn mac(&self) -> [u8; 6] {
+        self.mac
+    }
+
+    /// (Internal) Get the size of a buffer, in bytes
+    fn buffer_size(&self) -> u32 {
+        self.tx.len() as u32 * 4
+    }
+}
+
+impl Bus for Nic {
+    fn name(&self) -> &'static str {
+        "Network Interface Controller"
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        let mut mac = [0; 8];
+        mac[2..].copy_from_slice(&self.mac);
+
+        DeviceMetadata::new(
+            self.hw_id,
+            NIC_HEADER_SIZE + self.buffer_size() * 2,
+            NetworkType::Nic.into(),
+            None,
+            Some(u64::from_be_bytes(mac)),
+        )
+        .encode()
+    }
+
+    fn read(&mut self, addr: u32, ex: &mut u16) -> u32 {
+        if addr == 0x00 {
+            *ex = AuxHwException::MemoryNotReadable.into();
+            return 0;
+        }
+
+        self.peek(addr).unwrap()
+    }
+
+    fn write(&mut self, addr: u32, word: u32, ex: &mut u16) {
+        match addr {
+            0x00 => match word {
+                NIC_SEND => {
+                    if self.tx_len == 0 || self.tx_len > self.mtu {
+                        self.errors |= NIC_TX_ERROR;
+                        return;
+                    }
+
+                    let bytes = words_to_bytes(&self.tx);
+                    self.backend.send(&bytes[..self.tx_len as usize]);
+                }
+
+                NIC_RECEIVE => match self.queue.pop_front() {
+                    Some(packet) => {
+                        self.rx_len = packet.len() as u32;
+                        self.rx.fill(0);
+
+                        for (i, word) in bytes_to_words(packet).into_iter().enumerate() {
+                            self.rx[i] = word;
+                        }
+                    }
+                    None => self.rx_len = 0,
+                },
+
+                code => *ex = AuxHwException::UnknownOperation(code as u8).into(),
+            },
+            0x04 => self.errors &= !word,
+            0x08 => self.tx_len = word,
+            0x0C | 0x10 | 0x1C => *ex = AuxHwException::MemoryNotWritable.into(),
+            0x14 => self.control = word & NIC_RX_INTERRUPT,
+            0x18 => self.itr_code = word as u8,
+            _ => {
+                let offset = (addr - NIC_HEADER_SIZE) / 4;
+
+                match self.tx.get_mut(offset as usize) {
+                    Some(stored) => *stored = word,
+                    None => *ex = AuxHwException::MemoryNotWritable.into(),
+                }
+            }
+        }
+    }
+
+    fn reset(&mut self, _kind: ResetKind) {
+        self.tx.fill(0);
+        self.rx.fill(0);
+        self.tx_len = 0;
+        self.rx_len = 0;
+        self.queue.clear();
+        self.errors = 0;
+        self.control = 0;
+        self.itr_code = 0;
+    }
+
+    fn peek(&self, addr: u32) -> Option<u32> {
+        Some(match addr {
+            0x00 => 0,
+            0x04 => {
+                if self.queue.is_empty() {
+                    self.errors
+                } else {
+                    self.errors | NIC_RX_READY
+                }
+            }
+            0x08 => self.tx_len,
+            0x0C => self.rx_len,
+            0x10 => self.queue.len() as u32,
+            0x14 => self.control,
+            0x18 => self.itr_code.into(),
+            0x1C => self.mtu,
+            _ => {
+                let offset = ((addr - NIC_HEADER_SIZE) / 4) as usize;
+
+                match self.tx.get(offset) {
+                    Some(word) => *word,
+                    None => self.rx[offset - self.tx.len()],
+                }
+            }
+        })
+    }
+
+    fn poke(&mut self, addr: u32, word: u32) -> bool {
+        if addr < NIC_HEADER_SIZE {
+            return false;
+        }
+
+        let offset = ((addr - NIC_HEADER_SIZE) / 4) as usize;
+
+        if offset < self.tx.len() {
+            self.tx[offset] = word;
+        } else {
+            let rx_len = self.tx.len();
+            self.rx[offset - rx_len] = word;
+        }
+
+        true
+    }
+
+    fn ticks(&self) -> bool {
+        true
+    }
+
+    fn tick(&mut self) -> Option<u8> {
+        // Backends with flow control keep their packets until the queue has room for them
+        let room = self.queue.len() < NIC_RX_QUEUE_SIZE;
+
+        if !room && self.backend.flow_control() {
+            return None;
+        }
+
+        let mut packet = self.backend.receive()?;
+
+        if !room {
+            self.errors |= NIC_RX_OVERFLOW;
+            return None;
+        }
+
+        // Packets larger than the MTU are truncated
+        packet.truncate(self.mtu as usize);
+        self.queue.push_back(packet);
+
+        if self.control & NIC_RX_INTERRUPT != 0 {
+            Some(self.itr_code)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use super::NicBackend;
+
+/// Broadcast address, delivering packets to all the other ports of a switch
+pub const BROADCAST_MAC: [u8; 6] = [0xFF; 6];
+
+/// Number of packets each port of a switch keeps until its NIC receives them
+pub const SWITCH_PORT_QUEUE_SIZE: usize = 64;
+
+/// (Internal) Port of a switch
+#[derive(Debug)]
+struct Port {
+    mac: [u8; 6],
+    inbox: VecDeque<Vec<u8>>,
+}
+
+/// (Internal) State of a switch
+#[derive(Debug, Default)]
+struct SwitchState {
+    ports: Vec<Port>,
+    forwarded: u64,
+    dropped: u64,
+}
+
+/// In-process virtual switch, connecting the [`Nic`](super::Nic)s of several motherboards without any real network access.
+///
+/// Packets are routed using their first 6 bytes as the destination address: they are delivered to the port with this address,
+/// or to all the other ports for [`BROADCAST_MAC`]. Packets with an unknown destination or shorter than 6 bytes are dropped,
+/// as well as packets for a port already holding [`SWITCH_PORT_QUEUE_SIZE`] packets (once per full port).
+///
+/// Clones of a switch all share the same ports, and can be sent to other threads.
+#[derive(Debug, Clone, Default)]
+pub struct VirtualSwitch(Arc<Mutex<SwitchState>>);
+
+impl VirtualSwitch {
+    /// Create a switch with no port
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new port with the provided address, to use as a NIC's backend
+    pub fn connect(&self, mac: [u8; 6]) -> SwitchPort {
+        let mut state = self.0.lock().unwrap();
+
+        state.ports.push(Port {
+            mac,
+            inbox: VecDeque::new(),
+        });
+
+        SwitchPort {
+            switch: self.clone(),
+            id: state.ports.len() - 1,
+        }
+    }
+
+    /// Get the number of delivered packets (a broadcast packet is counted once per receiving port)
+    pub fn forwarded(&self) -> u64 {
+        self.0.lock().unwrap().forwarded
+    }
+
+    /// Get the number of dropped packets
+    pub fn dropped(&self) -> u64 {
+        self.0.lock().unwrap().dropped
+    }
+}
+
+/// Port of a [`VirtualSwitch`], used as a NIC's backend
+#[derive(Debug)]
+pub struct SwitchPort {
+    switch: VirtualSwitch,
+    id: usize,
+}
+
+impl NicBackend for SwitchPort {
+    fn send(&mut self, packet: &[u8]) {
+        let mut state = self.switch.0.lock().unwrap();
+        let state = &mut *state;
+
+        let Some(dest) = packet.get(..6) else {
+            state.dropped += 1;
+            return;
+        };
+
+        let mut delivered = 0;
+        let mut matched = false;
+
+        for (id, port) in state.ports.iter_mut().enumerate() {
+            if id != self.id && (dest == BROADCAST_MAC || dest == port.mac) {
+                matched = true;
+
+                if port.inbox.len() < SWITCH_PORT_QUEUE_SIZE {
+                    port.inbox.push_back(packet.to_vec());
+                    delivered += 1;
+                } else {
+                    state.dropped += 1;
+                }
+            }
+        }
+
+        if !matched {
+            state.dropped += 1;
+        }
+
+        state.forwarded += delivered;
+    }
+
+    fn receive(&mut self) -> Option<Vec<u8>> {
+        self.switch.0.lock().unwrap().ports[self.id]
+            .inbox
+            .pop_front()
+    }
+}
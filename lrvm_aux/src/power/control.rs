@@ -0,0 +1,97 @@
+//! The power controller component lets the guest shut the machine down with an exit status, or reboot it.
+//! See [`PowerControl`] for more details.
+
+use lrvm::board::{BoardRequest, Bus, ResetKind, ShutdownReason};
+use lrvm_tools::{
+    exceptions::AuxHwException,
+    metadata::{DeviceMetadata, PowerType},
+};
+
+/// Command: shut the machine down with the exit code
+pub const PWR_SHUTDOWN: u32 = 0x01;
+/// Command: reboot the machine (the reset reason is `ResetKind::Hardware`)
+pub const PWR_REBOOT: u32 = 0x02;
+/// Command: shut the machine down, telling the host the guest's test suite passed
+pub const PWR_TEST_PASS: u32 = 0x03;
+/// Command: shut the machine down, telling the host the guest's test suite failed with the exit code as failure code
+pub const PWR_TEST_FAIL: u32 = 0x04;
+
+/// The power controller is a 2-word-long component allowing the guest to end the VM with a status, for instance
+/// to report the result of a test suite to the host:
+///
+/// * Word 1: exit code, used by the next `PWR_SHUTDOWN` or `PWR_TEST_FAIL` command
+/// * Word 2: command (write-only), `PWR_SHUTDOWN`, `PWR_REBOOT`, `PWR_TEST_PASS` or `PWR_TEST_FAIL`
+///
+/// Commands take effect at the beginning of the next CPU cycle. Shutting down halts the CPU, with a [`ShutdownReason`]
+/// the host can retrieve through [`Cpu::shutdown_reason`](lrvm::cpu::Cpu::shutdown_reason) or the `shutdown` field of
+/// the state returned by [`run_vm`](lrvm_tools::debug::run_vm). Unknown commands raise an unknown operation exception.
+pub struct PowerControl {
+    exit_code: u32,
+    request: Option<BoardRequest>,
+    hw_id: u64,
+}
+
+impl PowerControl {
+    /// Create a power controller
+    pub fn new(hw_id: u64) -> Self {
+        Self {
+            exit_code: 0,
+            request: None,
+            hw_id,
+        }
+    }
+}
+
+impl Bus for PowerControl {
+    fn name(&self) -> &'static str {
+        "Power Controller"
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        DeviceMetadata::new(self.hw_id, 2 * 4, PowerType::Controller.into(), None, None).encode()
+    }
+
+    fn read(&mut self, addr: u32, ex: &mut u16) -> u32 {
+        if addr == 0x04 {
+            *ex = AuxHwException::MemoryNotReadable.into();
+            return 0;
+        }
+
+        self.exit_code
+    }
+
+    fn write(&mut self, addr: u32, word: u32, ex: &mut u16) {
+        if addr == 0x00 {
+            self.exit_code = word;
+            return;
+        }
+
+        self.request = Some(match word {
+            PWR_SHUTDOWN => BoardRequest::Shutdown(ShutdownReason::Exit(self.exit_code)),
+            PWR_REBOOT => BoardRequest::Reset,
+            PWR_TEST_PASS => BoardRequest::Shutdown(ShutdownReason::TestPassed),
+            PWR_TEST_FAIL => BoardRequest::Shutdown(ShutdownReason::TestFailed(self.exit_code)),
+            _ => {
+                *ex = AuxHwException::UnknownOperation(word as u8).into();
+                return;
+            }
+        });
+    }
+
+    fn reset(&mut self, _kind: ResetKind) {
+        self.exit_code = 0;
+        self.request = None;
+    }
+
+    fn peek(&self, addr: u32) -> Option<u32> {
+        Some(if addr == 0x00 { self.exit_code } else { 0 })
+    }
+
+    fn ticks(&self) -> bool {
+        true
+    }
+
+    fn request(&mut self) -> Option<BoardRequest> {
+        self.request.take()
+    }
+}
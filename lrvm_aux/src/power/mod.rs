@@ -0,0 +1,3 @@
+mod control;
+
+pub use self::control::*;
@@ -0,0 +1,765 @@
+//! Registration of the auxiliary components for declarative machine descriptions.
+//! See [`registry`] for more details.
+
+use std::{
+    fs,
+    io::{stdin, BufRead, Read, Write},
+    path::Path,
+    thread,
+    time::Duration,
+};
+
+use lrvm::board::Bus;
+use lrvm_tools::{
+    bytes::bytes_to_words,
+    config::{
+        BuildContext, ComponentConfig, ComponentRegistry, InputTarget, NoOptions, OutputTarget,
+    },
+};
+use serde::Deserialize;
+
+use crate::{
+    audio::{Pcm, WavFileBackend},
+    debug::BasicDebug,
+    display::{
+        AnsiTerminalBackend, BufferedDisplay, CharDisplay, Framebuffer, FramebufferBackend,
+        ImageFileBackend, ImageFormat, NumberDisplay, PixelFormat, TerminalBackend, TextDisplay,
+    },
+    entropy::Rng,
+    keyboard::{
+        AsyncKeyboard, EndOfInput, InputSource, KeyboardProducer, SyncCharKeyboard,
+        SyncLineKeyboard,
+    },
+    network::{local_mac, Nic, NicBackend},
+    power::PowerControl,
+    serial::{StdioBackend, Uart, UartBackend},
+    storage::{
+        BlockBackend, BlockDevice, BootRom, FileBackedMem, FileBlockBackend, HostFs,
+        MemoryBlockBackend, PersistentMem,
+    },
+    time::{IntervalTimer, RealtimeClock, Watchdog, WDT_HALT, WDT_INTERRUPT, WDT_RESET},
+    volatile_mem::{PoisonAction, Ram, SparseRam},
+};
+
+/// Get a registry containing all the auxiliary components of this crate, to build machines described with
+/// [`MachineConfig`](lrvm_tools::config::MachineConfig).
+///
+/// | Type                 | Component            | Fields                                                  |
+/// | -------------------- | -------------------- | ------------------------------------------------------- |
+/// | `bootrom`            | [`BootRom`]          | `size`, `boot` or `path` (binary file)                  |
+/// | `ram`                | [`Ram`]              | `size` (required), `boot` or `poison`                   |
+/// | `sparse_ram`         | [`SparseRam`]        | `size` (required), `resident_cap` (in bytes)            |
+/// | `persistent_mem`     | [`PersistentMem`]    | `size` (required), `boot`                               |
+/// | `file_backed_mem`    | [`FileBackedMem`]    | `path` (required), `size`, `writable` (default: `false`) |
+/// | `block_device`       | [`BlockDevice`]      | `path` or `size`, `writable` (default: `false`)         |
+/// | `host_fs`            | [`HostFs`]           | `path` (directory, required), `writable`                |
+/// | `buffered_display`   | [`BufferedDisplay`]  | `size` (required), `output` (default: `stdout`)         |
+/// | `char_display`       | [`CharDisplay`]      | `output` (default: `stdout`)                            |
+/// | `number_display`     | [`NumberDisplay`]    | `output` (default: `stdout`)                            |
+/// | `framebuffer`        | [`Framebuffer`]      | `width`, `height` (required), `format`, `path`          |
+/// | `text_display`       | [`TextDisplay`]      | `width` and `height` (default: 80x25), `output`         |
+/// | `pcm_audio`          | [`Pcm`]              | `path` (WAV file, required)                             |
+/// | `sync_char_keyboard` | [`SyncCharKeyboard`] | `input` (default: `stdin`), `on_eof`                    |
+/// | `sync_line_keyboard` | [`SyncLineKeyboard`] | `size` (required), `input` (default: `stdin`), `on_eof` |
+/// | `async_keyboard`     | [`AsyncKeyboard`]    | `size` (in events), `input` (default: `stdin`)          |
+/// | `realtime_clock`     | [`RealtimeClock`]    |                                                         |
+/// | `interval_timer`     | [`IntervalTimer`]    |                                                         |
+/// | `watchdog`           | [`Watchdog`]         | `timeout` (armed at boot if set), `action`              |
+/// | `power_control`      | [`PowerControl`]     |                                                         |
+/// | `rng`                | [`Rng`]              | `seed` (deterministic mode, default: host entropy)      |
+/// | `uart`               | [`Uart`]             | `path` (Unix socket, default: stdin/stdout in raw mode) |
+/// | `nic`                | [`Nic`]              | `path` and `peer` (Unix sockets, required), `mac`       |
+/// | `basic_debug`        | [`BasicDebug`]       | `output` (default: `stdout`)                            |
+///
+/// Each type parses its own options, so unknown options and invalid values are reported when the description is validated.
+///
+/// The RAM's `poison` detects reads of words that were never written, either raising an exception (`exception`) or printing
+/// a warning on the standard error output (`warn`). See [`Ram::poison`].
+///
+/// The file-backed memory can also use a copy-on-write `overlay`, either `memory` or the path of a delta file, in which case its
+/// file is never modified (see [`FileBackedMem::copy_on_write`]).
+///
+/// The block device uses a file if a `path` is provided, and an in-memory storage of `size` bytes otherwise.
+///
+/// The host filesystem is restricted to the `path` directory, and is read-only unless `writable` is `true`.
+///
+/// The framebuffer's `format` is either `rgb565` or `rgba8888` (default). It writes its frames to image files if a `path`
+/// is provided (`.ppm` or `.png`, with `{}` replaced by the frame's number), and draws them in the terminal otherwise.
+///
+/// The PCM audio component writes each playback to a WAV file, with `{}` in the `path` replaced by the playback's number.
+///
+/// The synchronous keyboards' `on_eof` tells what happens when their input is exhausted: `marker` (default) provides
+/// an empty input, `exception` raises an exception and `halt` halts the CPU (see [`EndOfInput`]).
+///
+/// The asynchronous keyboard reads the standard input from a background thread, and types other inputs by itself
+/// (see [`AsyncKeyboard::with_input`]).
+///
+/// The watchdog's `action` is either `reset` (default), `halt` or `interrupt`.
+///
+/// The NIC binds a datagram socket at `path` and sends its packets to the one at `peer`. Its `mac` is written as
+/// `aa:bb:cc:dd:ee:ff`, and is derived from the component's hardware identifier if it is not provided.
+pub fn registry() -> ComponentRegistry {
+    let mut registry = ComponentRegistry::new();
+
+    registry
+        .register(
+            "bootrom",
+            &["size", "boot"],
+            |config, options: PathOptions, ctx| {
+                let storage = match (config.boot == Some(true), &options.path) {
+                    (true, None) => boot_image(ctx)?,
+                    (false, Some(path)) => read_binary(ctx, path)?,
+                    (true, Some(_)) => {
+                        return Err("'boot' and 'path' cannot be used together".to_string())
+                    }
+                    (false, None) => return Err("either 'boot' or 'path' is required".to_string()),
+                };
+
+                let rom = match config.size {
+                    Some(size) => BootRom::with_size(storage, size, ctx.hw_id),
+                    None => BootRom::new(storage, ctx.hw_id),
+                };
+
+                boxed(rom)
+            },
+        )
+        .register(
+            "ram",
+            &["size", "boot"],
+            |config, options: RamOptions, ctx| {
+                let size = config.require_size()?;
+
+                let Some(poison) = options.poison else {
+                    return boxed(match config.boot {
+                        Some(true) => Ram::from_with_size(boot_image(ctx)?, size, ctx.hw_id),
+                        _ => Ram::new(size, ctx.hw_id),
+                    });
+                };
+
+                if config.boot == Some(true) {
+                    return Err("'poison' cannot be used with 'boot'".to_string());
+                }
+
+                let action = match poison {
+                    Poison::Exception => PoisonAction::Exception,
+                    Poison::Warn => PoisonAction::Report(Box::new(|offset| {
+                        eprintln!(
+                            "Warning: read of a never-written RAM word at offset {:#010X}",
+                            offset
+                        )
+                    })),
+                };
+
+                let mut ram = Ram::new(size, ctx.hw_id)?;
+                ram.poison(action);
+                Ok(Box::new(ram))
+            },
+        )
+        .register(
+            "sparse_ram",
+            &["size"],
+            |config, options: SparseRamOptions, ctx| {
+                let size = config.require_size()?;
+
+                boxed(match options.resident_cap {
+                    Some(resident_cap) => {
+                        SparseRam::with_resident_cap(size, resident_cap, ctx.hw_id)
+                    }
+                    None => SparseRam::new(size, ctx.hw_id),
+                })
+            },
+        )
+        .register(
+            "persistent_mem",
+            &["size", "boot"],
+            |config, _: NoOptions, ctx| {
+                let size = config.require_size()?;
+
+                boxed(match config.boot {
+                    Some(true) => PersistentMem::from_with_size(boot_image(ctx)?, size, ctx.hw_id),
+                    _ => PersistentMem::new(size, ctx.hw_id),
+                })
+            },
+        )
+        .register(
+            "file_backed_mem",
+            &["size"],
+            |config, options: FileBackedMemOptions, ctx| {
+                let path = ctx.resolve(&options.path);
+
+                if let Some(overlay) = &options.overlay {
+                    if config.size.is_some() || options.writable.is_some() {
+                        return Err(
+                            "'overlay' cannot be used with 'size' or 'writable'".to_string()
+                        );
+                    }
+
+                    let mem = match overlay.as_str() {
+                        "memory" => FileBackedMem::copy_on_write(&path, ctx.hw_id),
+                        delta => FileBackedMem::copy_on_write_with_delta_file(
+                            &path,
+                            ctx.resolve(delta),
+                            ctx.hw_id,
+                        ),
+                    };
+
+                    return mem
+                        .map(|mem| Box::new(mem) as Box<dyn Bus>)
+                        .map_err(|err| format!("failed to open '{}': {}", path.display(), err));
+                }
+
+                let mem = match (options.writable.unwrap_or(false), config.size) {
+                    (true, Some(size)) => FileBackedMem::writable_with_size(&path, size, ctx.hw_id),
+                    (true, None) => FileBackedMem::writable(&path, ctx.hw_id),
+                    (false, Some(size)) => {
+                        FileBackedMem::readonly_with_size(&path, size, ctx.hw_id)
+                    }
+                    (false, None) => FileBackedMem::readonly(&path, ctx.hw_id),
+                };
+
+                mem.map(|mem| Box::new(mem) as Box<dyn Bus>)
+                    .map_err(|err| format!("failed to open '{}': {}", path.display(), err))
+            },
+        )
+        .register("host_fs", &[], |_, options: HostFsOptions, ctx| {
+            let path = ctx.resolve(&options.path);
+
+            HostFs::new(&path, options.writable, ctx.hw_id)
+                .map(|fs| Box::new(fs) as Box<dyn Bus>)
+                .map_err(|err| format!("failed to open '{}': {}", path.display(), err))
+        })
+        .register(
+            "block_device",
+            &["size"],
+            |config, options: BlockDeviceOptions, ctx| {
+                let backend = match (&options.path, config.size) {
+                    (Some(path), None) => {
+                        let path = ctx.resolve(path);
+
+                        FileBlockBackend::open(&path, options.writable.unwrap_or(false))
+                            .map(|backend| Box::new(backend) as Box<dyn BlockBackend>)
+                            .map_err(|err| {
+                                format!("failed to open '{}': {}", path.display(), err)
+                            })?
+                    }
+                    (None, Some(size)) => {
+                        if options.writable == Some(false) {
+                            return Err("in-memory block devices cannot be read-only".to_string());
+                        }
+
+                        Box::new(MemoryBlockBackend::new(size as usize))
+                    }
+                    (Some(_), Some(_)) => {
+                        return Err("'path' and 'size' cannot be used together".to_string())
+                    }
+                    (None, None) => return Err("either 'path' or 'size' is required".to_string()),
+                };
+
+                Ok(Box::new(BlockDevice::new(backend, ctx.hw_id)))
+            },
+        )
+        .register(
+            "buffered_display",
+            &["size", "output"],
+            |config, options: OutputOptions, ctx| {
+                let mut output = output(config, options.output.as_deref(), ctx)?;
+
+                boxed(BufferedDisplay::new(
+                    config.require_size()?,
+                    Box::new(move |message| {
+                        let message = match message {
+                            Ok(message) => message.to_string(),
+                            Err((_, bytes)) => String::from_utf8_lossy(bytes).into_owned(),
+                        };
+
+                        emit(&mut output, &message);
+                    }),
+                    ctx.hw_id,
+                ))
+            },
+        )
+        .register(
+            "char_display",
+            &["output"],
+            |config, options: OutputOptions, ctx| {
+                let mut output = output(config, options.output.as_deref(), ctx)?;
+
+                Ok(Box::new(CharDisplay::new(
+                    Box::new(move |result| emit(&mut output, &result.unwrap_or('�').to_string())),
+                    ctx.hw_id,
+                )))
+            },
+        )
+        .register(
+            "number_display",
+            &["output"],
+            |config, options: OutputOptions, ctx| {
+                let mut output = output(config, options.output.as_deref(), ctx)?;
+
+                Ok(Box::new(NumberDisplay::new(
+                    Box::new(move |num, format, newline| {
+                        let newline = if newline { "\n" } else { "" };
+                        emit(&mut output, &format!("{}{}", format.format(num), newline))
+                    }),
+                    ctx.hw_id,
+                )))
+            },
+        )
+        .register("framebuffer", &[], |_, options: FramebufferOptions, ctx| {
+            let format = match &options.format {
+                Some(name) => PixelFormat::from_name(name)
+                    .ok_or_else(|| format!("unknown pixel format '{}'", name))?,
+                None => PixelFormat::Rgba8888,
+            };
+
+            let backend: Box<dyn FramebufferBackend> = match &options.path {
+                Some(path) => {
+                    let image_format = ImageFormat::from_extension(path).ok_or_else(|| {
+                        format!("'{}' is neither a '.ppm' nor a '.png' file", path)
+                    })?;
+
+                    let path = ctx.resolve(path);
+                    Box::new(ImageFileBackend::new(path.to_string_lossy(), image_format))
+                }
+                None => Box::new(TerminalBackend::new()),
+            };
+
+            boxed(Framebuffer::new(
+                options.width,
+                options.height,
+                format,
+                backend,
+                ctx.hw_id,
+            ))
+        })
+        .register("pcm_audio", &[], |_, options: RequiredPathOptions, ctx| {
+            let path = ctx.resolve(&options.path);
+
+            Ok(Box::new(Pcm::new(
+                Box::new(WavFileBackend::new(path.to_string_lossy())),
+                ctx.hw_id,
+            )))
+        })
+        .register(
+            "text_display",
+            &["output"],
+            |config, options: TextDisplayOptions, ctx| {
+                let output = output(config, options.output.as_deref(), ctx)?;
+
+                boxed(TextDisplay::new(
+                    options.width.unwrap_or(80),
+                    options.height.unwrap_or(25),
+                    Box::new(AnsiTerminalBackend::with_output(output)),
+                    ctx.hw_id,
+                ))
+            },
+        )
+        .register(
+            "sync_char_keyboard",
+            &["input"],
+            |config, options: SyncKeyboardOptions, ctx| {
+                Ok(Box::new(SyncCharKeyboard::with_source(
+                    input_source(config, &options, ctx)?,
+                    ctx.hw_id,
+                )))
+            },
+        )
+        .register(
+            "sync_line_keyboard",
+            &["size", "input"],
+            |config, options: SyncKeyboardOptions, ctx| {
+                boxed(SyncLineKeyboard::with_source(
+                    config.require_size()?,
+                    input_source(config, &options, ctx)?,
+                    ctx.hw_id,
+                ))
+            },
+        )
+        .register(
+            "async_keyboard",
+            &["size", "input"],
+            |config, options: InputOptions, ctx| {
+                let keyboard = match config.size {
+                    Some(size) => AsyncKeyboard::with_queue_size(size as usize, ctx.hw_id)?,
+                    None => AsyncKeyboard::new(ctx.hw_id),
+                };
+
+                // The standard input is read from a background thread as it blocks,
+                // other inputs are read right away and typed by the keyboard itself
+                let input = match input_target(config, options.input.as_deref(), ctx)? {
+                    InputTarget::Stdin => {
+                        let producer = keyboard.producer();
+
+                        thread::spawn(move || {
+                            for line in stdin().lock().lines() {
+                                match line {
+                                    Ok(line) => type_paced(&producer, &format!("{}\n", line)),
+                                    Err(_) => break,
+                                }
+                            }
+                        });
+
+                        return Ok(Box::new(keyboard));
+                    }
+                    InputTarget::Script(script) => InputSource::from_script(script),
+                    target => {
+                        let mut text = String::new();
+                        target
+                            .open()?
+                            .read_to_string(&mut text)
+                            .map_err(|err| format!("failed to read keyboard input: {}", err))?;
+
+                        InputSource::from_text(text)
+                    }
+                };
+
+                Ok(Box::new(keyboard.with_input(input)))
+            },
+        )
+        .register("realtime_clock", &[], |_, _: NoOptions, ctx| {
+            Ok(Box::new(RealtimeClock::new(ctx.hw_id)))
+        })
+        .register("power_control", &[], |_, _: NoOptions, ctx| {
+            Ok(Box::new(PowerControl::new(ctx.hw_id)))
+        })
+        .register("watchdog", &[], |_, options: WatchdogOptions, ctx| {
+            let action = match options.action {
+                WatchdogAction::Reset => WDT_RESET,
+                WatchdogAction::Halt => WDT_HALT,
+                WatchdogAction::Interrupt => WDT_INTERRUPT,
+            };
+
+            Ok(Box::new(match options.timeout {
+                Some(timeout) => Watchdog::armed(timeout, action, ctx.hw_id),
+                None => Watchdog::new(ctx.hw_id),
+            }))
+        })
+        .register("interval_timer", &[], |_, _: NoOptions, ctx| {
+            Ok(Box::new(IntervalTimer::new(ctx.hw_id)))
+        })
+        .register("rng", &[], |_, options: RngOptions, ctx| {
+            Ok(Box::new(match options.seed {
+                Some(seed) => Rng::seeded(seed, ctx.hw_id),
+                None => Rng::host_entropy(ctx.hw_id),
+            }))
+        })
+        .register("uart", &[], |_, options: PathOptions, ctx| {
+            let backend: Box<dyn UartBackend> = match &options.path {
+                Some(path) => unix_socket(&ctx.resolve(path))?,
+                None => Box::new(StdioBackend::raw()),
+            };
+
+            Ok(Box::new(Uart::new(backend, ctx.hw_id)))
+        })
+        .register("nic", &[], |_, options: NicOptions, ctx| {
+            let (path, peer) = (ctx.resolve(&options.path), ctx.resolve(&options.peer));
+
+            let mac = match &options.mac {
+                Some(mac) => parse_mac(mac)?,
+                None => local_mac(ctx.hw_id),
+            };
+
+            Ok(Box::new(Nic::new(
+                datagram_socket(&path, &peer)?,
+                mac,
+                ctx.hw_id,
+            )))
+        })
+        .register(
+            "basic_debug",
+            &["output"],
+            |config, options: OutputOptions, ctx| {
+                let mut output = output(config, options.output.as_deref(), ctx)?;
+
+                Ok(Box::new(BasicDebug::new(
+                    ctx.hw_id,
+                    Box::new(move |info| emit(&mut output, &format!("[debug:basic] {}\n", info))),
+                )))
+            },
+        );
+
+    registry
+}
+
+/// Options of the types with an optional file path
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PathOptions {
+    path: Option<String>,
+}
+
+/// Options of the types with a required file path
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RequiredPathOptions {
+    path: String,
+}
+
+/// Options of the `ram` type
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RamOptions {
+    poison: Option<Poison>,
+}
+
+/// Detection of uninitialized reads in a RAM
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Poison {
+    Exception,
+    Warn,
+}
+
+/// Options of the `sparse_ram` type
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SparseRamOptions {
+    resident_cap: Option<u32>,
+}
+
+/// Options of the `file_backed_mem` type
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileBackedMemOptions {
+    path: String,
+    writable: Option<bool>,
+    overlay: Option<String>,
+}
+
+/// Options of the `host_fs` type
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct HostFsOptions {
+    path: String,
+    #[serde(default)]
+    writable: bool,
+}
+
+/// Options of the `block_device` type
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BlockDeviceOptions {
+    path: Option<String>,
+    writable: Option<bool>,
+}
+
+/// Options of the types writing to an output
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OutputOptions {
+    output: Option<String>,
+}
+
+/// Options of the `framebuffer` type
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FramebufferOptions {
+    width: u32,
+    height: u32,
+    format: Option<String>,
+    path: Option<String>,
+}
+
+/// Options of the `text_display` type
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TextDisplayOptions {
+    width: Option<u32>,
+    height: Option<u32>,
+    output: Option<String>,
+}
+
+/// Options of the types reading from an input
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct InputOptions {
+    input: Option<String>,
+}
+
+/// Options of the synchronous keyboard types
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SyncKeyboardOptions {
+    input: Option<String>,
+    #[serde(default)]
+    on_eof: OnEof,
+}
+
+/// Behaviour of a synchronous keyboard at the end of its input
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+enum OnEof {
+    #[default]
+    Marker,
+    Exception,
+    Halt,
+}
+
+/// Options of the `watchdog` type
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WatchdogOptions {
+    timeout: Option<u32>,
+    #[serde(default)]
+    action: WatchdogAction,
+}
+
+/// Action of a watchdog on timeout
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+enum WatchdogAction {
+    #[default]
+    Reset,
+    Halt,
+    Interrupt,
+}
+
+/// Options of the `rng` type
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RngOptions {
+    seed: Option<u32>,
+}
+
+/// Options of the `nic` type
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NicOptions {
+    path: String,
+    peer: String,
+    mac: Option<String>,
+}
+
+/// Write to an output, ignoring failures so the VM keeps running
+fn emit(output: &mut dyn Write, text: &str) {
+    let _ = output.write_all(text.as_bytes());
+    let _ = output.flush();
+}
+
+/// Box a component, converting its creation error
+fn boxed(component: Result<impl Bus + 'static, &'static str>) -> Result<Box<dyn Bus>, String> {
+    component
+        .map(|component| Box::new(component) as Box<dyn Bus>)
+        .map_err(str::to_string)
+}
+
+/// Get the machine's boot image
+fn boot_image(ctx: &BuildContext) -> Result<Vec<u32>, String> {
+    ctx.boot_image
+        .map(<[u32]>::to_vec)
+        .ok_or_else(|| "machine has no boot image".to_string())
+}
+
+/// Read a binary file as a list of words
+fn read_binary(ctx: &BuildContext, path: &str) -> Result<Vec<u32>, String> {
+    let path = ctx.resolve(path);
+    let bytes =
+        fs::read(&path).map_err(|err| format!("failed to read '{}': {}", path.display(), err))?;
+
+    if bytes.len() % 4 != 0 {
+        return Err(format!(
+            "size of '{}' is not a multiple of 4 bytes",
+            path.display()
+        ));
+    }
+
+    Ok(bytes_to_words(bytes))
+}
+
+/// Open a component's output target
+fn output(
+    config: &ComponentConfig,
+    output: Option<&str>,
+    ctx: &BuildContext,
+) -> Result<Box<dyn Write>, String> {
+    match &config.output_target {
+        Some(target) => target.open(),
+        None => OutputTarget::parse(output.unwrap_or("stdout"), ctx.base_dir)?.open(),
+    }
+}
+
+/// Get a component's input target
+fn input_target(
+    config: &ComponentConfig,
+    input: Option<&str>,
+    ctx: &BuildContext,
+) -> Result<InputTarget, String> {
+    match &config.input_target {
+        Some(target) => Ok(target.clone()),
+        None => InputTarget::parse(input.unwrap_or("stdin"), ctx.base_dir),
+    }
+}
+
+/// Get a synchronous keyboard's input source
+fn input_source(
+    config: &ComponentConfig,
+    options: &SyncKeyboardOptions,
+    ctx: &BuildContext,
+) -> Result<InputSource, String> {
+    let end = match options.on_eof {
+        OnEof::Marker => EndOfInput::default(),
+        OnEof::Exception => EndOfInput::Exception,
+        OnEof::Halt => EndOfInput::Halt,
+    };
+
+    let source = match input_target(config, options.input.as_deref(), ctx)? {
+        InputTarget::Script(script) => InputSource::from_script(script),
+        input => InputSource::from_reader(input.open()?),
+    };
+
+    Ok(source.on_end(end))
+}
+
+/// Type a text on an asynchronous keyboard, waiting for room in its queue instead of losing events
+fn type_paced(producer: &KeyboardProducer, text: &str) {
+    let mut buf = [0; 2];
+
+    for c in text.chars() {
+        while !producer.has_room() {
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        // A press and a release event are pushed for each character
+        producer.type_text(c.encode_utf8(&mut buf));
+    }
+}
+
+/// Connect to a Unix domain socket
+#[cfg(unix)]
+fn unix_socket(path: &Path) -> Result<Box<dyn UartBackend>, String> {
+    crate::serial::UnixSocketBackend::connect(path)
+        .map(|backend| Box::new(backend) as Box<dyn UartBackend>)
+        .map_err(|err| format!("failed to connect to '{}': {}", path.display(), err))
+}
+
+/// Connect to a Unix domain socket (unsupported on this platform)
+#[cfg(not(unix))]
+fn unix_socket(_: &Path) -> Result<Box<dyn UartBackend>, String> {
+    Err("Unix domain sockets are not supported on this platform".to_string())
+}
+
+/// Bind a Unix datagram socket exchanging packets with a peer socket
+#[cfg(unix)]
+fn datagram_socket(path: &Path, peer: &Path) -> Result<Box<dyn NicBackend>, String> {
+    crate::network::UnixDatagramBackend::bind(path, peer)
+        .map(|backend| Box::new(backend) as Box<dyn NicBackend>)
+        .map_err(|err| format!("failed to bind '{}': {}", path.display(), err))
+}
+
+/// Bind a Unix datagram socket (unsupported on this platform)
+#[cfg(not(unix))]
+fn datagram_socket(_: &Path, _: &Path) -> Result<Box<dyn NicBackend>, String> {
+    Err("Unix domain sockets are not supported on this platform".to_string())
+}
+
+/// Parse a hardware address written as `aa:bb:cc:dd:ee:ff`
+fn parse_mac(mac: &str) -> Result<[u8; 6], String> {
+    let err = || format!("invalid hardware address '{}'", mac);
+
+    let bytes = mac
+        .split(':')
+        .map(|byte| u8::from_str_radix(byte, 16).map_err(|_| err()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    bytes.try_into().map_err(|_| err())
+}
@@ -0,0 +1,312 @@
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    io::{stdin, stdout, IsTerminal, Read, Write},
+    process::{Command, Stdio},
+    rc::Rc,
+    sync::mpsc::{channel, Receiver, Sender},
+    thread,
+};
+
+#[cfg(unix)]
+use std::{
+    io::{ErrorKind, Result as IOResult},
+    os::unix::net::{UnixListener, UnixStream},
+    path::Path,
+};
+
+/// Host side of a [`Uart`](super::Uart), sending and receiving the bytes of the serial line
+pub trait UartBackend {
+    /// Transmit a byte sent by the guest
+    fn transmit(&mut self, byte: u8);
+
+    /// Get the next byte to send to the guest, if any.
+    /// This is called on CPU cycles, so it must never block.
+    fn receive(&mut self) -> Option<u8>;
+
+    /// Check if the backend keeps its bytes until the UART has room to receive them (default: `true`).
+    /// Without flow control, a byte is received on each cycle even if the receive FIFO is full, in which case it is lost.
+    fn flow_control(&self) -> bool {
+        true
+    }
+}
+
+/// Backend connected to the host's standard input and output.
+///
+/// Standard input is read from a background thread, which stays blocked on it until the process exits.
+pub struct StdioBackend {
+    input: Receiver<u8>,
+    _raw_mode: Option<RawMode>,
+}
+
+impl StdioBackend {
+    /// Create a backend using the terminal as-is (input is only received after each newline, and echoed by the terminal)
+    pub fn new() -> Self {
+        Self::open(None)
+    }
+
+    /// Create a backend switching the terminal to raw mode, so each key is received as soon as it is pressed and is not echoed.
+    /// The terminal is restored when the backend is dropped.
+    ///
+    /// If the standard input is not a terminal (or on platforms not supporting it), the terminal is used as-is.
+    pub fn raw() -> Self {
+        Self::open(RawMode::enable())
+    }
+
+    fn open(raw_mode: Option<RawMode>) -> Self {
+        let (sender, input) = channel();
+
+        thread::spawn(move || {
+            for byte in stdin().lock().bytes() {
+                match byte {
+                    Ok(byte) if sender.send(byte).is_ok() => {}
+                    _ => break,
+                }
+            }
+        });
+
+        Self {
+            input,
+            _raw_mode: raw_mode,
+        }
+    }
+}
+
+impl Default for StdioBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UartBackend for StdioBackend {
+    fn transmit(&mut self, byte: u8) {
+        let mut stdout = stdout();
+        let _ = stdout.write_all(&[byte]);
+        let _ = stdout.flush();
+    }
+
+    fn receive(&mut self) -> Option<u8> {
+        self.input.try_recv().ok()
+    }
+}
+
+/// (Internal) Terminal switched to raw mode, restored when dropped
+struct RawMode {
+    saved: String,
+}
+
+impl RawMode {
+    fn enable() -> Option<Self> {
+        if !cfg!(unix) || !stdin().is_terminal() {
+            return None;
+        }
+
+        let saved = Command::new("stty")
+            .arg("-g")
+            .stdin(Stdio::inherit())
+            .output()
+            .ok()
+            .filter(|output| output.status.success())?;
+
+        let enabled = Command::new("stty")
+            .args(["raw", "-echo"])
+            .stdin(Stdio::inherit())
+            .status()
+            .is_ok_and(|status| status.success());
+
+        if enabled {
+            Some(Self {
+                saved: String::from_utf8_lossy(&saved.stdout).trim().to_string(),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = Command::new("stty")
+            .arg(&self.saved)
+            .stdin(Stdio::inherit())
+            .status();
+    }
+}
+
+/// In-memory backend, shared between the UART and the code feeding it and reading its output (e.g. tests).
+/// Clones of a pipe all share the same buffers.
+#[derive(Debug, Clone, Default)]
+pub struct PipeBackend(Rc<RefCell<PipeBuffers>>);
+
+#[derive(Debug, Default)]
+struct PipeBuffers {
+    to_guest: VecDeque<u8>,
+    from_guest: Vec<u8>,
+    lossy: bool,
+}
+
+impl PipeBackend {
+    /// Create an empty pipe
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty pipe without flow control, which sends a byte to the UART on each cycle like a serial line
+    /// would, even if it has no room for it (see [`UartBackend::flow_control`])
+    pub fn without_flow_control() -> Self {
+        let pipe = Self::default();
+        pipe.0.borrow_mut().lossy = true;
+        pipe
+    }
+
+    /// Queue bytes for the guest to receive
+    pub fn send(&self, bytes: &[u8]) {
+        self.0.borrow_mut().to_guest.extend(bytes);
+    }
+
+    /// Get the number of queued bytes the UART did not receive yet
+    pub fn pending(&self) -> usize {
+        self.0.borrow().to_guest.len()
+    }
+
+    /// Get the bytes transmitted by the guest
+    pub fn output(&self) -> Vec<u8> {
+        self.0.borrow().from_guest.clone()
+    }
+
+    /// Get the bytes transmitted by the guest and clear them
+    pub fn take_output(&self) -> Vec<u8> {
+        std::mem::take(&mut self.0.borrow_mut().from_guest)
+    }
+}
+
+impl UartBackend for PipeBackend {
+    fn transmit(&mut self, byte: u8) {
+        self.0.borrow_mut().from_guest.push(byte);
+    }
+
+    fn receive(&mut self) -> Option<u8> {
+        self.0.borrow_mut().to_guest.pop_front()
+    }
+
+    fn flow_control(&self) -> bool {
+        !self.0.borrow().lossy
+    }
+}
+
+/// Backend linking two UARTs together, whether they are in the same VM or in VMs running on different threads:
+/// the bytes transmitted by each side are received by the other one.
+pub struct LinkBackend {
+    sender: Sender<u8>,
+    receiver: Receiver<u8>,
+}
+
+impl LinkBackend {
+    /// Create both ends of a link
+    pub fn pair() -> (Self, Self) {
+        let (sender_a, receiver_b) = channel();
+        let (sender_b, receiver_a) = channel();
+
+        (
+            Self {
+                sender: sender_a,
+                receiver: receiver_a,
+            },
+            Self {
+                sender: sender_b,
+                receiver: receiver_b,
+            },
+        )
+    }
+}
+
+impl UartBackend for LinkBackend {
+    fn transmit(&mut self, byte: u8) {
+        // Bytes are lost if the other side was dropped, like on a disconnected serial line
+        let _ = self.sender.send(byte);
+    }
+
+    fn receive(&mut self) -> Option<u8> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+/// Backend connected to a Unix domain socket, either as a client or as a server accepting a single client at a time.
+///
+/// Bytes transmitted while no client is connected are lost.
+#[cfg(unix)]
+pub struct UnixSocketBackend {
+    listener: Option<UnixListener>,
+    stream: Option<UnixStream>,
+}
+
+#[cfg(unix)]
+impl UnixSocketBackend {
+    /// Connect to an existing socket
+    pub fn connect(path: impl AsRef<Path>) -> IOResult<Self> {
+        let stream = UnixStream::connect(path)?;
+        stream.set_nonblocking(true)?;
+
+        Ok(Self {
+            listener: None,
+            stream: Some(stream),
+        })
+    }
+
+    /// Create a socket and wait for clients to connect to it (without blocking)
+    pub fn listen(path: impl AsRef<Path>) -> IOResult<Self> {
+        let listener = UnixListener::bind(path)?;
+        listener.set_nonblocking(true)?;
+
+        Ok(Self {
+            listener: Some(listener),
+            stream: None,
+        })
+    }
+
+    /// (Internal) Get the connected stream, accepting a new client if there is none
+    fn stream(&mut self) -> Option<&mut UnixStream> {
+        if self.stream.is_none() {
+            let (stream, _) = self.listener.as_ref()?.accept().ok()?;
+            stream.set_nonblocking(true).ok()?;
+            self.stream = Some(stream);
+        }
+
+        self.stream.as_mut()
+    }
+}
+
+#[cfg(unix)]
+impl UartBackend for UnixSocketBackend {
+    fn transmit(&mut self, byte: u8) {
+        let Some(stream) = self.stream() else {
+            return;
+        };
+
+        loop {
+            match stream.write(&[byte]) {
+                Ok(1) => return,
+                Err(err) if err.kind() == ErrorKind::WouldBlock => thread::yield_now(),
+                _ => break,
+            }
+        }
+
+        // The client disconnected
+        self.stream = None;
+    }
+
+    fn receive(&mut self) -> Option<u8> {
+        let stream = self.stream()?;
+        let mut byte = [0];
+
+        match stream.read(&mut byte) {
+            Ok(1) => Some(byte[0]),
+            Err(err) if err.kind() == ErrorKind::WouldBlock => None,
+            _ => {
+                // The client disconnected
+                self.stream = None;
+                None
+            }
+        }
+    }
+}
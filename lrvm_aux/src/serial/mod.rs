@@ -0,0 +1,4 @@
+mod backend;
+mod uart;
+
+pub use self::{backend::*, uart::*};
@@ -0,0 +1,194 @@
+use std::collections::VecDeque;
+
+use lrvm::board::{Bus, ResetKind};
+use lrvm_tools::metadata::{DeviceMetadata, SerialType};
+
+use super::UartBackend;
+
+/// Status flag: the receive FIFO contains at least one byte
+pub const UART_RX_READY: u32 = 0x01;
+/// Status flag: the transmit FIFO is empty
+pub const UART_TX_EMPTY: u32 = 0x02;
+/// Status flag: the transmit FIFO is full (written bytes are lost)
+pub const UART_TX_FULL: u32 = 0x04;
+/// Status flag: a byte was received while the receive FIFO was full, and was lost (only for backends without flow control)
+pub const UART_OVERRUN: u32 = 0x08;
+
+/// Control flag: raise a hardware interruption when a byte is received
+pub const UART_RX_INTERRUPT: u32 = 0x01;
+/// Control flag: raise a hardware interruption when the transmit FIFO was emptied
+pub const UART_TX_INTERRUPT: u32 = 0x02;
+
+/// Default size of the FIFOs, in bytes
+pub const UART_DEFAULT_FIFO_SIZE: usize = 16;
+
+/// The UART is a 4-word-long serial port, exchanging bytes with a host [`UartBackend`] through a receive and a transmit FIFO.
+///
+/// * Word 1: data ; reading it pops a byte from the receive FIFO (`0` if it is empty), writing it pushes a byte to the transmit FIFO
+/// * Word 2: status flags (`UART_RX_READY`, `UART_TX_EMPTY`, `UART_TX_FULL` and `UART_OVERRUN`) ; writing `UART_OVERRUN` to it clears this flag
+/// * Word 3: control flags (`UART_RX_INTERRUPT` and `UART_TX_INTERRUPT`)
+/// * Word 4: interruption code (only the weakest byte is kept)
+///
+/// On each CPU cycle, the whole transmit FIFO is sent to the backend, and at most one byte is received from it.
+/// Bytes are only received while the receive FIFO has room, so the backend keeps them until the guest reads the FIFO,
+/// unless it has no [flow control](UartBackend::flow_control).
+/// The size of the FIFOs is provided in the metadata's additional data.
+pub struct Uart {
+    backend: Box<dyn UartBackend>,
+    fifo_size: usize,
+    rx: VecDeque<u8>,
+    tx: VecDeque<u8>,
+    overrun: bool,
+    control: u32,
+    itr_code: u8,
+    hw_id: u64,
+}
+
+impl Uart {
+    /// Create a new UART with FIFOs of [`UART_DEFAULT_FIFO_SIZE`] bytes
+    pub fn new(backend: Box<dyn UartBackend>, hw_id: u64) -> Self {
+        Self::with_fifo_size(backend, UART_DEFAULT_FIFO_SIZE, hw_id).unwrap()
+    }
+
+    /// Create a new UART with FIFOs of the provided size, in bytes
+    /// Returns an error message if the size is 0.
+    pub fn with_fifo_size(
+        backend: Box<dyn UartBackend>,
+        fifo_size: usize,
+        hw_id: u64,
+    ) -> Result<Self, &'static str> {
+        if fifo_size == 0 {
+            return Err("UART's FIFO size cannot be 0");
+        }
+
+        Ok(Self {
+            backend,
+            fifo_size,
+            rx: VecDeque::with_capacity(fifo_size),
+            tx: VecDeque::with_capacity(fifo_size),
+            overrun: false,
+            control: 0,
+            itr_code: 0,
+            hw_id,
+        })
+    }
+
+    /// Get the status flags
+    fn status(&self) -> u32 {
+        let mut status = 0;
+
+        if !self.rx.is_empty() {
+            status |= UART_RX_READY;
+        }
+
+        if self.tx.is_empty() {
+            status |= UART_TX_EMPTY;
+        }
+
+        if self.tx.len() == self.fifo_size {
+            status |= UART_TX_FULL;
+        }
+
+        if self.overrun {
+            status |= UART_OVERRUN;
+        }
+
+        status
+    }
+}
+
+impl Bus for Uart {
+    fn name(&self) -> &'static str {
+        "UART"
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        DeviceMetadata::new(
+            self.hw_id,
+            16,
+            SerialType::Uart.into(),
+            None,
+            Some(self.fifo_size as u64),
+        )
+        .encode()
+    }
+
+    fn read(&mut self, addr: u32, _ex: &mut u16) -> u32 {
+        match addr {
+            0x00 => self.rx.pop_front().unwrap_or(0).into(),
+            _ => self.peek(addr).unwrap(),
+        }
+    }
+
+    fn write(&mut self, addr: u32, word: u32, _ex: &mut u16) {
+        match addr {
+            0x00 => {
+                if self.tx.len() < self.fifo_size {
+                    self.tx.push_back(word as u8);
+                }
+            }
+            0x04 => {
+                if word & UART_OVERRUN != 0 {
+                    self.overrun = false;
+                }
+            }
+            0x08 => self.control = word & (UART_RX_INTERRUPT | UART_TX_INTERRUPT),
+            0x0C => self.itr_code = word as u8,
+            _ => unreachable!(),
+        }
+    }
+
+    fn reset(&mut self, _kind: ResetKind) {
+        self.rx.clear();
+        self.tx.clear();
+        self.overrun = false;
+        self.control = 0;
+        self.itr_code = 0;
+    }
+
+    fn peek(&self, addr: u32) -> Option<u32> {
+        Some(match addr {
+            0x00 => self.rx.front().copied().unwrap_or(0).into(),
+            0x04 => self.status(),
+            0x08 => self.control,
+            0x0C => self.itr_code.into(),
+            _ => unreachable!(),
+        })
+    }
+
+    fn ticks(&self) -> bool {
+        true
+    }
+
+    fn tick(&mut self) -> Option<u8> {
+        let mut interrupt = false;
+
+        if !self.tx.is_empty() {
+            for byte in self.tx.drain(..) {
+                self.backend.transmit(byte);
+            }
+
+            interrupt |= self.control & UART_TX_INTERRUPT != 0;
+        }
+
+        // Backends with flow control keep their bytes until the receive FIFO has room for them
+        let room = self.rx.len() < self.fifo_size;
+
+        if room || !self.backend.flow_control() {
+            if let Some(byte) = self.backend.receive() {
+                if room {
+                    self.rx.push_back(byte);
+                    interrupt |= self.control & UART_RX_INTERRUPT != 0;
+                } else {
+                    self.overrun = true;
+                }
+            }
+        }
+
+        if interrupt {
+            Some(self.itr_code)
+        } else {
+            None
+        }
+    }
+}
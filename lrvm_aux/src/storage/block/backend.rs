@@ -0,0 +1,147 @@
+use std::{
+    cell::RefCell,
+    fs::{File, OpenOptions},
+    io::{Error, ErrorKind, Read, Result as IOResult, Seek, SeekFrom, Write},
+    path::Path,
+    rc::Rc,
+};
+
+/// Storage of a [`BlockDevice`](super::BlockDevice), accessed by byte offsets
+pub trait BlockBackend {
+    /// Get the storage's size, in bytes
+    fn size(&self) -> u64;
+
+    /// Check if the storage can be written
+    fn writable(&self) -> bool;
+
+    /// Fill the provided buffer with the bytes starting at an offset
+    fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> IOResult<()>;
+
+    /// Write the provided bytes starting at an offset
+    fn write_at(&mut self, offset: u64, buf: &[u8]) -> IOResult<()>;
+
+    /// Make sure all written data reached the underlying storage
+    fn flush(&mut self) -> IOResult<()>;
+}
+
+/// Backend storing the sectors in a real file, which may be larger than the 32-bit address space
+pub struct FileBlockBackend {
+    file: File,
+    size: u64,
+    writable: bool,
+}
+
+impl FileBlockBackend {
+    /// Open an existing file in read-only or writable mode
+    pub fn open(path: impl AsRef<Path>, writable: bool) -> IOResult<Self> {
+        let file = OpenOptions::new().read(true).write(writable).open(path)?;
+        let size = file.metadata()?.len();
+
+        Ok(Self {
+            file,
+            size,
+            writable,
+        })
+    }
+
+    /// Create a writable file of the provided size, filled with zeroes (or truncate an existing one)
+    pub fn create(path: impl AsRef<Path>, size: u64) -> IOResult<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+
+        file.set_len(size)?;
+
+        Ok(Self {
+            file,
+            size,
+            writable: true,
+        })
+    }
+}
+
+impl BlockBackend for FileBlockBackend {
+    fn size(&self) -> u64 {
+        self.size
+    }
+
+    fn writable(&self) -> bool {
+        self.writable
+    }
+
+    fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> IOResult<()> {
+        self.file.seek(SeekFrom::Start(offset))?;
+        self.file.read_exact(buf)
+    }
+
+    fn write_at(&mut self, offset: u64, buf: &[u8]) -> IOResult<()> {
+        self.file.seek(SeekFrom::Start(offset))?;
+        self.file.write_all(buf)
+    }
+
+    fn flush(&mut self) -> IOResult<()> {
+        self.file.sync_data()
+    }
+}
+
+/// In-memory backend, shared between the device and the code inspecting its content (e.g. tests).
+/// Clones of a backend all share the same bytes.
+#[derive(Debug, Clone)]
+pub struct MemoryBlockBackend(Rc<RefCell<Vec<u8>>>);
+
+impl MemoryBlockBackend {
+    /// Create a backend of the provided size, filled with zeroes
+    pub fn new(size: usize) -> Self {
+        Self::from_bytes(vec![0; size])
+    }
+
+    /// Create a backend with the provided content
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self(Rc::new(RefCell::new(bytes)))
+    }
+
+    /// Get a copy of the backend's content
+    pub fn bytes(&self) -> Vec<u8> {
+        self.0.borrow().clone()
+    }
+}
+
+impl BlockBackend for MemoryBlockBackend {
+    fn size(&self) -> u64 {
+        self.0.borrow().len() as u64
+    }
+
+    fn writable(&self) -> bool {
+        true
+    }
+
+    fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> IOResult<()> {
+        let bytes = self.0.borrow();
+        let range = range(offset, buf.len(), bytes.len())?;
+        buf.copy_from_slice(&bytes[range]);
+        Ok(())
+    }
+
+    fn write_at(&mut self, offset: u64, buf: &[u8]) -> IOResult<()> {
+        let mut bytes = self.0.borrow_mut();
+        let range = range(offset, buf.len(), bytes.len())?;
+        bytes[range].copy_from_slice(buf);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> IOResult<()> {
+        Ok(())
+    }
+}
+
+/// (Internal) Get the range of bytes to access in an in-memory storage
+fn range(offset: u64, len: usize, size: usize) -> IOResult<std::ops::Range<usize>> {
+    usize::try_from(offset)
+        .ok()
+        .and_then(|start| Some(start..start.checked_add(len)?))
+        .filter(|range| range.end <= size)
+        .ok_or_else(|| Error::new(ErrorKind::UnexpectedEof, "access beyond the storage's end"))
+}
@@ -0,0 +1,401 @@
+//! The block device component offers a sector-based storage, which is not limited by the 32-bit address space.
+//! See [`BlockDevice`] for more details.
+
+mod backend;
+mod monitor;
+
+pub use self::{backend::*, monitor::*};
+
+use std::{cell::RefCell, rc::Rc};
+
+use lrvm::board::{Bus, DmaPort, ResetKind};
+use lrvm_tools::{
+    bytes::{bytes_to_words, words_to_bytes},
+    exceptions::AuxHwException,
+    metadata::{DeviceMetadata, StorageType},
+};
+
+/// Command: read sectors
+pub const BLK_READ: u32 = 0x01;
+/// Command: write sectors
+pub const BLK_WRITE: u32 = 0x02;
+/// Command: make sure all written sectors reached the backend's storage
+pub const BLK_FLUSH: u32 = 0x03;
+/// Command: get the device's identity (sector size, number of sectors and flags)
+pub const BLK_IDENTIFY: u32 = 0x04;
+
+/// Status: the last command succeeded
+pub const BLK_STATUS_OK: u32 = 0x00;
+/// Status: a DMA transfer is in progress
+pub const BLK_STATUS_BUSY: u32 = 0x01;
+/// Status: the requested sectors are beyond the end of the device
+pub const BLK_ERR_RANGE: u32 = 0x10;
+/// Status: the backend failed to access the sectors
+pub const BLK_ERR_IO: u32 = 0x11;
+/// Status: the device is read-only
+pub const BLK_ERR_READONLY: u32 = 0x12;
+/// Status: invalid number of sectors (`0`, more than one in sector buffer mode, more than [`BLK_MAX_DMA_SIZE`] bytes
+/// or beyond the end of the address space in DMA mode)
+pub const BLK_ERR_COUNT: u32 = 0x13;
+/// Status: the DMA address is unaligned, too close to the end of the address space or an exception occurred during the DMA transfer
+pub const BLK_ERR_DMA: u32 = 0x14;
+
+/// Flag: transfer data from or to the DMA address instead of the sector buffer
+pub const BLK_DMA: u32 = 0x01;
+
+/// Identity flag: the device is writable
+pub const BLK_ID_WRITABLE: u32 = 0x01;
+
+/// Default size of a sector, in bytes
+pub const BLK_DEFAULT_SECTOR_SIZE: u32 = 512;
+
+/// Maximum number of bytes a single DMA command can transfer (1 MB)
+pub const BLK_MAX_DMA_SIZE: u32 = 0x100000;
+
+/// Size of the block device's header, in bytes
+pub const BLK_HEADER_SIZE: u32 = 0x20;
+
+/// The block device is a disk controller giving access to a [`BlockBackend`] sector by sector.
+/// It is made of a header followed by a sector buffer, which is one sector long.
+///
+/// The header is 8-word-long:
+///
+/// * Word 1: command (write-only), `BLK_READ`, `BLK_WRITE`, `BLK_FLUSH` or `BLK_IDENTIFY`
+/// * Word 2: status of the last command, `BLK_STATUS_OK`, `BLK_STATUS_BUSY` or an error code (read-only)
+/// * Word 3: sector size, in bytes (read-only)
+/// * Word 4: number of sectors (read-only)
+/// * Word 5: LBA, index of the first sector to read or write
+/// * Word 6: number of sectors to read or write
+/// * Word 7: DMA address
+/// * Word 8: flags (`BLK_DMA`)
+///
+/// By default, commands transfer a single sector through the sector buffer and complete immediately.
+/// With the `BLK_DMA` flag, they transfer up to [`BLK_MAX_DMA_SIZE`] bytes of sectors from or to the DMA address (physical and aligned) instead,
+/// and complete at the beginning of the next CPU cycle, before the next instruction runs.
+/// The transferred bytes must fit between the DMA address and the end of the address space.
+///
+/// The identity is made of 3 words (sector size, number of sectors and flags with `BLK_ID_WRITABLE`), followed by zeroes up to the end of the sector.
+///
+/// Host code can observe the device's activity and inject failures through a [`BlockMonitor`].
+/// The sector size is provided in the metadata's additional data (strongest 32 bits), along with the number of sectors (weakest 32 bits).
+pub struct BlockDevice {
+    backend: Box<dyn BlockBackend>,
+    sector_size: u32,
+    sectors: u32,
+    buffer: Vec<u32>,
+    status: u32,
+    lba: u32,
+    count: u32,
+    dma_addr: u32,
+    flags: u32,
+    pending: Option<u32>,
+    monitor: Rc<RefCell<MonitorState>>,
+    hw_id: u64,
+}
+
+impl BlockDevice {
+    /// Create a block device with sectors of [`BLK_DEFAULT_SECTOR_SIZE`] bytes
+    pub fn new(backend: Box<dyn BlockBackend>, hw_id: u64) -> Self {
+        Self::with_sector_size(backend, BLK_DEFAULT_SECTOR_SIZE, hw_id).unwrap()
+    }
+
+    /// Create a block device with sectors of the provided size, in bytes
+    /// Returns an error message if the size is lower than 16 bytes, not a multiple of 4 bytes or larger than 64 KB.
+    /// The bytes beyond the backend's last complete sector are not accessible.
+    pub fn with_sector_size(
+        backend: Box<dyn BlockBackend>,
+        sector_size: u32,
+        hw_id: u64,
+    ) -> Result<Self, &'static str> {
+        if !(16..=0x10000).contains(&sector_size) {
+            return Err("Block device's sector size must be between 16 bytes and 64 KB");
+        }
+
+        if sector_size % 4 != 0 {
+            return Err("Block device's sector size must be aligned");
+        }
+
+        let sectors = (backend.size() / u64::from(sector_size)).min(u32::MAX.into()) as u32;
+
+        Ok(Self {
+            backend,
+            sector_size,
+            sectors,
+            buffer: vec![0; sector_size as usize / 4],
+            status: BLK_STATUS_OK,
+            lba: 0,
+            count: 0,
+            dma_addr: 0,
+            flags: 0,
+            pending: None,
+            monitor: Rc::new(RefCell::new(MonitorState::default())),
+            hw_id,
+        })
+    }
+
+    /// Get a handle to observe this device and inject failures
+    pub fn monitor(&self) -> BlockMonitor {
+        BlockMonitor(Rc::clone(&self.monitor))
+    }
+
+    /// (Internal) Check if a command can be run with the current registers
+    fn validate(&self, command: u32) -> Result<(), u32> {
+        let dma = self.flags & BLK_DMA != 0;
+
+        if dma && self.dma_addr % 4 != 0 {
+            return Err(BLK_ERR_DMA);
+        }
+
+        // Number of bytes left between the DMA address and the end of the address space
+        let dma_room = (1 << 32) - u64::from(self.dma_addr);
+
+        if command == BLK_IDENTIFY && dma && u64::from(self.sector_size) > dma_room {
+            return Err(BLK_ERR_DMA);
+        }
+
+        if command == BLK_READ || command == BLK_WRITE {
+            if self.count == 0 || (!dma && self.count != 1) {
+                return Err(BLK_ERR_COUNT);
+            }
+
+            let len = u64::from(self.count) * u64::from(self.sector_size);
+
+            if dma && (len > u64::from(BLK_MAX_DMA_SIZE) || len > dma_room) {
+                return Err(BLK_ERR_COUNT);
+            }
+
+            if u64::from(self.lba) + u64::from(self.count) > u64::from(self.sectors) {
+                return Err(BLK_ERR_RANGE);
+            }
+
+            if command == BLK_WRITE && !self.backend.writable() {
+                return Err(BLK_ERR_READONLY);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// (Internal) Run a command, through the sector buffer or the provided DMA port, and get its status
+    fn execute(&mut self, command: u32, port: Option<&mut DmaPort>) -> u32 {
+        let status = match self.transfer(command, port) {
+            Ok(()) => BLK_STATUS_OK,
+            Err(status) => status,
+        };
+
+        if status != BLK_STATUS_OK {
+            self.monitor.borrow_mut().stats.errors += 1;
+        }
+
+        status
+    }
+
+    /// (Internal) Run a command, through the sector buffer or the provided DMA port
+    fn transfer(&mut self, command: u32, port: Option<&mut DmaPort>) -> Result<(), u32> {
+        // The registers may have changed since the command was accepted
+        self.validate(command)?;
+
+        let offset = u64::from(self.lba) * u64::from(self.sector_size);
+        let len = self.count as usize * self.sector_size as usize;
+
+        if (command == BLK_READ || command == BLK_WRITE)
+            && self
+                .monitor
+                .borrow_mut()
+                .inject_failure(self.lba, self.count)
+        {
+            return Err(BLK_ERR_IO);
+        }
+
+        match command {
+            BLK_READ => {
+                let mut bytes = vec![0; len];
+
+                self.backend
+                    .read_at(offset, &mut bytes)
+                    .map_err(|_| BLK_ERR_IO)?;
+
+                self.deliver(bytes_to_words(bytes), port)?;
+
+                let mut monitor = self.monitor.borrow_mut();
+                monitor.stats.reads += 1;
+                monitor.stats.bytes_read += len as u64;
+            }
+
+            BLK_WRITE => {
+                let words = match port {
+                    None => self.buffer.clone(),
+                    Some(port) => {
+                        let mut ex = 0;
+                        let words = (0..len as u32 / 4)
+                            .map(|i| port.read(self.dma_addr + i * 4, &mut ex))
+                            .collect();
+
+                        if ex != 0 {
+                            return Err(BLK_ERR_DMA);
+                        }
+
+                        words
+                    }
+                };
+
+                self.backend
+                    .write_at(offset, &words_to_bytes(words))
+                    .map_err(|_| BLK_ERR_IO)?;
+
+                let mut monitor = self.monitor.borrow_mut();
+                monitor.stats.writes += 1;
+                monitor.stats.bytes_written += len as u64;
+            }
+
+            BLK_FLUSH => {
+                self.backend.flush().map_err(|_| BLK_ERR_IO)?;
+                self.monitor.borrow_mut().stats.flushes += 1;
+            }
+
+            BLK_IDENTIFY => {
+                let mut identity = vec![0; self.buffer.len()];
+                identity[0] = self.sector_size;
+                identity[1] = self.sectors;
+                identity[2] = if self.backend.writable() {
+                    BLK_ID_WRITABLE
+                } else {
+                    0
+                };
+
+                self.deliver(identity, port)?;
+            }
+
+            _ => unreachable!(),
+        }
+
+        Ok(())
+    }
+
+    /// (Internal) Put words in the sector buffer or send them through the provided DMA port
+    fn deliver(&mut self, words: Vec<u32>, port: Option<&mut DmaPort>) -> Result<(), u32> {
+        match port {
+            None => self.buffer = words,
+            Some(port) => {
+                let mut ex = 0;
+
+                for (i, word) in words.into_iter().enumerate() {
+                    port.write(self.dma_addr + i as u32 * 4, word, &mut ex);
+
+                    if ex != 0 {
+                        return Err(BLK_ERR_DMA);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Bus for BlockDevice {
+    fn name(&self) -> &'static str {
+        "Block Device"
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        DeviceMetadata::new(
+            self.hw_id,
+            BLK_HEADER_SIZE + self.sector_size,
+            StorageType::Block.into(),
+            None,
+            Some((u64::from(self.sector_size) << 32) | u64::from(self.sectors)),
+        )
+        .encode()
+    }
+
+    fn read(&mut self, addr: u32, ex: &mut u16) -> u32 {
+        if addr == 0x00 {
+            *ex = AuxHwException::MemoryNotReadable.into();
+            return 0;
+        }
+
+        self.peek(addr).unwrap()
+    }
+
+    fn write(&mut self, addr: u32, word: u32, ex: &mut u16) {
+        match addr {
+            0x00 => {
+                if !matches!(word, BLK_READ | BLK_WRITE | BLK_FLUSH | BLK_IDENTIFY) {
+                    *ex = AuxHwException::UnknownOperation(word as u8).into();
+                    return;
+                }
+
+                // Commands sent during a DMA transfer are ignored
+                if self.pending.is_some() {
+                    return;
+                }
+
+                self.status = match self.validate(word) {
+                    Err(status) => {
+                        self.monitor.borrow_mut().stats.errors += 1;
+                        status
+                    }
+                    Ok(()) if self.flags & BLK_DMA != 0 => {
+                        self.pending = Some(word);
+                        BLK_STATUS_BUSY
+                    }
+                    Ok(()) => self.execute(word, None),
+                };
+            }
+            0x04 | 0x08 | 0x0C => *ex = AuxHwException::MemoryNotWritable.into(),
+            0x10 => self.lba = word,
+            0x14 => self.count = word,
+            0x18 => self.dma_addr = word,
+            0x1C => self.flags = word & BLK_DMA,
+            _ => self.buffer[((addr - BLK_HEADER_SIZE) / 4) as usize] = word,
+        }
+    }
+
+    fn reset(&mut self, kind: ResetKind) {
+        if kind != ResetKind::Cold && self.backend.writable() {
+            let _ = self.backend.flush();
+        }
+
+        self.buffer.fill(0);
+        self.status = BLK_STATUS_OK;
+        self.lba = 0;
+        self.count = 0;
+        self.dma_addr = 0;
+        self.flags = 0;
+        self.pending = None;
+    }
+
+    fn peek(&self, addr: u32) -> Option<u32> {
+        Some(match addr {
+            0x00 => 0,
+            0x04 => self.status,
+            0x08 => self.sector_size,
+            0x0C => self.sectors,
+            0x10 => self.lba,
+            0x14 => self.count,
+            0x18 => self.dma_addr,
+            0x1C => self.flags,
+            _ => self.buffer[((addr - BLK_HEADER_SIZE) / 4) as usize],
+        })
+    }
+
+    fn poke(&mut self, addr: u32, word: u32) -> bool {
+        if addr < BLK_HEADER_SIZE {
+            return false;
+        }
+
+        self.buffer[((addr - BLK_HEADER_SIZE) / 4) as usize] = word;
+        true
+    }
+
+    fn ticks(&self) -> bool {
+        true
+    }
+
+    fn dma(&mut self, port: &mut DmaPort) {
+        if let Some(command) = self.pending.take() {
+            self.status = self.execute(command, Some(port));
+        }
+    }
+}
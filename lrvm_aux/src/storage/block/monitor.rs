@@ -0,0 +1,67 @@
+use std::{cell::RefCell, collections::HashSet, rc::Rc};
+
+/// Statistics of a [`BlockDevice`](super::BlockDevice), counted since its creation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BlockStats {
+    /// Number of successful read commands
+    pub reads: u64,
+    /// Number of successful write commands
+    pub writes: u64,
+    /// Number of successful flush commands
+    pub flushes: u64,
+    /// Number of bytes read from the backend
+    pub bytes_read: u64,
+    /// Number of bytes written to the backend
+    pub bytes_written: u64,
+    /// Number of failed commands (including injected failures)
+    pub errors: u64,
+}
+
+/// (Internal) State shared between a block device and its monitors
+#[derive(Debug, Default)]
+pub(super) struct MonitorState {
+    pub(super) stats: BlockStats,
+    pub(super) fail_next: u64,
+    pub(super) faulty_sectors: HashSet<u32>,
+}
+
+impl MonitorState {
+    /// Check if a command accessing the provided sectors must fail, consuming a pending failure if so
+    pub(super) fn inject_failure(&mut self, lba: u32, count: u32) -> bool {
+        if self.fail_next > 0 {
+            self.fail_next -= 1;
+            return true;
+        }
+
+        (0..count).any(|i| self.faulty_sectors.contains(&lba.wrapping_add(i)))
+    }
+}
+
+/// Host-side handle to observe a [`BlockDevice`](super::BlockDevice)'s activity and to inject failures.
+/// Clones of a monitor all share the same device.
+#[derive(Debug, Clone)]
+pub struct BlockMonitor(pub(super) Rc<RefCell<MonitorState>>);
+
+impl BlockMonitor {
+    /// Get the device's statistics
+    pub fn stats(&self) -> BlockStats {
+        self.0.borrow().stats
+    }
+
+    /// Make the next commands fail with an I/O error
+    pub fn fail_next(&self, commands: u64) {
+        self.0.borrow_mut().fail_next = commands;
+    }
+
+    /// Make all read and write commands accessing a sector fail with an I/O error, until the faults are cleared
+    pub fn fail_sector(&self, lba: u32) {
+        self.0.borrow_mut().faulty_sectors.insert(lba);
+    }
+
+    /// Remove all injected failures
+    pub fn clear_faults(&self) {
+        let mut state = self.0.borrow_mut();
+        state.fail_next = 0;
+        state.faulty_sectors.clear();
+    }
+}
@@ -1,7 +1,7 @@
 //! The BootROM component offers a simple read-only storage.
 //! See [`BootROM`] for more details.
 
-use lrvm::board::Bus;
+use lrvm::board::{Bus, ResetKind};
 use lrvm_tools::{
     exceptions::AuxHwException,
     metadata::{DeviceMetadata, StorageType},
@@ -105,5 +105,22 @@ impl Bus for BootRom {
         *ex = AuxHwException::MemoryNotWritable.into();
     }
 
-    fn reset(&mut self) {}
+    fn reset(&mut self, _kind: ResetKind) {}
+
+    fn peek(&self, addr: u32) -> Option<u32> {
+        Some(self.storage.get(addr as usize / 4).copied().unwrap_or(0))
+    }
+
+    // Debuggers are allowed to patch the ROM
+    fn poke(&mut self, addr: u32, word: u32) -> bool {
+        let addr = addr as usize / 4;
+
+        if addr >= self.storage.len() {
+            self.storage.resize(addr + 1, 0);
+            self.len = self.storage.len() as u32;
+        }
+
+        self.storage[addr] = word;
+        true
+    }
 }
@@ -2,26 +2,35 @@
 //! See [`FileBackedMem`] for more details.
 
 use std::{
+    cell::RefCell,
     cmp::Ordering,
     fs::{File, OpenOptions},
     io::{Read, Result as IOResult, Seek, SeekFrom, Write},
     path::Path,
+    rc::Rc,
 };
 
-use lrvm::board::Bus;
+use lrvm::board::{Bus, ResetKind};
 use lrvm_tools::{
     exceptions::AuxHwException,
     metadata::{DeviceMetadata, StorageType},
 };
 
+use super::{overlay::OverlayState, FileOverlay};
+
 /// The persistent memory component contains a read-only or writable, persistent storage that does not reset with the motherboard.
+/// Warm resets and the ones requested by the guest flush the written data to the disk.
 ///
 /// It uses a real file to store its data and is perfect for storing data that persists after the VM is destroyed.
+///
+/// In copy-on-write mode, the file is never modified: written words are kept in a delta (in memory or in a delta file)
+/// which takes precedence over the file's content, and which the host can commit, discard or export through a [`FileOverlay`].
 pub struct FileBackedMem {
     handler: File,
     size: u32,
     real_size: u32,
     writable: bool,
+    overlay: Option<Rc<RefCell<OverlayState>>>,
     hw_id: u64,
 }
 
@@ -51,6 +60,7 @@ impl FileBackedMem {
             real_size,
             handler,
             writable,
+            overlay: None,
             hw_id,
         })
     }
@@ -84,6 +94,37 @@ impl FileBackedMem {
         mem.size = size;
         Ok(mem)
     }
+
+    /// Create a new copy-on-write persistent memory component, keeping the written words in memory
+    pub fn copy_on_write(path: impl AsRef<Path>, hw_id: u64) -> IOResult<Self> {
+        let mut mem = Self::open(&path, false, hw_id)?;
+        mem.writable = true;
+        mem.overlay = Some(Rc::new(RefCell::new(OverlayState::in_memory(
+            path.as_ref(),
+        ))));
+        Ok(mem)
+    }
+
+    /// Create a new copy-on-write persistent memory component, keeping the written words in a delta file.
+    /// If the delta file already exists, the writes it contains are applied again.
+    pub fn copy_on_write_with_delta_file(
+        path: impl AsRef<Path>,
+        delta_path: impl AsRef<Path>,
+        hw_id: u64,
+    ) -> IOResult<Self> {
+        let mut mem = Self::open(&path, false, hw_id)?;
+        mem.writable = true;
+        mem.overlay = Some(Rc::new(RefCell::new(OverlayState::with_delta_file(
+            path.as_ref(),
+            delta_path.as_ref(),
+        )?)));
+        Ok(mem)
+    }
+
+    /// Get a handle to the delta, if the component is in copy-on-write mode
+    pub fn overlay(&self) -> Option<FileOverlay> {
+        self.overlay.clone().map(FileOverlay)
+    }
 }
 
 impl Bus for FileBackedMem {
@@ -107,6 +148,10 @@ impl Bus for FileBackedMem {
             return 0;
         }
 
+        if let Some(word) = self.overlay.as_ref().and_then(|o| o.borrow().get(addr)) {
+            return word;
+        }
+
         let mut buffer = [0; 4];
 
         if self.handler.seek(SeekFrom::Start(addr.into())).is_err() {
@@ -126,10 +171,61 @@ impl Bus for FileBackedMem {
         if !self.writable {
             *ex = AuxHwException::MemoryNotWritable.into();
         } else if addr < self.real_size {
-            self.handler.seek(SeekFrom::Start(addr.into())).unwrap();
-            self.handler.write_all(&word.to_be_bytes()).unwrap();
+            match &self.overlay {
+                Some(overlay) => {
+                    if overlay.borrow_mut().set(addr, word).is_err() {
+                        *ex = AuxHwException::GenericPhysicalWriteError.into();
+                    }
+                }
+                None => {
+                    self.handler.seek(SeekFrom::Start(addr.into())).unwrap();
+                    self.handler.write_all(&word.to_be_bytes()).unwrap();
+                }
+            }
+        }
+    }
+
+    fn reset(&mut self, kind: ResetKind) {
+        if self.writable && kind != ResetKind::Cold {
+            match &self.overlay {
+                Some(overlay) => {
+                    let _ = overlay.borrow_mut().sync();
+                }
+                None => {
+                    let _ = self.handler.sync_data();
+                }
+            }
         }
     }
 
-    fn reset(&mut self) {}
+    fn peek(&self, addr: u32) -> Option<u32> {
+        if addr >= self.real_size {
+            return Some(0);
+        }
+
+        if let Some(word) = self.overlay.as_ref().and_then(|o| o.borrow().get(addr)) {
+            return Some(word);
+        }
+
+        let mut buffer = [0; 4];
+        let mut handler = &self.handler;
+
+        handler.seek(SeekFrom::Start(addr.into())).ok()?;
+        handler.read_exact(&mut buffer).ok()?;
+
+        Some(u32::from_be_bytes(buffer))
+    }
+
+    fn poke(&mut self, addr: u32, word: u32) -> bool {
+        if !self.writable || addr >= self.real_size {
+            return false;
+        }
+
+        if let Some(overlay) = &self.overlay {
+            return overlay.borrow_mut().set(addr, word).is_ok();
+        }
+
+        self.handler.seek(SeekFrom::Start(addr.into())).is_ok()
+            && self.handler.write_all(&word.to_be_bytes()).is_ok()
+    }
 }
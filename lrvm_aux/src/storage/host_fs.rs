@@ -0,0 +1,448 @@
+//! The host filesystem component gives access to the files of a host directory.
+//! See [`HostFs`] for more details.
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{Error as IOError, ErrorKind, Read, Result as IOResult, Seek, SeekFrom, Write},
+    path::{Component, Path, PathBuf},
+};
+
+use lrvm::board::{Bus, ResetKind};
+use lrvm_tools::{
+    bytes::{bytes_to_words, words_to_bytes},
+    exceptions::AuxHwException,
+    metadata::{DeviceMetadata, StorageType},
+};
+
+/// Command: open the file whose path is in the buffer, with the flags in the argument register
+pub const HFS_OPEN: u32 = 0x01;
+/// Command: read bytes from a file to the buffer
+pub const HFS_READ: u32 = 0x02;
+/// Command: write bytes from the buffer to a file
+pub const HFS_WRITE: u32 = 0x03;
+/// Command: move a file's cursor by the (signed) offset in the argument register
+pub const HFS_SEEK: u32 = 0x04;
+/// Command: close a file
+pub const HFS_CLOSE: u32 = 0x05;
+/// Command: get the size and kind of the file or directory whose path is in the buffer
+pub const HFS_STAT: u32 = 0x06;
+/// Command: get the name of an entry of the directory whose path is in the buffer, the entry's index being in the argument register
+pub const HFS_LIST: u32 = 0x07;
+
+/// Open flag: the file can be read
+pub const HFS_OPEN_READ: u32 = 0x01;
+/// Open flag: the file can be written
+pub const HFS_OPEN_WRITE: u32 = 0x02;
+/// Open flag: create the file if it does not exist
+pub const HFS_OPEN_CREATE: u32 = 0x04;
+/// Open flag: empty the file
+pub const HFS_OPEN_TRUNCATE: u32 = 0x08;
+/// Open flag: always write at the end of the file
+pub const HFS_OPEN_APPEND: u32 = 0x10;
+
+/// Seek origin: start of the file
+pub const HFS_SEEK_START: u32 = 0x00;
+/// Seek origin: current position of the cursor
+pub const HFS_SEEK_CURRENT: u32 = 0x01;
+/// Seek origin: end of the file
+pub const HFS_SEEK_END: u32 = 0x02;
+
+/// Kind: the path leads to a file
+pub const HFS_KIND_FILE: u32 = 0x01;
+/// Kind: the path leads to a directory
+pub const HFS_KIND_DIR: u32 = 0x02;
+
+/// Maximum number of files that can be open at the same time
+pub const HFS_MAX_HANDLES: usize = 16;
+
+/// Size of the buffer, in bytes
+pub const HFS_BUFFER_SIZE: u32 = 0x1000;
+
+/// Size of the host filesystem's header, in bytes
+pub const HFS_HEADER_SIZE: u32 = 0x20;
+
+/// The host filesystem component lets the guest open, read and write the files of a host directory, which it cannot escape.
+/// It is made of a header followed by a buffer of [`HFS_BUFFER_SIZE`] bytes, containing paths and data as big-endian words.
+///
+/// The header is 8-word-long:
+///
+/// * Word 1: command (write-only), `HFS_OPEN`, `HFS_READ`, `HFS_WRITE`, `HFS_SEEK`, `HFS_CLOSE`, `HFS_STAT` or `HFS_LIST`
+/// * Word 2: handle of the file to use (set by `HFS_OPEN`)
+/// * Word 3: length of the path or of the data to read or write, in bytes
+/// * Word 4: argument (`HFS_OPEN_*` flags, seek offset or directory entry's index)
+/// * Word 5: seek origin, `HFS_SEEK_START`, `HFS_SEEK_CURRENT` or `HFS_SEEK_END`
+/// * Word 6: result of the last command (read-only)
+/// * Word 7: kind of the last stat or listed entry, `HFS_KIND_FILE` or `HFS_KIND_DIR` (read-only)
+/// * Word 8: buffer size, in bytes (read-only)
+///
+/// The result is the file's size for `HFS_OPEN` and `HFS_STAT`, the number of bytes transferred for `HFS_READ` and `HFS_WRITE`,
+/// the cursor's new position for `HFS_SEEK` and the length of the entry's name for `HFS_LIST` (`0` with a kind of `0` past the last entry).
+/// Sizes and positions are saturated to 32 bits, entries are listed in alphabetical order and names longer than the buffer are truncated.
+///
+/// Paths are relative to the directory and separated by `/`. Absolute paths and `..` components are rejected, as well as symbolic links
+/// leading outside the directory. Failing commands raise one of the filesystem exceptions of [`AuxHwException`] (e.g. `FileNotFound`).
+///
+/// Files are closed when the motherboard is reset. If the component is read-only, opening a file for writing raises `PermissionDenied`.
+pub struct HostFs {
+    root: PathBuf,
+    writable: bool,
+    files: Vec<Option<OpenFile>>,
+    buffer: Vec<u32>,
+    handle: u32,
+    length: u32,
+    arg: u32,
+    origin: u32,
+    result: u32,
+    kind: u32,
+    hw_id: u64,
+}
+
+/// (Internal) File opened by the guest
+struct OpenFile {
+    file: File,
+    writable: bool,
+}
+
+impl HostFs {
+    /// Create a host filesystem component restricted to the provided directory
+    pub fn new(root: impl AsRef<Path>, writable: bool, hw_id: u64) -> IOResult<Self> {
+        let root = root.as_ref().canonicalize()?;
+
+        if !root.is_dir() {
+            return Err(IOError::new(
+                ErrorKind::InvalidInput,
+                "host filesystem's root must be a directory",
+            ));
+        }
+
+        Ok(Self {
+            root,
+            writable,
+            files: (0..HFS_MAX_HANDLES).map(|_| None).collect(),
+            buffer: vec![0; HFS_BUFFER_SIZE as usize / 4],
+            handle: 0,
+            length: 0,
+            arg: 0,
+            origin: 0,
+            result: 0,
+            kind: 0,
+            hw_id,
+        })
+    }
+
+    /// Get the directory the component is restricted to
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// (Internal) Run a command
+    fn run(&mut self, command: u32) -> Result<(), AuxHwException> {
+        match command {
+            HFS_OPEN => {
+                let modifies =
+                    HFS_OPEN_WRITE | HFS_OPEN_CREATE | HFS_OPEN_TRUNCATE | HFS_OPEN_APPEND;
+
+                if self.arg & modifies != 0 && !self.writable {
+                    return Err(AuxHwException::PermissionDenied);
+                }
+
+                let path = self.path()?;
+                let slot = self
+                    .files
+                    .iter()
+                    .position(Option::is_none)
+                    .ok_or(AuxHwException::TooManyOpenFiles)?;
+
+                let file = OpenOptions::new()
+                    .read(self.arg & HFS_OPEN_READ != 0)
+                    .write(self.arg & HFS_OPEN_WRITE != 0)
+                    .create(self.arg & HFS_OPEN_CREATE != 0)
+                    .truncate(self.arg & HFS_OPEN_TRUNCATE != 0)
+                    .append(self.arg & HFS_OPEN_APPEND != 0)
+                    .open(path)
+                    .map_err(fs_exception)?;
+
+                let metadata = file.metadata().map_err(fs_exception)?;
+
+                if metadata.is_dir() {
+                    return Err(AuxHwException::WrongFileType);
+                }
+
+                self.files[slot] = Some(OpenFile {
+                    file,
+                    writable: self.arg & (HFS_OPEN_WRITE | HFS_OPEN_APPEND) != 0,
+                });
+                self.handle = slot as u32 + 1;
+                self.result = saturate(metadata.len());
+            }
+
+            HFS_READ => {
+                let length = self.data_length()?;
+                let file = &mut self.file()?.file;
+
+                let mut bytes = vec![0; length];
+                let mut read = 0;
+
+                while read < length {
+                    match file.read(&mut bytes[read..]) {
+                        Ok(0) => break,
+                        Ok(count) => read += count,
+                        Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                        Err(err) => return Err(fs_exception(err)),
+                    }
+                }
+
+                for (i, word) in bytes_to_words(&bytes[..read]).into_iter().enumerate() {
+                    self.buffer[i] = word;
+                }
+
+                self.result = read as u32;
+            }
+
+            HFS_WRITE => {
+                let length = self.data_length()?;
+                let bytes = words_to_bytes(&self.buffer);
+                let file = self.file()?;
+
+                if !file.writable {
+                    return Err(AuxHwException::PermissionDenied);
+                }
+
+                file.file
+                    .write_all(&bytes[..length])
+                    .map_err(fs_exception)?;
+
+                self.result = length as u32;
+            }
+
+            HFS_SEEK => {
+                let pos = match self.origin {
+                    HFS_SEEK_START => SeekFrom::Start(self.arg.into()),
+                    HFS_SEEK_CURRENT => SeekFrom::Current((self.arg as i32).into()),
+                    HFS_SEEK_END => SeekFrom::End((self.arg as i32).into()),
+                    _ => return Err(AuxHwException::UnsupportedOperation),
+                };
+
+                let pos = self.file()?.file.seek(pos).map_err(fs_exception)?;
+                self.result = saturate(pos);
+            }
+
+            HFS_CLOSE => {
+                self.file()?;
+                self.files[self.handle as usize - 1] = None;
+            }
+
+            HFS_STAT => {
+                let metadata = fs::metadata(self.path()?).map_err(fs_exception)?;
+
+                self.result = saturate(metadata.len());
+                self.kind = kind(&metadata);
+            }
+
+            HFS_LIST => {
+                let path = self.path()?;
+
+                if !path.is_dir() {
+                    return Err(AuxHwException::WrongFileType);
+                }
+
+                let mut entries = fs::read_dir(path)
+                    .and_then(|entries| entries.collect::<IOResult<Vec<_>>>())
+                    .map_err(fs_exception)?;
+
+                entries.sort_by_key(|entry| entry.file_name());
+
+                let Some(entry) = entries.get(self.arg as usize) else {
+                    self.result = 0;
+                    self.kind = 0;
+                    return Ok(());
+                };
+
+                let metadata = entry.metadata().map_err(fs_exception)?;
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let name = &name.as_bytes()[..name.len().min(HFS_BUFFER_SIZE as usize)];
+
+                self.buffer.fill(0);
+
+                for (i, word) in bytes_to_words(name).into_iter().enumerate() {
+                    self.buffer[i] = word;
+                }
+
+                self.result = name.len() as u32;
+                self.kind = kind(&metadata);
+            }
+
+            _ => return Err(AuxHwException::UnknownOperation(command as u8)),
+        }
+
+        Ok(())
+    }
+
+    /// (Internal) Get the length of the data to transfer
+    fn data_length(&self) -> Result<usize, AuxHwException> {
+        if self.length > HFS_BUFFER_SIZE {
+            return Err(AuxHwException::UnsupportedOperation);
+        }
+
+        Ok(self.length as usize)
+    }
+
+    /// (Internal) Get the file referred to by the handle register
+    fn file(&mut self) -> Result<&mut OpenFile, AuxHwException> {
+        let slot = (self.handle as usize).wrapping_sub(1);
+
+        self.files
+            .get_mut(slot)
+            .and_then(Option::as_mut)
+            .ok_or(AuxHwException::InvalidHandle)
+    }
+
+    /// (Internal) Get the host path of the guest path in the buffer, making sure it does not lead outside the root directory
+    fn path(&self) -> Result<PathBuf, AuxHwException> {
+        let length = self.data_length()?;
+        let bytes = words_to_bytes(&self.buffer);
+
+        let path = std::str::from_utf8(&bytes[..length])
+            .map_err(|_| AuxHwException::PathOutsideSandbox)?;
+
+        if path.contains('\0') || path.contains('\\') {
+            return Err(AuxHwException::PathOutsideSandbox);
+        }
+
+        let mut host_path = self.root.clone();
+
+        for component in Path::new(path).components() {
+            match component {
+                Component::Normal(name) => host_path.push(name),
+                Component::CurDir => {}
+                Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                    return Err(AuxHwException::PathOutsideSandbox)
+                }
+            }
+        }
+
+        // Symbolic links may lead outside the root directory, so the real path of the file
+        // (or of its parent directory if it does not exist yet) is checked as well.
+        // Dangling links are rejected as their target could be created outside of it.
+        let real_path = match host_path.canonicalize() {
+            Ok(real_path) => real_path,
+            Err(_) if host_path.symlink_metadata().is_ok() => {
+                return Err(AuxHwException::PathOutsideSandbox)
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound => host_path
+                .parent()
+                .unwrap_or(&host_path)
+                .canonicalize()
+                .map_err(fs_exception)?,
+            Err(err) => return Err(fs_exception(err)),
+        };
+
+        if !real_path.starts_with(&self.root) {
+            return Err(AuxHwException::PathOutsideSandbox);
+        }
+
+        Ok(host_path)
+    }
+}
+
+/// (Internal) Convert a host I/O error to an exception
+fn fs_exception(err: IOError) -> AuxHwException {
+    match err.kind() {
+        ErrorKind::NotFound => AuxHwException::FileNotFound,
+        ErrorKind::PermissionDenied | ErrorKind::ReadOnlyFilesystem => {
+            AuxHwException::PermissionDenied
+        }
+        ErrorKind::IsADirectory | ErrorKind::NotADirectory => AuxHwException::WrongFileType,
+        _ => AuxHwException::HostIoError,
+    }
+}
+
+/// (Internal) Get the kind of a file or directory
+fn kind(metadata: &fs::Metadata) -> u32 {
+    if metadata.is_dir() {
+        HFS_KIND_DIR
+    } else {
+        HFS_KIND_FILE
+    }
+}
+
+/// (Internal) Saturate a size or position to 32 bits
+fn saturate(value: u64) -> u32 {
+    value.min(u32::MAX.into()) as u32
+}
+
+impl Bus for HostFs {
+    fn name(&self) -> &'static str {
+        "Host Filesystem"
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        DeviceMetadata::new(
+            self.hw_id,
+            HFS_HEADER_SIZE + HFS_BUFFER_SIZE,
+            StorageType::HostFs.into(),
+            None,
+            Some(self.writable.into()),
+        )
+        .encode()
+    }
+
+    fn read(&mut self, addr: u32, ex: &mut u16) -> u32 {
+        if addr == 0x00 {
+            *ex = AuxHwException::MemoryNotReadable.into();
+            return 0;
+        }
+
+        self.peek(addr).unwrap()
+    }
+
+    fn write(&mut self, addr: u32, word: u32, ex: &mut u16) {
+        match addr {
+            0x00 => {
+                if let Err(err) = self.run(word) {
+                    *ex = err.into();
+                }
+            }
+            0x04 => self.handle = word,
+            0x08 => self.length = word,
+            0x0C => self.arg = word,
+            0x10 => self.origin = word,
+            0x14 | 0x18 | 0x1C => *ex = AuxHwException::MemoryNotWritable.into(),
+            _ => self.buffer[((addr - HFS_HEADER_SIZE) / 4) as usize] = word,
+        }
+    }
+
+    fn reset(&mut self, _kind: ResetKind) {
+        self.files.iter_mut().for_each(|file| *file = None);
+        self.buffer.fill(0);
+        self.handle = 0;
+        self.length = 0;
+        self.arg = 0;
+        self.origin = 0;
+        self.result = 0;
+        self.kind = 0;
+    }
+
+    fn peek(&self, addr: u32) -> Option<u32> {
+        Some(match addr {
+            0x00 => 0,
+            0x04 => self.handle,
+            0x08 => self.length,
+            0x0C => self.arg,
+            0x10 => self.origin,
+            0x14 => self.result,
+            0x18 => self.kind,
+            0x1C => HFS_BUFFER_SIZE,
+            _ => self.buffer[((addr - HFS_HEADER_SIZE) / 4) as usize],
+        })
+    }
+
+    fn poke(&mut self, addr: u32, word: u32) -> bool {
+        if addr < HFS_HEADER_SIZE {
+            return false;
+        }
+
+        self.buffer[((addr - HFS_HEADER_SIZE) / 4) as usize] = word;
+        true
+    }
+}
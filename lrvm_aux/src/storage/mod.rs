@@ -1,5 +1,11 @@
+mod block;
 mod bootrom;
 mod file_backed;
+mod host_fs;
+mod overlay;
 mod persistent;
 
-pub use self::{bootrom::BootRom, file_backed::FileBackedMem, persistent::PersistentMem};
+pub use self::{
+    block::*, bootrom::BootRom, file_backed::FileBackedMem, host_fs::*, overlay::FileOverlay,
+    persistent::PersistentMem,
+};
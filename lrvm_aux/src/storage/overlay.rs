@@ -0,0 +1,181 @@
+//! Copy-on-write overlays keep the words written to a file-backed memory apart from its file.
+//! See [`FileOverlay`] for more details.
+
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    fs::{File, OpenOptions},
+    io::{BufWriter, Read, Result as IOResult, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+/// (Internal) Words written to a copy-on-write [`FileBackedMem`](super::FileBackedMem), shared with its [`FileOverlay`] handles
+#[derive(Debug)]
+pub(super) struct OverlayState {
+    /// Path of the base file
+    base: PathBuf,
+    /// Written words, indexed by address
+    words: BTreeMap<u32, u32>,
+    /// Delta file, if the delta is not only kept in memory ; records are appended to it, and it is rewritten compactly when synced
+    delta_file: Option<BufWriter<File>>,
+}
+
+impl OverlayState {
+    /// Create an overlay over the provided base file, with the delta only kept in memory
+    pub(super) fn in_memory(base: &Path) -> Self {
+        Self {
+            base: base.to_path_buf(),
+            words: BTreeMap::new(),
+            delta_file: None,
+        }
+    }
+
+    /// Create an overlay over the provided base file, with the delta stored in a file (created if it does not exist).
+    /// The writes stored in an existing delta file are applied again.
+    pub(super) fn with_delta_file(base: &Path, delta_path: &Path) -> IOResult<Self> {
+        let mut delta_file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(delta_path)?;
+
+        // Reading the file leaves its cursor at the end, where the next records are appended
+        let mut bytes = vec![];
+        delta_file.read_to_end(&mut bytes)?;
+
+        Ok(Self {
+            base: base.to_path_buf(),
+            words: decode_delta(&bytes),
+            delta_file: Some(BufWriter::new(delta_file)),
+        })
+    }
+
+    /// Get a written word
+    pub(super) fn get(&self, addr: u32) -> Option<u32> {
+        self.words.get(&addr).copied()
+    }
+
+    /// Write a word to the delta
+    pub(super) fn set(&mut self, addr: u32, word: u32) -> IOResult<()> {
+        if let Some(delta_file) = &mut self.delta_file {
+            delta_file.write_all(&encode_record(addr, word))?;
+        }
+
+        self.words.insert(addr, word);
+        Ok(())
+    }
+
+    /// Rewrite the delta file with a single record per written word, and make sure its content reached the disk
+    pub(super) fn sync(&mut self) -> IOResult<()> {
+        let Some(delta_file) = &mut self.delta_file else {
+            return Ok(());
+        };
+
+        truncate(delta_file)?;
+
+        for (addr, word) in &self.words {
+            delta_file.write_all(&encode_record(*addr, *word))?;
+        }
+
+        delta_file.flush()?;
+        delta_file.get_ref().sync_data()
+    }
+
+    /// Forget all the written words, and empty the delta file
+    fn clear(&mut self) -> IOResult<()> {
+        self.words.clear();
+
+        match &mut self.delta_file {
+            Some(delta_file) => truncate(delta_file),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Host-side handle to the delta of a copy-on-write [`FileBackedMem`](super::FileBackedMem), obtained with
+/// [`FileBackedMem::overlay`](super::FileBackedMem::overlay). Clones of a handle all share the same delta.
+///
+/// Delta files and exported deltas are made of 8-byte records: the word's address followed by the word, both big-endian.
+/// When an address appears multiple times, the last record wins.
+///
+/// Writes are buffered before being appended to the delta file, which is rewritten with a single record per word
+/// when the memory is synced (on non-cold resets).
+#[derive(Debug, Clone)]
+pub struct FileOverlay(pub(super) Rc<RefCell<OverlayState>>);
+
+impl FileOverlay {
+    /// Get the number of words written since the delta was created, committed or discarded
+    pub fn dirty_words(&self) -> usize {
+        self.0.borrow().words.len()
+    }
+
+    /// Get the written words, indexed by address
+    pub fn delta(&self) -> BTreeMap<u32, u32> {
+        self.0.borrow().words.clone()
+    }
+
+    /// Write the delta to the base file, then discard it
+    pub fn commit(&self) -> IOResult<()> {
+        {
+            let state = self.0.borrow();
+            let mut base = OpenOptions::new().write(true).open(&state.base)?;
+
+            for (addr, word) in &state.words {
+                base.seek(SeekFrom::Start((*addr).into()))?;
+                base.write_all(&word.to_be_bytes())?;
+            }
+
+            base.sync_data()?;
+        }
+
+        self.discard()
+    }
+
+    /// Forget all the written words, so the memory's content is the base file's one again
+    pub fn discard(&self) -> IOResult<()> {
+        self.0.borrow_mut().clear()
+    }
+
+    /// Write the delta to a file, which can later be used as a delta file
+    pub fn export(&self, path: impl AsRef<Path>) -> IOResult<()> {
+        let bytes = self
+            .0
+            .borrow()
+            .words
+            .iter()
+            .flat_map(|(addr, word)| encode_record(*addr, *word))
+            .collect::<Vec<_>>();
+
+        std::fs::write(path, bytes)
+    }
+}
+
+/// (Internal) Empty a delta file, dropping its buffered records
+fn truncate(delta_file: &mut BufWriter<File>) -> IOResult<()> {
+    // Seeking flushes the buffered records first, which doesn't matter as the file is emptied right after
+    delta_file.seek(SeekFrom::Start(0))?;
+    delta_file.get_ref().set_len(0)
+}
+
+/// (Internal) Encode a delta record
+fn encode_record(addr: u32, word: u32) -> [u8; 8] {
+    let mut record = [0; 8];
+    record[..4].copy_from_slice(&addr.to_be_bytes());
+    record[4..].copy_from_slice(&word.to_be_bytes());
+    record
+}
+
+/// (Internal) Decode delta records, ignoring an eventual incomplete record at the end
+fn decode_delta(bytes: &[u8]) -> BTreeMap<u32, u32> {
+    bytes
+        .chunks_exact(8)
+        .map(|record| {
+            (
+                u32::from_be_bytes(record[..4].try_into().unwrap()),
+                u32::from_be_bytes(record[4..].try_into().unwrap()),
+            )
+        })
+        .collect()
+}
@@ -1,13 +1,14 @@
 //! The persistent memory component offers a simple storage that remains after reboot.
 //! See [`PersistentMem`] for more details.
 
-use lrvm::board::Bus;
+use lrvm::board::{Bus, ResetKind};
 use lrvm_tools::metadata::{DeviceMetadata, StorageType};
 
-/// The flash memory component contains a writable, persistent storage that does not reset with the motherboard.
-/// It is though reset when the VM is destroyed.
+/// The flash memory component contains a writable, persistent storage that survives warm resets and the ones requested by the guest.
+/// Cold resets (power-on) restore the content it was created with, and it is lost when the VM is destroyed.
 pub struct PersistentMem {
     storage: Vec<u32>,
+    initial: Vec<u32>,
     size: u32,
     hw_id: u64,
 }
@@ -21,13 +22,16 @@ impl PersistentMem {
         } else if size % 4 != 0 {
             Err("Flash memory's size must be a multiple of 4 bytes")
         } else {
+            let storage = vec![
+                0;
+                size.try_into().map_err(|_| {
+                    "Flash memory size cannot exceed your CPU architecture's supported size"
+                })?
+            ];
+
             Ok(Self {
-                storage: vec![
-                    0;
-                    size.try_into().map_err(|_| {
-                        "Flash memory size cannot exceed your CPU architecture's supported size"
-                    })?
-                ],
+                initial: storage.clone(),
+                storage,
                 size: size / 4,
                 hw_id,
             })
@@ -43,6 +47,7 @@ impl PersistentMem {
             .map_err(|_| "Flash memory's length cannot be larger than 2^32 words")?;
 
         Ok(Self {
+            initial: storage.clone(),
             storage,
             size: size / 4,
             hw_id,
@@ -81,6 +86,7 @@ impl PersistentMem {
         storage.resize(size as usize, 0);
 
         Ok(Self {
+            initial: storage.clone(),
             storage,
             size,
             hw_id,
@@ -117,7 +123,18 @@ impl Bus for PersistentMem {
         self.storage[addr as usize / 4] = word;
     }
 
-    fn reset(&mut self) {
-        self.storage = vec![0; self.storage.len()];
+    fn reset(&mut self, kind: ResetKind) {
+        if kind == ResetKind::Cold {
+            self.storage.copy_from_slice(&self.initial);
+        }
+    }
+
+    fn peek(&self, addr: u32) -> Option<u32> {
+        Some(self.storage[addr as usize / 4])
+    }
+
+    fn poke(&mut self, addr: u32, word: u32) -> bool {
+        self.storage[addr as usize / 4] = word;
+        true
     }
 }
@@ -0,0 +1,167 @@
+use lrvm_tools::{
+    asm::{ExtInstr, Instr, Program},
+    debug::{exec_vm, RunConfig},
+};
+
+use crate::{
+    storage::{
+        BlockDevice, BlockStats, BootRom, MemoryBlockBackend, BLK_DMA, BLK_ERR_COUNT, BLK_ERR_DMA,
+        BLK_ERR_IO, BLK_ERR_RANGE, BLK_HEADER_SIZE, BLK_IDENTIFY, BLK_ID_WRITABLE,
+        BLK_MAX_DMA_SIZE, BLK_READ, BLK_STATUS_BUSY, BLK_STATUS_OK, BLK_WRITE,
+    },
+    volatile_mem::Ram,
+};
+
+/// Address of the RAM
+const RAM: u32 = 0x400;
+
+/// Address the block device is mapped at
+const BLOCK: u32 = 0x500;
+
+/// Set the block device's registers and send it a command
+fn command(program: &mut Program, lba: u32, count: u32, flags: u32, command: u32) {
+    for (addr, word) in [
+        (0x10, lba),
+        (0x14, count),
+        (0x18, RAM),
+        (0x1C, flags),
+        (0x00, command),
+    ] {
+        program.append_all(ExtInstr::WriteAddrLit(BLOCK + addr, word).to_prog_words());
+    }
+}
+
+#[test]
+fn block_device_transfers() {
+    let mut disk = vec![0; 64];
+    disk[0..4].copy_from_slice(&[0x01, 0x23, 0x45, 0x67]);
+
+    let backend = MemoryBlockBackend::from_bytes(disk);
+    let device = BlockDevice::with_sector_size(Box::new(backend.clone()), 16, 0x2).unwrap();
+    let monitor = device.monitor();
+
+    let mut program = Program::new();
+
+    // Write the second sector through the sector buffer
+    program.append_all(
+        ExtInstr::WriteAddrLit(BLOCK + BLK_HEADER_SIZE + 0x0C, 0x89ABCDEF).to_prog_words(),
+    );
+    command(&mut program, 1, 1, 0, BLK_WRITE);
+
+    // Read the first two sectors to the RAM through DMA
+    command(&mut program, 0, 2, BLK_DMA, BLK_READ);
+
+    // Read the last sector and the one after it (out of range)
+    command(&mut program, 3, 2, BLK_DMA, BLK_READ);
+
+    program.append(Instr::Halt().into());
+
+    let (mut vm, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), RAM, 0x0).unwrap()),
+            Box::new(Ram::new(BLOCK - RAM, 0x1).unwrap()),
+            Box::new(device),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+
+    assert_eq!(&backend.bytes()[28..32], &[0x89, 0xAB, 0xCD, 0xEF]);
+
+    vm.map(|mem| {
+        assert_eq!(mem.peek(RAM), Some(0x01234567));
+        assert_eq!(mem.peek(RAM + 0x1C), Some(0x89ABCDEF));
+        assert_eq!(mem.peek(BLOCK + 0x04), Some(BLK_ERR_RANGE));
+        assert_eq!(mem.peek(BLOCK + 0x0C), Some(4));
+    });
+
+    assert_eq!(
+        monitor.stats(),
+        BlockStats {
+            reads: 1,
+            writes: 1,
+            flushes: 0,
+            bytes_read: 32,
+            bytes_written: 16,
+            errors: 1,
+        }
+    );
+}
+
+#[test]
+fn block_device_failures() {
+    let device = BlockDevice::new(Box::new(MemoryBlockBackend::new(2048)), 0x1);
+    let monitor = device.monitor();
+    monitor.fail_sector(2);
+
+    let mut vm = lrvm_tools::debug::prepare_vm(vec![Box::new(device)]);
+
+    vm.map(|mem| {
+        let mut ex = 0;
+
+        let mut run = |lba, command| {
+            mem.write(0x10, lba, &mut ex);
+            mem.write(0x14, 1, &mut ex);
+            mem.write(0x00, command, &mut ex);
+            mem.peek(0x04).unwrap()
+        };
+
+        assert_eq!(run(1, BLK_READ), BLK_STATUS_OK);
+        assert_eq!(run(2, BLK_READ), BLK_ERR_IO);
+
+        monitor.clear_faults();
+        monitor.fail_next(1);
+
+        assert_eq!(run(2, BLK_WRITE), BLK_ERR_IO);
+        assert_eq!(run(2, BLK_WRITE), BLK_STATUS_OK);
+
+        assert_eq!(run(0, BLK_IDENTIFY), BLK_STATUS_OK);
+        assert_eq!(mem.peek(BLK_HEADER_SIZE), Some(512));
+        assert_eq!(mem.peek(BLK_HEADER_SIZE + 0x04), Some(4));
+        assert_eq!(mem.peek(BLK_HEADER_SIZE + 0x08), Some(BLK_ID_WRITABLE));
+
+        assert_eq!(ex, 0);
+    });
+
+    assert_eq!(monitor.stats().errors, 2);
+}
+
+#[test]
+fn block_device_dma_limits() {
+    let device = BlockDevice::new(Box::new(MemoryBlockBackend::new(2048)), 0x1);
+    let monitor = device.monitor();
+
+    let mut vm = lrvm_tools::debug::prepare_vm(vec![Box::new(device)]);
+
+    vm.map(|mem| {
+        let mut ex = 0;
+
+        let mut run = |count, dma_addr, command| {
+            mem.write(0x14, count, &mut ex);
+            mem.write(0x18, dma_addr, &mut ex);
+            mem.write(0x1C, BLK_DMA, &mut ex);
+            mem.write(0x00, command, &mut ex);
+            mem.peek(0x04).unwrap()
+        };
+
+        // Transfers larger than the limit are rejected before touching the backend
+        assert_eq!(run(u32::MAX, 0x1000, BLK_READ), BLK_ERR_COUNT);
+        assert_eq!(
+            run(BLK_MAX_DMA_SIZE / 512 + 1, 0x1000, BLK_WRITE),
+            BLK_ERR_COUNT
+        );
+
+        // Transfers can't go past the end of the address space
+        assert_eq!(run(2, 0xFFFFFE00, BLK_READ), BLK_ERR_COUNT);
+        assert_eq!(run(1, 0xFFFFFF00, BLK_IDENTIFY), BLK_ERR_DMA);
+        assert_eq!(run(1, 0xFFFFFE00, BLK_READ), BLK_STATUS_BUSY);
+
+        assert_eq!(ex, 0);
+    });
+
+    assert_eq!(monitor.stats().errors, 4);
+}
@@ -1,6 +1,7 @@
+use lrvm::board::ResetKind;
 use lrvm_tools::{
-    asm::{ExtInstr, Instr, Program},
-    debug::{exec_vm, RunConfig},
+    asm::{ExtInstr, HwInfo, Instr, Program, Reg},
+    debug::{exec_vm, prepare_vm, run_vm, RunConfig},
 };
 
 use crate::storage::{BootRom, PersistentMem};
@@ -53,3 +54,78 @@ fn flash_mem() {
     assert_eq!(word_b, 0x89ABCDEF, "Expected word at address 0x00001008 to contain 0x89ABCDEF but it actually contains {:#010X}", word_b);
     assert_eq!(word_c, 0x00000000, "Expected word at address 0x00001010 to contain 0x01234567 but it actually contains {:#010X}", word_c);
 }
+
+#[test]
+fn flash_mem_reset_kinds() {
+    // Store the reason of the last reset at the flash's second word
+    let mut program = Program::from_instr(vec![Instr::Hwd(
+        Reg::a0,
+        0u8.into(),
+        HwInfo::ResetReason.code().into(),
+    )]);
+    program.append_all(ExtInstr::WriteAddr(0x1004, Reg::a0).to_prog_words());
+    program.append(Instr::Halt().into());
+
+    let (mut vm, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), 0x1000, 0x0).unwrap()),
+            Box::new(PersistentMem::from_with_size(vec![0, 0, 0x01234567], 0x10, 0x1).unwrap()),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+    assert_eq!(vm.map(|mem| mem.peek(0x1004)), Some(0x00));
+    assert!(vm.map(|mem| mem.poke(0x1008, 0x89ABCDEF)));
+
+    // Warm resets keep the flash's content
+    vm.reset(ResetKind::Warm);
+    run_vm(vm.cpu(), RunConfig::halt_on_ex());
+
+    assert_eq!(vm.cpu().last_reset(), ResetKind::Warm);
+    assert_eq!(vm.map(|mem| mem.peek(0x1004)), Some(0x01));
+    assert_eq!(vm.map(|mem| mem.peek(0x1008)), Some(0x89ABCDEF));
+
+    // Cold resets restore its initial content
+    vm.reset(ResetKind::Cold);
+
+    assert_eq!(vm.map(|mem| mem.peek(0x1004)), Some(0x00));
+    assert_eq!(vm.map(|mem| mem.peek(0x1008)), Some(0x01234567));
+}
+
+#[test]
+fn flash_mem_guest_reset() {
+    // Store the reason of the last reset, then reset the whole machine (forever)
+    let mut program = Program::from_instr(vec![Instr::Hwd(
+        Reg::a0,
+        0u8.into(),
+        HwInfo::ResetReason.code().into(),
+    )]);
+    program.append_all(ExtInstr::WriteAddr(0x1004, Reg::a0).to_prog_words());
+    program.append(Instr::Reset(0u8.into()).into());
+
+    let mut vm = prepare_vm(vec![
+        Box::new(BootRom::with_size(program.encode_words(), 0x1000, 0x0).unwrap()),
+        Box::new(PersistentMem::from_with_size(vec![0, 0, 0x01234567], 0x10, 0x1).unwrap()),
+    ]);
+
+    assert!(vm.map(|mem| mem.poke(0x1008, 0x89ABCDEF)));
+
+    // The program never halts, so run a few loops manually (the cycles counter is reset along with the CPU)
+    for _ in 0..20 {
+        vm.cpu().next();
+        assert_eq!(
+            vm.cpu().regs.et,
+            0,
+            "Unexpected exception occurred while running the VM!"
+        );
+    }
+
+    // Resets requested by the guest keep the flash's content too
+    assert_eq!(vm.cpu().last_reset(), ResetKind::Guest { supervisor: true });
+    assert_eq!(vm.map(|mem| mem.peek(0x1004)), Some(0x11));
+    assert_eq!(vm.map(|mem| mem.peek(0x1008)), Some(0x89ABCDEF));
+}
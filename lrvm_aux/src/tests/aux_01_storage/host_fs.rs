@@ -0,0 +1,231 @@
+use std::{fs, path::PathBuf};
+
+use lrvm::board::Bus;
+use lrvm_tools::{
+    asm::{ExtInstr, Instr, Program},
+    bytes::bytes_to_words,
+    debug::{exec_vm, RunConfig},
+    exceptions::AuxHwException,
+};
+
+use crate::{
+    storage::{
+        BootRom, HostFs, HFS_CLOSE, HFS_HEADER_SIZE, HFS_KIND_DIR, HFS_KIND_FILE, HFS_LIST,
+        HFS_MAX_HANDLES, HFS_OPEN, HFS_OPEN_CREATE, HFS_OPEN_READ, HFS_OPEN_TRUNCATE,
+        HFS_OPEN_WRITE, HFS_READ, HFS_SEEK, HFS_SEEK_END, HFS_STAT, HFS_WRITE,
+    },
+    tests::TempDir,
+};
+
+/// Address the host filesystem is mapped at
+const HFS: u32 = 0x200;
+
+/// Create a sandbox directory containing a file, inside a temporary directory
+fn sandbox(name: &str) -> (TempDir, PathBuf) {
+    let tmp = TempDir::new(&format!("host-fs-{}", name));
+    let dir = tmp.join("sandbox");
+
+    fs::create_dir_all(dir.join("sub")).unwrap();
+    fs::write(dir.join("in.txt"), "hello, world").unwrap();
+
+    (tmp, dir)
+}
+
+/// Put a path in the buffer and run a command, returning the raised exception
+fn command(hfs: &mut HostFs, path: &str, arg: u32, command: u32) -> Option<AuxHwException> {
+    for (i, word) in bytes_to_words(path).into_iter().enumerate() {
+        assert!(hfs.poke(HFS_HEADER_SIZE + i as u32 * 4, word));
+    }
+
+    let mut ex = 0;
+    hfs.write(0x08, path.len() as u32, &mut ex);
+    hfs.write(0x0C, arg, &mut ex);
+    hfs.write(0x00, command, &mut ex);
+
+    (ex != 0).then(|| AuxHwException::decode(ex).unwrap())
+}
+
+#[test]
+fn host_fs_copy() {
+    let (_tmp, dir) = sandbox("copy");
+
+    let mut program = Program::new();
+
+    let mut write = |addr: u32, word: u32| {
+        program.append_all(ExtInstr::WriteAddrLit(HFS + addr, word).to_prog_words());
+    };
+
+    for (path, flags) in [
+        ("in.txt", HFS_OPEN_READ),
+        (
+            "out.txt",
+            HFS_OPEN_WRITE | HFS_OPEN_CREATE | HFS_OPEN_TRUNCATE,
+        ),
+    ] {
+        for (i, word) in bytes_to_words(path).into_iter().enumerate() {
+            write(HFS_HEADER_SIZE + i as u32 * 4, word);
+        }
+
+        write(0x08, path.len() as u32);
+        write(0x0C, flags);
+        write(0x00, HFS_OPEN);
+    }
+
+    // Copy the first file to the second one
+    write(0x04, 1);
+    write(0x08, 64);
+    write(0x00, HFS_READ);
+
+    write(0x04, 2);
+    write(0x08, 12);
+    write(0x00, HFS_WRITE);
+    write(0x00, HFS_CLOSE);
+
+    program.append(Instr::Halt().into());
+
+    let (mut vm, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), HFS, 0x0).unwrap()),
+            Box::new(HostFs::new(&dir, true, 0x1).unwrap()),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+
+    vm.map(|mem| assert_eq!(mem.peek(HFS + 0x14), Some(12)));
+
+    assert_eq!(
+        fs::read_to_string(dir.join("out.txt")).unwrap(),
+        "hello, world"
+    );
+}
+
+#[test]
+fn host_fs_commands() {
+    let (_tmp, dir) = sandbox("commands");
+    let mut hfs = HostFs::new(&dir, false, 0x1).unwrap();
+    let mut ex = 0;
+
+    assert!(command(&mut hfs, "./in.txt", HFS_OPEN_READ, HFS_OPEN).is_none());
+    assert_eq!(hfs.read(0x04, &mut ex), 1);
+    assert_eq!(hfs.read(0x14, &mut ex), 12);
+
+    hfs.write(0x0C, -5i32 as u32, &mut ex);
+    hfs.write(0x10, HFS_SEEK_END, &mut ex);
+    hfs.write(0x00, HFS_SEEK, &mut ex);
+    assert_eq!(ex, 0);
+    assert_eq!(hfs.read(0x14, &mut ex), 7);
+
+    hfs.write(0x08, 8, &mut ex);
+    hfs.write(0x00, HFS_READ, &mut ex);
+    assert_eq!(ex, 0);
+    assert_eq!(hfs.read(0x14, &mut ex), 5);
+    assert_eq!(
+        hfs.read(HFS_HEADER_SIZE, &mut ex),
+        u32::from_be_bytes(*b"worl")
+    );
+
+    // Closed handles cannot be used anymore
+    hfs.write(0x00, HFS_CLOSE, &mut ex);
+    assert_eq!(ex, 0);
+    hfs.write(0x00, HFS_READ, &mut ex);
+    assert!(matches!(
+        AuxHwException::decode(ex),
+        Ok(AuxHwException::InvalidHandle)
+    ));
+
+    assert!(command(&mut hfs, "sub", 0, HFS_STAT).is_none());
+    assert_eq!(hfs.read(0x18, &mut ex), HFS_KIND_DIR);
+
+    // Entries are listed in alphabetical order
+    assert!(command(&mut hfs, "", 0, HFS_LIST).is_none());
+    assert_eq!(hfs.read(0x14, &mut ex), 6);
+    assert_eq!(hfs.read(0x18, &mut ex), HFS_KIND_FILE);
+    assert_eq!(
+        hfs.read(HFS_HEADER_SIZE, &mut ex),
+        u32::from_be_bytes(*b"in.t")
+    );
+
+    assert!(command(&mut hfs, "", 1, HFS_LIST).is_none());
+    assert_eq!(hfs.read(0x18, &mut ex), HFS_KIND_DIR);
+
+    assert!(command(&mut hfs, "", 2, HFS_LIST).is_none());
+    assert_eq!(hfs.read(0x14, &mut ex), 0);
+    assert_eq!(hfs.read(0x18, &mut ex), 0);
+
+    // Failures
+    assert!(matches!(
+        command(&mut hfs, "missing.txt", HFS_OPEN_READ, HFS_OPEN),
+        Some(AuxHwException::FileNotFound)
+    ));
+    assert!(matches!(
+        command(&mut hfs, "in.txt", HFS_OPEN_WRITE, HFS_OPEN),
+        Some(AuxHwException::PermissionDenied)
+    ));
+    assert!(matches!(
+        command(&mut hfs, "sub", HFS_OPEN_READ, HFS_OPEN),
+        Some(AuxHwException::WrongFileType)
+    ));
+    assert!(matches!(
+        command(&mut hfs, "in.txt", 0, HFS_LIST),
+        Some(AuxHwException::WrongFileType)
+    ));
+
+    for _ in 0..HFS_MAX_HANDLES {
+        assert!(command(&mut hfs, "in.txt", HFS_OPEN_READ, HFS_OPEN).is_none());
+    }
+
+    assert!(matches!(
+        command(&mut hfs, "in.txt", HFS_OPEN_READ, HFS_OPEN),
+        Some(AuxHwException::TooManyOpenFiles)
+    ));
+}
+
+#[test]
+fn host_fs_sandbox() {
+    let (tmp, dir) = sandbox("sandbox");
+    let secret = tmp.join("secret");
+    fs::write(&secret, "secret").unwrap();
+
+    let mut hfs = HostFs::new(&dir, true, 0x1).unwrap();
+    let outside = format!("../{}", secret.display());
+
+    for path in [
+        "../in.txt",
+        "sub/../../in.txt",
+        "/etc/passwd",
+        outside.as_str(),
+    ] {
+        assert!(matches!(
+            command(&mut hfs, path, HFS_OPEN_READ, HFS_OPEN),
+            Some(AuxHwException::PathOutsideSandbox)
+        ));
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::symlink;
+
+        symlink(&secret, dir.join("link")).unwrap();
+        symlink(tmp.join("missing"), dir.join("dangling")).unwrap();
+
+        assert!(matches!(
+            command(&mut hfs, "link", HFS_OPEN_READ, HFS_OPEN),
+            Some(AuxHwException::PathOutsideSandbox)
+        ));
+        assert!(matches!(
+            command(
+                &mut hfs,
+                "dangling",
+                HFS_OPEN_WRITE | HFS_OPEN_CREATE,
+                HFS_OPEN
+            ),
+            Some(AuxHwException::PathOutsideSandbox)
+        ));
+        assert!(!tmp.join("missing").exists());
+    }
+}
@@ -1,2 +1,5 @@
+pub mod block;
 pub mod bootrom;
 pub mod flash;
+pub mod host_fs;
+pub mod overlay;
@@ -0,0 +1,110 @@
+use std::{fs, path::PathBuf};
+
+use lrvm::board::{Bus, ResetKind};
+use lrvm_tools::{
+    asm::{ExtInstr, Instr, Program},
+    debug::{exec_vm, RunConfig},
+};
+
+use crate::{
+    storage::{BootRom, FileBackedMem},
+    tests::TempDir,
+};
+
+/// Address the file-backed memory is mapped at
+const MEM: u32 = 0x100;
+
+/// Create a base file in a temporary directory
+fn base_file(dir: &TempDir) -> PathBuf {
+    let path = dir.join("base");
+    fs::write(&path, [0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF]).unwrap();
+    path
+}
+
+#[test]
+fn overlay_commit_discard() {
+    let dir = TempDir::new("overlay-commit");
+    let path = base_file(&dir);
+
+    let mut program = Program::new();
+    program.append_all(ExtInstr::WriteAddrLit(MEM + 0x04, 0xAABBCCDD).to_prog_words());
+    program.append(Instr::Halt().into());
+
+    let mem = FileBackedMem::copy_on_write(&path, 0x1).unwrap();
+    let overlay = mem.overlay().unwrap();
+
+    let (mut vm, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), MEM, 0x0).unwrap()),
+            Box::new(mem),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+
+    // The write only reached the delta
+    assert_eq!(vm.map(|mem| mem.peek(MEM + 0x04)), Some(0xAABBCCDD));
+    assert_eq!(fs::read(&path).unwrap()[4..], [0x89, 0xAB, 0xCD, 0xEF]);
+    assert_eq!(overlay.dirty_words(), 1);
+
+    overlay.discard().unwrap();
+    assert_eq!(vm.map(|mem| mem.peek(MEM + 0x04)), Some(0x89ABCDEF));
+
+    assert!(vm.map(|mem| mem.poke(MEM, 0x00112233)));
+    overlay.commit().unwrap();
+
+    assert_eq!(overlay.dirty_words(), 0);
+    assert_eq!(fs::read(&path).unwrap()[..4], [0x00, 0x11, 0x22, 0x33]);
+    assert_eq!(vm.map(|mem| mem.peek(MEM)), Some(0x00112233));
+}
+
+#[test]
+fn overlay_delta_file() {
+    let dir = TempDir::new("overlay-delta");
+    let path = base_file(&dir);
+    let delta_path = dir.join("base.delta");
+    let export_path = dir.join("base.export");
+
+    let mut mem = FileBackedMem::copy_on_write_with_delta_file(&path, &delta_path, 0x1).unwrap();
+    let mut ex = 0;
+
+    mem.write(0x00, 0x11111111, &mut ex);
+    mem.write(0x00, 0x22222222, &mut ex);
+    mem.write(0x04, 0x33333333, &mut ex);
+    mem.reset(ResetKind::Warm);
+    assert_eq!(ex, 0);
+
+    // Syncing the delta file rewrites it with the last value of each word
+    assert_eq!(fs::read(&delta_path).unwrap().len(), 16);
+
+    mem.overlay().unwrap().export(&export_path).unwrap();
+
+    // Writes which were not synced yet are kept as well
+    mem.write(0x04, 0x44444444, &mut ex);
+    mem.write(0x04, 0x55555555, &mut ex);
+    assert_eq!(ex, 0);
+    drop(mem);
+
+    assert_eq!(fs::read(&delta_path).unwrap().len(), 32);
+
+    // Writes are applied again when the delta file is reopened, the last one winning
+    let mut mem = FileBackedMem::copy_on_write_with_delta_file(&path, &delta_path, 0x1).unwrap();
+    assert_eq!(mem.read(0x00, &mut ex), 0x22222222);
+    assert_eq!(mem.read(0x04, &mut ex), 0x55555555);
+
+    // The exported delta only contains the last value of each word
+    assert_eq!(fs::read(&export_path).unwrap().len(), 16);
+
+    let mut exported =
+        FileBackedMem::copy_on_write_with_delta_file(&path, &export_path, 0x2).unwrap();
+    assert_eq!(exported.read(0x00, &mut ex), 0x22222222);
+
+    mem.overlay().unwrap().discard().unwrap();
+    assert_eq!(fs::read(&delta_path).unwrap().len(), 0);
+    assert_eq!(mem.read(0x00, &mut ex), 0x01234567);
+    assert_eq!(fs::read(&path).unwrap()[..4], [0x01, 0x23, 0x45, 0x67]);
+}
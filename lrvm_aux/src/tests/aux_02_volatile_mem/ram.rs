@@ -1,9 +1,16 @@
+use std::{cell::RefCell, rc::Rc};
+
+use lrvm::board::ResetKind;
 use lrvm_tools::{
     asm::{ExtInstr, Instr, Program},
     debug::{exec_vm, RunConfig},
+    exceptions::AuxHwException,
 };
 
-use crate::{storage::BootRom, volatile_mem::Ram};
+use crate::{
+    storage::BootRom,
+    volatile_mem::{PoisonAction, Ram},
+};
 
 #[test]
 fn ram() {
@@ -53,3 +60,71 @@ fn ram() {
     assert_eq!(word_b, 0x89ABCDEF, "Expected word at address 0x00001008 to contain 0x89ABCDEF but it actually contains {:#010X}", word_b);
     assert_eq!(word_c, 0x00000000, "Expected word at address 0x00001010 to contain 0x01234567 but it actually contains {:#010X}", word_c);
 }
+
+#[test]
+fn ram_poisoned_exception() {
+    let mut program = Program::from_instr(ExtInstr::WriteAddrLit(0x1000, 0x01234567).to_instr());
+    program.append_all(ExtInstr::ReadAddr(0x1000).to_prog_words());
+    program.append_all(ExtInstr::ReadAddr(0x1008).to_prog_words());
+    program.append(Instr::Halt().into());
+
+    let mut ram = Ram::new(0x1000, 0x1).unwrap();
+    ram.poison(PoisonAction::Exception);
+
+    let (_, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), 0x1000, 0x0).unwrap()),
+            Box::new(ram),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    let ex = state
+        .ex
+        .expect("Reading a never-written word did not raise an exception");
+
+    assert!(matches!(
+        AuxHwException::decode(ex.associated),
+        Ok(AuxHwException::UninitializedRead)
+    ));
+}
+
+#[test]
+fn ram_poisoned_report() {
+    let mut program = Program::from_instr(ExtInstr::WriteAddrLit(0x1000, 0x01234567).to_instr());
+    program.append_all(ExtInstr::ReadAddr(0x1000).to_prog_words());
+    program.append_all(ExtInstr::ReadAddr(0x1008).to_prog_words());
+    program.append_all(ExtInstr::ReadAddr(0x100C).to_prog_words());
+    program.append(Instr::Halt().into());
+
+    let reported = Rc::new(RefCell::new(vec![]));
+    let reported_handler = Rc::clone(&reported);
+
+    let mut ram = Ram::new(0x1000, 0x1).unwrap();
+    ram.poison(PoisonAction::Report(Box::new(move |offset| {
+        reported_handler.borrow_mut().push(offset)
+    })));
+
+    let (mut vm, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), 0x1000, 0x0).unwrap()),
+            Box::new(ram),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+
+    assert_eq!(*reported.borrow(), vec![0x08, 0x0C]);
+
+    // Resetting clears the tracking
+    let mut ex = 0;
+    vm.reset(ResetKind::Warm);
+    vm.map(|mem| mem.read(0x1000, &mut ex));
+
+    assert_eq!(ex, 0);
+    assert_eq!(*reported.borrow(), vec![0x08, 0x0C, 0x00]);
+}
@@ -0,0 +1,91 @@
+use lrvm::board::ResetKind;
+use lrvm_tools::{
+    asm::{ExtInstr, Instr, Program},
+    debug::{exec_vm, RunConfig},
+    exceptions::AuxHwException,
+};
+
+use crate::{
+    storage::BootRom,
+    volatile_mem::{SparseRam, SPARSE_RAM_PAGE_SIZE},
+};
+
+/// Address the sparse RAM is mapped at
+const RAM: u32 = 0x1000;
+
+#[test]
+fn sparse_ram() {
+    let mut program = Program::from_instr(ExtInstr::WriteAddrLit(RAM, 0x01234567).to_instr());
+    program.append_all(ExtInstr::WriteAddrLit(RAM + 0x08, 0x89ABCDEF).to_prog_words());
+    program.append_all(ExtInstr::WriteAddrLit(RAM + 0x7FFF_0000, 0xAABBCCDD).to_prog_words());
+    program.append_all(ExtInstr::WriteAddrLit(RAM + 0x4000_0000, 0).to_prog_words());
+    program.append(Instr::Halt().into());
+
+    let ram = SparseRam::new(0x8000_0000, 0x1).unwrap();
+    let usage = ram.usage();
+
+    let (mut vm, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), RAM, 0x0).unwrap()),
+            Box::new(ram),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+
+    assert_eq!(vm.map(|mem| mem.peek(RAM)), Some(0x01234567));
+    assert_eq!(vm.map(|mem| mem.peek(RAM + 0x08)), Some(0x89ABCDEF));
+    assert_eq!(vm.map(|mem| mem.peek(RAM + 0x7FFF_0000)), Some(0xAABBCCDD));
+    assert_eq!(vm.map(|mem| mem.peek(RAM + 0x3FFF_FFFC)), Some(0));
+
+    // Writing a zero to an unallocated page does not allocate it
+    assert_eq!(usage.resident_pages(), 2);
+    assert_eq!(usage.resident_bytes(), 2 * u64::from(SPARSE_RAM_PAGE_SIZE));
+
+    vm.reset(ResetKind::Warm);
+
+    assert_eq!(usage.resident_pages(), 0);
+    assert_eq!(
+        usage.peak_resident_bytes(),
+        2 * u64::from(SPARSE_RAM_PAGE_SIZE)
+    );
+    assert_eq!(vm.map(|mem| mem.peek(RAM)), Some(0));
+}
+
+#[test]
+fn sparse_ram_resident_cap() {
+    let mut program = Program::from_instr(ExtInstr::WriteAddrLit(RAM, 0x01234567).to_instr());
+    program.append_all(ExtInstr::WriteAddrLit(RAM + 0x0FFC, 0x89ABCDEF).to_prog_words());
+    program.append_all(ExtInstr::WriteAddrLit(RAM + 0x1000, 0xAABBCCDD).to_prog_words());
+    program.append(Instr::Halt().into());
+
+    // The cap is rounded down to a single page
+    let ram = SparseRam::with_resident_cap(0x10_0000, 0x1FFF, 0x1).unwrap();
+    let usage = ram.usage();
+
+    let (mut vm, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), RAM, 0x0).unwrap()),
+            Box::new(ram),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    let ex = state
+        .ex
+        .expect("Writing beyond the resident cap did not raise an exception");
+
+    assert!(matches!(
+        AuxHwException::decode(ex.associated),
+        Ok(AuxHwException::ResidentCapExceeded)
+    ));
+
+    assert_eq!(usage.resident_pages(), 1);
+    assert_eq!(vm.map(|mem| mem.peek(RAM + 0x0FFC)), Some(0x89ABCDEF));
+    assert_eq!(vm.map(|mem| mem.peek(RAM + 0x1000)), Some(0));
+    assert!(!vm.map(|mem| mem.poke(RAM + 0x1000, 0x1)));
+}
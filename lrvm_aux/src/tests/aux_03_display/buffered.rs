@@ -1,5 +1,3 @@
-use std::sync::{Arc, Mutex};
-
 use lrvm_tools::{
     asm::{ExtInstr, Instr, Program, Reg},
     debug::{exec_vm, RunConfig},
@@ -47,36 +45,12 @@ fn buffered_display() {
     let mut prog = display_prog("Hello world!", 0x1000, 0x1100 - 0x04).unwrap();
     prog.append(Instr::Halt().into());
 
-    #[allow(clippy::mutex_atomic)]
-    let received_msg = Arc::new(Mutex::new(false));
-    let received_msg_closure = Arc::clone(&received_msg);
+    let (display, capture, log) = BufferedDisplay::new_capture_strict(0x100, 0x1).unwrap();
 
     let (_, state) = exec_vm(
         vec![
             Box::new(BootRom::with_size(prog.encode_words(), 0x1000, 0x0).unwrap()),
-            Box::new(
-                BufferedDisplay::new(
-                    0x100,
-                    Box::new(move |msg| {
-                        let mut received_msg = received_msg_closure.lock().unwrap();
-
-                        assert!(
-                            !*received_msg,
-                            "Received a message twice (second message: {})",
-                            msg.unwrap_or("<Invalid UTF-8 string>")
-                        );
-
-                        let msg = msg
-                            .expect("Invalid UTF-8 message received")
-                            .trim_end_matches(char::from(0));
-                        assert_eq!(msg, "Hello world!", "Invalid message received: {}", msg);
-
-                        *received_msg = true;
-                    }),
-                    0x1,
-                )
-                .unwrap(),
-            ),
+            Box::new(display),
         ],
         RunConfig::halt_on_ex(),
     );
@@ -85,8 +59,85 @@ fn buffered_display() {
         panic!("Unexpected exception occurred while running the VM!");
     }
 
+    let messages = log.messages();
+
     assert!(
-        *received_msg.lock().unwrap(),
+        !messages.is_empty(),
         "No message received by buffered display"
     );
+    assert_eq!(
+        messages.len(),
+        1,
+        "Received a message twice (messages: {:?})",
+        messages
+    );
+
+    let msg = messages[0]
+        .as_ref()
+        .expect("Invalid UTF-8 message received");
+    assert_eq!(msg, "Hello world!", "Invalid message received: {}", msg);
+
+    assert_eq!(capture.text(), "Hello world!");
+}
+
+#[test]
+fn buffered_display_lossy() {
+    let mut instr = ExtInstr::WriteAddrLit(0x1000, 0x48_69_FF_00).to_instr();
+    instr.extend(ExtInstr::WriteAddrLit(0x1100 - 0x04, 0xAA).to_instr());
+
+    let mut prog = Program::from_instr(instr);
+    prog.append(Instr::Halt().into());
+
+    let (display, capture) = BufferedDisplay::new_capture(0x100, 0x1).unwrap();
+
+    let (_, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(prog.encode_words(), 0x1000, 0x0).unwrap()),
+            Box::new(display),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    if state.ex.is_some() {
+        panic!("Unexpected exception occurred while running the VM!");
+    }
+
+    assert_eq!(capture.text(), "Hi\u{FFFD}");
+}
+
+#[test]
+fn buffered_display_strict() {
+    let mut instr = ExtInstr::WriteAddrLit(0x1000, 0x48_69_FF_00).to_instr();
+    instr.extend(ExtInstr::WriteAddrLit(0x1100 - 0x04, 0xAA).to_instr());
+    instr.extend(ExtInstr::WriteAddrLit(0x1000, 0x48_69_00_00).to_instr());
+    instr.extend(ExtInstr::WriteAddrLit(0x1100 - 0x04, 0xAA).to_instr());
+
+    let mut prog = Program::from_instr(instr);
+    prog.append(Instr::Halt().into());
+
+    let (display, capture, log) = BufferedDisplay::new_capture_strict(0x100, 0x1).unwrap();
+
+    let (_, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(prog.encode_words(), 0x1000, 0x0).unwrap()),
+            Box::new(display),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    if state.ex.is_some() {
+        panic!("Unexpected exception occurred while running the VM!");
+    }
+
+    // The invalid message is only recorded in the log, with its raw content
+    let errors = log.errors();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].valid_up_to(), 2);
+
+    let messages = log.messages();
+    assert_eq!(messages.len(), 2);
+    assert!(matches!(&messages[0], Err((_, bytes)) if bytes[..4] == [0x48, 0x69, 0xFF, 0x00]));
+    assert_eq!(messages[1], Ok("Hi".to_string()));
+
+    assert_eq!(capture.text(), "Hi");
 }
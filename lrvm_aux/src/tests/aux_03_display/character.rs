@@ -2,43 +2,28 @@ use crate::display::CharDisplay;
 use crate::storage::BootRom;
 use lrvm_tools::asm::{ExtInstr, Instr, Program};
 use lrvm_tools::debug::{exec_vm, RunConfig};
-use std::sync::{Arc, Mutex};
 
-fn display_prog(character: char, display_addr: u32) -> Program {
-    Program::from_instr(ExtInstr::WriteAddrLit(display_addr, character as u32).to_instr())
+fn display_prog(chars: &[u32], display_addr: u32) -> Program {
+    let mut instr = vec![];
+
+    for c in chars {
+        instr.extend(ExtInstr::WriteAddrLit(display_addr, *c).to_instr());
+    }
+
+    instr.push(Instr::Halt());
+    Program::from_instr(instr)
 }
 
 #[test]
-fn buffered_display() {
-    let mut prog = display_prog('Z', 0x1000);
-    prog.append(Instr::Halt().into());
+fn char_display() {
+    let prog = display_prog(&['Z' as u32, '\u{E9}' as u32, 0xD800, '!' as u32], 0x1000);
 
-    #[allow(clippy::mutex_atomic)]
-    let received_msg = Arc::new(Mutex::new(false));
-    let received_msg_closure = Arc::clone(&received_msg);
+    let (display, capture) = CharDisplay::new_capture(0x1);
 
     let (_, state) = exec_vm(
         vec![
             Box::new(BootRom::with_size(prog.encode_words(), 0x1000, 0x0).unwrap()),
-            Box::new(CharDisplay::new(
-                Box::new(move |msg| {
-                    let mut received_msg = received_msg_closure.lock().unwrap();
-
-                    assert!(
-                        !*received_msg,
-                        "Received a message twice (second message: {})",
-                        msg.map(String::from)
-                            .unwrap_or_else(|_| String::from("<Invalid UTF-8 character>"))
-                    );
-
-                    let msg = msg.expect("Invalid UTF-8 character received");
-
-                    assert_eq!(msg, 'Z', "Invalid character received: {}", msg);
-
-                    *received_msg = true;
-                }),
-                0x1,
-            )),
+            Box::new(display),
         ],
         RunConfig::halt_on_ex(),
     );
@@ -47,8 +32,5 @@ fn buffered_display() {
         panic!("Unexpected exception occurred while running the VM!");
     }
 
-    assert!(
-        *received_msg.lock().unwrap(),
-        "No message received by buffered display"
-    );
+    assert_eq!(capture.text(), "Z\u{E9}\u{FFFD}!");
 }
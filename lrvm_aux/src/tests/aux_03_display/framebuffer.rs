@@ -0,0 +1,102 @@
+use lrvm::board::Bus;
+use lrvm_tools::{
+    asm::{ExtInstr, Instr, Program},
+    debug::{exec_vm, RunConfig},
+};
+
+use crate::{
+    display::{CaptureBackend, Framebuffer, PixelFormat, FB_FLIP, FB_HEADER_SIZE, FB_PRESENT},
+    storage::BootRom,
+};
+
+/// Address of the framebuffer
+const FB: u32 = 0x1000;
+
+#[test]
+fn framebuffer_flip() {
+    let capture = CaptureBackend::new();
+
+    let mut program = Program::new();
+
+    // Draw a 2x2 frame, flip, then draw a single pixel in the other buffer and present it
+    for (i, pixel) in [0xFF0000FF, 0x00FF00FF, 0x0000FFFF, 0xFFFFFFFF]
+        .iter()
+        .enumerate()
+    {
+        program.append_all(
+            ExtInstr::WriteAddrLit(FB + FB_HEADER_SIZE + i as u32 * 4, *pixel).to_prog_words(),
+        );
+    }
+
+    program.append_all(ExtInstr::WriteAddrLit(FB, FB_FLIP).to_prog_words());
+    program.append_all(ExtInstr::WriteAddrLit(FB + FB_HEADER_SIZE, 0x12345678).to_prog_words());
+    program.append_all(ExtInstr::WriteAddrLit(FB, FB_PRESENT).to_prog_words());
+    program.append(Instr::Halt().into());
+
+    let (mut vm, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), FB, 0x0).unwrap()),
+            Box::new(
+                Framebuffer::new(2, 2, PixelFormat::Rgba8888, Box::new(capture.clone()), 0x1)
+                    .unwrap(),
+            ),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+
+    let frames = capture.take_frames();
+    assert_eq!(frames.len(), 2);
+
+    assert_eq!(frames[0].pixel(0, 0), [0xFF, 0x00, 0x00, 0xFF]);
+    assert_eq!(frames[0].pixel(1, 0), [0x00, 0xFF, 0x00, 0xFF]);
+    assert_eq!(frames[0].pixel(0, 1), [0x00, 0x00, 0xFF, 0xFF]);
+    assert_eq!(frames[0].pixel(1, 1), [0xFF, 0xFF, 0xFF, 0xFF]);
+
+    assert_eq!(frames[1].pixel(0, 0), [0x12, 0x34, 0x56, 0x78]);
+    assert_eq!(frames[1].pixel(1, 1), [0x00, 0x00, 0x00, 0x00]);
+
+    vm.map(|mem| {
+        assert_eq!(mem.peek(FB + 0x04), Some(2));
+        assert_eq!(mem.peek(FB + 0x08), Some(2));
+        assert_eq!(mem.peek(FB + 0x10), Some(2));
+    });
+}
+
+#[test]
+fn framebuffer_rgb565_images() {
+    let capture = CaptureBackend::new();
+
+    let mut fb =
+        Framebuffer::new(3, 1, PixelFormat::Rgb565, Box::new(capture.clone()), 0x1).unwrap();
+
+    // Three pixels (red, green, blue) packed in two words
+    assert!(fb.poke(FB_HEADER_SIZE, 0xF800_07E0));
+    assert!(fb.poke(FB_HEADER_SIZE + 4, 0x001F_0000));
+    assert!(!fb.poke(0x04, 0));
+
+    let mut ex = 0;
+    fb.write(0x00, FB_PRESENT, &mut ex);
+    assert_eq!(ex, 0);
+
+    let frame = capture.last_frame().unwrap();
+    assert_eq!(frame.pixel(0, 0), [0xFF, 0x00, 0x00, 0xFF]);
+    assert_eq!(frame.pixel(1, 0), [0x00, 0xFF, 0x00, 0xFF]);
+    assert_eq!(frame.pixel(2, 0), [0x00, 0x00, 0xFF, 0xFF]);
+
+    let mut ppm = b"P6\n3 1\n255\n".to_vec();
+    ppm.extend_from_slice(&[0xFF, 0, 0, 0, 0xFF, 0, 0, 0, 0xFF]);
+    assert_eq!(frame.to_ppm(), ppm);
+
+    let png = frame.to_png();
+    assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+    assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xAE, 0x42, 0x60, 0x82]));
+
+    // Writing to the header's read-only words is forbidden
+    fb.write(0x04, 1, &mut ex);
+    assert_ne!(ex, 0);
+}
@@ -1,2 +1,5 @@
 pub mod buffered;
 pub mod character;
+pub mod framebuffer;
+pub mod number;
+pub mod text;
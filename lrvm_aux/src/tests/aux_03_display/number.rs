@@ -0,0 +1,38 @@
+use crate::display::NumberDisplay;
+use crate::storage::BootRom;
+use lrvm_tools::asm::{ExtInstr, Instr, Program};
+use lrvm_tools::debug::{exec_vm, RunConfig};
+
+#[test]
+fn number_display() {
+    let mut instr = vec![];
+
+    for (offset, num) in [
+        (0x00, 0x2A),
+        (0x04, 0x2A),
+        (0x18, 42),
+        (0x1C, 42),
+        (0x08, 7),
+    ] {
+        instr.extend(ExtInstr::WriteAddrLit(0x1000 + offset, num).to_instr());
+    }
+
+    instr.push(Instr::Halt());
+    let prog = Program::from_instr(instr);
+
+    let (display, capture) = NumberDisplay::new_capture(0x1);
+
+    let (_, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(prog.encode_words(), 0x1000, 0x0).unwrap()),
+            Box::new(display),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    if state.ex.is_some() {
+        panic!("Unexpected exception occurred while running the VM!");
+    }
+
+    assert_eq!(capture.text(), "0x2A\n0x0000002A\n4200000000427\n");
+}
@@ -0,0 +1,153 @@
+use std::{cell::RefCell, io::Write, rc::Rc};
+
+use lrvm::board::Bus;
+use lrvm_tools::{
+    asm::{ExtInstr, Instr, Program},
+    debug::{exec_vm, RunConfig},
+};
+
+use crate::{
+    display::{
+        AnsiTerminalBackend, SnapshotBackend, TextCell, TextDisplay, TEXT_CURSOR_VISIBLE,
+        TEXT_CUSTOM_FG, TEXT_HEADER_SIZE, TEXT_REFRESH, TEXT_SCROLL,
+    },
+    storage::BootRom,
+};
+
+/// Address of the text display
+const TEXT: u32 = 0x1000;
+
+/// Output shared with the test
+#[derive(Clone, Default)]
+struct SharedOutput(Rc<RefCell<Vec<u8>>>);
+
+impl Write for SharedOutput {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn text_display_scroll() {
+    let snapshot = SnapshotBackend::new();
+
+    let mut program = Program::new();
+
+    // Write "Hi" on the first row and "Yo" on the last one, then scroll so "Yo" moves to the middle row
+    for (cell, ch) in [(0, 'H'), (1, 'i'), (8, 'Y'), (9, 'o')] {
+        program.append_all(
+            ExtInstr::WriteAddrLit(TEXT + TEXT_HEADER_SIZE + cell * 4, ch as u32).to_prog_words(),
+        );
+    }
+
+    program.append_all(ExtInstr::WriteAddrLit(TEXT + 0x0C, 2).to_prog_words());
+    program.append_all(ExtInstr::WriteAddrLit(TEXT + 0x14, TEXT_CURSOR_VISIBLE).to_prog_words());
+    program.append_all(ExtInstr::WriteAddrLit(TEXT, TEXT_SCROLL).to_prog_words());
+    program.append_all(ExtInstr::WriteAddrLit(TEXT, TEXT_REFRESH).to_prog_words());
+    program.append(Instr::Halt().into());
+
+    let (mut vm, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), TEXT, 0x0).unwrap()),
+            Box::new(TextDisplay::new(4, 3, Box::new(snapshot.clone()), 0x1).unwrap()),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+
+    assert_eq!(snapshot.text().unwrap(), "\nYo\n");
+    assert_eq!(snapshot.screen().unwrap().cursor(), Some((2, 0)));
+
+    vm.map(|mem| {
+        assert_eq!(mem.peek(TEXT + 0x04), Some(4));
+        assert_eq!(mem.peek(TEXT + 0x08), Some(3));
+        assert_eq!(mem.peek(TEXT + 0x18), Some(1));
+    });
+}
+
+#[test]
+fn text_display_ansi_diff() {
+    let output = SharedOutput::default();
+
+    let mut display = TextDisplay::new(
+        3,
+        1,
+        Box::new(AnsiTerminalBackend::with_output(Box::new(output.clone()))),
+        0x1,
+    )
+    .unwrap();
+
+    let cell = |ch, fg, attrs| {
+        TextCell {
+            ch,
+            fg,
+            bg: 0,
+            attrs,
+        }
+        .encode()
+    };
+
+    let mut ex = 0;
+
+    assert!(display.poke(TEXT_HEADER_SIZE, cell('a', 0, 0)));
+    assert!(display.poke(TEXT_HEADER_SIZE + 4, cell('b', 0, 0)));
+    display.write(0x00, TEXT_REFRESH, &mut ex);
+
+    let first = String::from_utf8(output.0.take()).unwrap();
+    assert!(first.contains("\x1b[2J"));
+    assert!(first.contains('a') && first.contains('b'));
+
+    // Only the changed cell is redrawn
+    assert!(display.poke(TEXT_HEADER_SIZE + 4, cell('c', 9, TEXT_CUSTOM_FG)));
+    display.write(0x00, TEXT_REFRESH, &mut ex);
+
+    let second = String::from_utf8(output.0.take()).unwrap();
+    assert!(!second.contains("\x1b[2J"));
+    assert!(!second.contains('a'));
+    assert!(second.contains("\x1b[1;2H\x1b[0;91mc"));
+
+    assert_eq!(ex, 0);
+}
+
+#[test]
+fn text_display_ansi_control_chars() {
+    let output = SharedOutput::default();
+
+    let mut display = TextDisplay::new(
+        4,
+        1,
+        Box::new(AnsiTerminalBackend::with_output(Box::new(output.clone()))),
+        0x1,
+    )
+    .unwrap();
+
+    for (i, ch) in ['\x1b', '\n', '\x7f', '\u{9b}'].into_iter().enumerate() {
+        let cell = TextCell {
+            ch,
+            fg: 0,
+            bg: 0,
+            attrs: 0,
+        };
+
+        assert!(display.poke(TEXT_HEADER_SIZE + i as u32 * 4, cell.encode()));
+    }
+
+    let mut ex = 0;
+    display.write(0x00, TEXT_REFRESH, &mut ex);
+    assert_eq!(ex, 0);
+
+    // Only the backend's own escape sequences reach the terminal
+    let out = String::from_utf8(output.0.take()).unwrap();
+    assert_eq!(out.matches('\x1b').count(), out.matches("\x1b[").count());
+    assert!(!out.contains(['\n', '\x7f', '\u{9b}']));
+    assert_eq!(out.matches('�').count(), 4);
+}
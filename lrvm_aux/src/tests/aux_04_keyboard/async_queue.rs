@@ -0,0 +1,173 @@
+use std::thread;
+
+use lrvm_tools::{
+    asm::{ExtInstr, Instr, Program, Reg},
+    debug::{prepare_vm, run_vm, RunConfig},
+};
+
+use crate::{
+    keyboard::{
+        AsyncKeyboard, InputSource, KeyEvent, KBD_DATA_READY, KBD_INTERRUPT, KBD_OVERFLOW,
+        KEY_ENTER, MOD_CTRL,
+    },
+    storage::BootRom,
+    volatile_mem::Ram,
+};
+
+/// Address of the RAM
+const RAM: u32 = 0x100;
+
+/// Address the keyboard is mapped at
+const KEYBOARD: u32 = 0x200;
+
+/// Address of the exception handler
+const HANDLER: u32 = 0x80;
+
+#[test]
+fn async_keyboard_queue() {
+    let keyboard = AsyncKeyboard::with_queue_size(3, 0x1).unwrap();
+    let producer = keyboard.producer();
+
+    // Pop two events and store them in RAM
+    let mut program = Program::new();
+
+    for i in 0..2 {
+        program.append_all(ExtInstr::ReadAddr(KEYBOARD).to_prog_words());
+        program.append(Instr::Cpy(Reg::rr0, (RAM as u16 + i * 4).into()).into());
+        program.append(Instr::Wea(Reg::rr0.into(), 0u8.into(), 0u8.into()).into());
+    }
+
+    program.append(Instr::Halt().into());
+
+    let mut vm = prepare_vm(vec![
+        Box::new(BootRom::with_size(program.encode_words(), RAM, 0x0).unwrap()),
+        Box::new(Ram::new(KEYBOARD - RAM, 0x2).unwrap()),
+        Box::new(keyboard),
+    ]);
+
+    // Only the first three events fit in the queue
+    assert!(!producer.type_text("a\n"));
+    assert_eq!(producer.pending(), 3);
+
+    let state = run_vm(vm.cpu(), RunConfig::halt_on_ex());
+
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+
+    vm.map(|mem| {
+        let mut ex = 0;
+
+        assert_eq!(
+            mem.peek(RAM),
+            Some(KeyEvent::press(b'a'.into(), 0).encode())
+        );
+        assert_eq!(
+            mem.peek(RAM + 0x04),
+            Some(KeyEvent::release(b'a'.into(), 0).encode())
+        );
+
+        assert_eq!(
+            mem.peek(KEYBOARD + 0x04),
+            Some(KBD_DATA_READY | KBD_OVERFLOW)
+        );
+        assert_eq!(mem.peek(KEYBOARD + 0x08), Some(1));
+        assert_eq!(
+            KeyEvent::decode(mem.read(KEYBOARD, &mut ex)),
+            KeyEvent::press(KEY_ENTER, 0)
+        );
+
+        mem.write(KEYBOARD + 0x04, KBD_OVERFLOW, &mut ex);
+        assert_eq!(mem.peek(KEYBOARD + 0x04), Some(0));
+        assert_eq!(ex, 0);
+    });
+}
+
+#[test]
+fn async_keyboard_interruption() {
+    let keyboard = AsyncKeyboard::new(0x1);
+    let producer = keyboard.producer();
+
+    let mut program = Program::new();
+    program.append_all(ExtInstr::SetReg(Reg::ev, HANDLER).to_prog_words());
+    program.append_all(ExtInstr::WriteAddrLit(KEYBOARD + 0x10, 0x24).to_prog_words());
+    program.append_all(ExtInstr::WriteAddrLit(KEYBOARD + 0x0C, KBD_INTERRUPT).to_prog_words());
+
+    // Switch to userland and loop forever, until the keyboard interrupts the loop
+    program.append(Instr::Cpy(Reg::smt, 0u16.into()).into());
+    program.append(Instr::Jpr(0u16.into()).into());
+
+    // The exception handler halts the processor
+    let mut words = program.encode_words();
+    words.resize(HANDLER as usize / 4, 0);
+    words.extend(Program::from_instr(vec![Instr::Halt()]).encode_words());
+
+    let mut vm = prepare_vm(vec![
+        Box::new(BootRom::with_size(words, RAM, 0x0).unwrap()),
+        Box::new(Ram::new(KEYBOARD - RAM, 0x2).unwrap()),
+        Box::new(keyboard),
+    ]);
+
+    // Events are pushed from another thread
+    thread::spawn(move || assert!(producer.push(KeyEvent::press(b'c'.into(), MOD_CTRL))))
+        .join()
+        .unwrap();
+
+    let state = run_vm(vm.cpu(), RunConfig::new().with_cycles_limit(Some(1000)));
+
+    assert_eq!(state.addr, HANDLER, "Exception handler was not run");
+    assert_eq!(vm.cpu().regs.et, (0xF0 << 16) + 0x24);
+
+    vm.map(|mem| {
+        assert_eq!(
+            mem.peek(KEYBOARD),
+            Some(KeyEvent::press(b'c'.into(), MOD_CTRL).encode())
+        )
+    });
+}
+
+#[test]
+fn async_keyboard_input() {
+    // The queue only has room for a single character at once
+    let keyboard = AsyncKeyboard::with_queue_size(3, 0x1)
+        .unwrap()
+        .with_input(InputSource::from_text("ab\n"));
+
+    // Pop all events and store them in RAM
+    let mut program = Program::new();
+
+    for i in 0..6 {
+        program.append_all(ExtInstr::ReadAddr(KEYBOARD).to_prog_words());
+        program.append(Instr::Cpy(Reg::rr0, (RAM as u16 + i * 4).into()).into());
+        program.append(Instr::Wea(Reg::rr0.into(), 0u8.into(), 0u8.into()).into());
+    }
+
+    program.append(Instr::Halt().into());
+
+    let mut vm = prepare_vm(vec![
+        Box::new(BootRom::with_size(program.encode_words(), RAM, 0x0).unwrap()),
+        Box::new(Ram::new(KEYBOARD - RAM, 0x2).unwrap()),
+        Box::new(keyboard),
+    ]);
+
+    let state = run_vm(vm.cpu(), RunConfig::halt_on_ex());
+
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+
+    vm.map(|mem| {
+        let expected = [b'a'.into(), b'b'.into(), KEY_ENTER]
+            .into_iter()
+            .flat_map(|code| [KeyEvent::press(code, 0), KeyEvent::release(code, 0)]);
+
+        for (i, event) in expected.enumerate() {
+            assert_eq!(mem.peek(RAM + i as u32 * 4), Some(event.encode()));
+        }
+
+        // No event was lost
+        assert_eq!(mem.peek(KEYBOARD + 0x04), Some(0));
+    });
+}
@@ -0,0 +1,197 @@
+use std::fs;
+
+use lrvm::board::Bus;
+use lrvm_tools::{
+    asm::{ExtInstr, Instr, Program},
+    bytes::words_to_bytes,
+    config::InputScript,
+    debug::{exec_vm, RunConfig},
+    exceptions::AuxHwException,
+};
+
+use crate::{
+    keyboard::{EndOfInput, InputSource, SyncCharKeyboard, SyncLineKeyboard},
+    storage::BootRom,
+    tests::TempDir,
+};
+
+/// Capacity of the line keyboards, in bytes
+const CAPACITY: u32 = 0x40;
+
+/// Trigger a line input and get the line in the keyboard's buffer, along with the raised exception
+fn input_line(keyb: &mut SyncLineKeyboard) -> (String, u16) {
+    let mut ex = 0;
+    keyb.write(CAPACITY - 4, 0xAA, &mut ex);
+
+    let words = (0..CAPACITY / 4 - 1)
+        .map(|i| keyb.peek(i * 4).unwrap())
+        .collect::<Vec<_>>();
+
+    // The line ends at the first NUL character, as the buffer is not cleared between inputs
+    let bytes = words_to_bytes(words);
+    let len = bytes
+        .iter()
+        .position(|byte| *byte == 0)
+        .unwrap_or(bytes.len());
+
+    (String::from_utf8(bytes[..len].to_vec()).unwrap(), ex)
+}
+
+/// Trigger a character input and get the keyboard's buffer, along with the raised exception
+fn input_char(keyb: &mut SyncCharKeyboard) -> (char, u16) {
+    let mut ex = 0;
+    keyb.write(0x04, 0x01, &mut ex);
+
+    (char::from_u32(keyb.peek(0x00).unwrap()).unwrap(), ex)
+}
+
+#[test]
+fn input_source_lines() {
+    let source = InputSource::from_lines(["first", "second"]).on_end(EndOfInput::Exception);
+    let progress = source.progress();
+
+    let mut keyb = SyncLineKeyboard::with_source(CAPACITY, source, 0x1).unwrap();
+
+    assert_eq!(input_line(&mut keyb), ("first".to_string(), 0));
+    assert_eq!(input_line(&mut keyb), ("second".to_string(), 0));
+    assert_eq!(progress.consumed(), 2);
+    assert!(!progress.exhausted());
+
+    let (line, ex) = input_line(&mut keyb);
+    assert_eq!(line, "");
+    assert!(matches!(
+        AuxHwException::decode(ex),
+        Ok(AuxHwException::EndOfInput)
+    ));
+
+    assert_eq!(progress.consumed(), 2);
+    assert!(progress.exhausted());
+}
+
+#[test]
+fn input_source_chars() {
+    let source = InputSource::from_text("a\r\nb").on_end(EndOfInput::Marker('\u{4}'));
+    let progress = source.progress();
+
+    let mut keyb = SyncCharKeyboard::with_source(source, 0x1);
+
+    for expected in ['a', '\r', '\n', 'b', '\u{4}', '\u{4}'] {
+        assert_eq!(input_char(&mut keyb), (expected, 0));
+    }
+
+    assert_eq!(progress.consumed(), 4);
+    assert!(progress.exhausted());
+
+    // Lines are taken from the same kind of text, without their line terminator
+    let mut keyb =
+        SyncLineKeyboard::with_source(CAPACITY, InputSource::from_text("a\r\nb"), 0x1).unwrap();
+
+    assert_eq!(input_line(&mut keyb), ("a".to_string(), 0));
+    assert_eq!(input_line(&mut keyb), ("b".to_string(), 0));
+    assert_eq!(input_line(&mut keyb), ("".to_string(), 0));
+}
+
+#[test]
+fn input_source_script() {
+    let script = InputScript::new();
+    script.push_line("first");
+
+    let source = InputSource::from_script(script.clone()).on_end(EndOfInput::Exception);
+    let progress = source.progress();
+
+    let mut keyb = SyncLineKeyboard::with_source(CAPACITY, source, 0x1).unwrap();
+
+    assert_eq!(input_line(&mut keyb), ("first".to_string(), 0));
+    assert_eq!(script.remaining(), 0);
+
+    let (_, ex) = input_line(&mut keyb);
+    assert_eq!(ex, AuxHwException::EndOfInput.encode());
+    assert!(progress.exhausted());
+
+    // Input pushed by the host once the script is exhausted is still provided
+    script.push_str("second\nthird");
+
+    assert_eq!(input_line(&mut keyb), ("second".to_string(), 0));
+    assert_eq!(script.remaining(), 5);
+    assert_eq!(input_line(&mut keyb), ("third".to_string(), 0));
+    assert_eq!(progress.consumed(), 3);
+    assert!(!progress.exhausted());
+}
+
+#[test]
+fn input_source_record_log() {
+    let dir = TempDir::new("input-record-log");
+    let text_path = dir.join("text");
+    let log_path = dir.join("log");
+
+    fs::write(&text_path, "héllo \"world\"\n\ttab\\\n").unwrap();
+
+    let source = InputSource::from_file(&text_path)
+        .unwrap()
+        .record_to(&log_path)
+        .unwrap();
+
+    let mut keyb = SyncLineKeyboard::with_source(CAPACITY, source, 0x1).unwrap();
+
+    assert_eq!(input_line(&mut keyb).0, "héllo \"world\"");
+    assert_eq!(input_line(&mut keyb).0, "\ttab\\");
+    drop(keyb);
+
+    assert_eq!(
+        fs::read_to_string(&log_path).unwrap(),
+        "h\\u{e9}llo \\\"world\\\"\\n\n\\ttab\\\\\\n\n"
+    );
+
+    // Replaying the log provides the same inputs
+    let source = InputSource::from_record_log(&log_path).unwrap();
+    let progress = source.progress();
+
+    let mut keyb = SyncLineKeyboard::with_source(CAPACITY, source, 0x1).unwrap();
+
+    assert_eq!(input_line(&mut keyb).0, "héllo \"world\"");
+    assert_eq!(input_line(&mut keyb).0, "\ttab\\");
+    assert_eq!(progress.consumed(), 2);
+
+    fs::write(&log_path, "valid\n\\q\n").unwrap();
+    assert!(InputSource::from_record_log(&log_path).is_err());
+}
+
+#[test]
+fn input_source_halt() {
+    let mut prog = Program::new();
+
+    for _ in 0..3 {
+        prog.append_all(ExtInstr::WriteAddrLit(0x1000 + CAPACITY - 4, 0xAA).to_prog_words());
+    }
+
+    prog.append(Instr::Jpr(0u16.into()).into());
+
+    let source = InputSource::from_lines(["only"]).on_end(EndOfInput::Halt);
+    let progress = source.progress();
+
+    let (mut vm, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(prog.encode_words(), 0x1000, 0x0).unwrap()),
+            Box::new(SyncLineKeyboard::with_source(CAPACITY, source, 0x1).unwrap()),
+        ],
+        RunConfig::halt_on_ex().with_cycles_limit(Some(1000)),
+    );
+
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+
+    assert!(
+        vm.cpu().halted(),
+        "The end of the input did not halt the CPU"
+    );
+    assert_eq!(progress.consumed(), 1);
+    assert!(progress.exhausted());
+
+    // The buffer is left untouched
+    assert_eq!(
+        vm.map(|mem| mem.peek(0x1000)),
+        Some(u32::from_be_bytes(*b"only"))
+    );
+}
@@ -1,2 +1,4 @@
+pub mod async_queue;
+pub mod input;
 pub mod sync_char;
 pub mod sync_line;
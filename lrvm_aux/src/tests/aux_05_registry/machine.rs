@@ -0,0 +1,115 @@
+use std::fs;
+
+use lrvm_tools::{
+    asm::{ExtInstr, Instr, Program},
+    bytes::words_to_bytes,
+    config::MachineConfig,
+    debug::{run_vm, RunConfig},
+};
+
+use crate::{registry::registry, tests::TempDir};
+
+#[test]
+fn machine() {
+    let dir = TempDir::new("machine");
+
+    let mut program = Program::from_instr(ExtInstr::WriteAddrLit(0x2000, 0x01234567).to_instr());
+    program.append_all(ExtInstr::WriteAddrLit(0x3000, 0x2A).to_prog_words());
+    program.append(Instr::Halt().into());
+
+    fs::write(dir.join("boot.bin"), words_to_bytes(program.encode_words())).unwrap();
+
+    let config = MachineConfig::from_toml(
+        r#"
+        [boot]
+        binary = "boot.bin"
+
+        [[components]]
+        type = "bootrom"
+        size = 0x1000
+        boot = true
+
+        [[components]]
+        type = "ram"
+        size = 0x1000
+        addr = 0x2000
+
+        [[components]]
+        type = "number_display"
+        addr = 0x3000
+        output = "file:out.txt"
+        "#,
+    )
+    .unwrap();
+
+    let mut vm = config.build(&registry(), dir.path()).unwrap();
+
+    let state = run_vm(vm.cpu(), RunConfig::halt_on_ex());
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+
+    let mut ex = 0;
+    assert_eq!(vm.map(|mem| mem.read(0x2000, &mut ex)), 0x01234567);
+    assert_eq!(ex, 0);
+
+    assert_eq!(fs::read_to_string(dir.join("out.txt")).unwrap(), "0x2A\n");
+}
+
+#[test]
+fn machine_validation() {
+    let registry = registry();
+
+    let check = |json: &str| {
+        MachineConfig::from_json(json)
+            .and_then(|config| config.validate(&registry))
+            .unwrap_err()
+            .to_string()
+    };
+
+    assert_eq!(
+        check(r#"{ "components": [{ "type": "ram", "size": 16 }, { "type": "gpu" }] }"#),
+        "Component 1: Unknown component type 'gpu' (known types: async_keyboard, basic_debug, block_device, bootrom, buffered_display, char_display, file_backed_mem, framebuffer, host_fs, interval_timer, nic, number_display, pcm_audio, persistent_mem, power_control, ram, realtime_clock, rng, sparse_ram, sync_char_keyboard, sync_line_keyboard, text_display, uart, watchdog)"
+    );
+
+    assert_eq!(
+        check(r#"{ "components": [{ "type": "realtime_clock", "size": 16 }] }"#),
+        "Component 0: Field 'size' is not supported by components of type 'realtime_clock'"
+    );
+
+    assert_eq!(
+        check(r#"{ "components": [{ "type": "ram", "size": 16, "addr": 2 }] }"#),
+        "Component 0: Mapping address 0x00000002 is not aligned on 4 bytes"
+    );
+
+    assert_eq!(
+        check(r#"{ "components": [{ "type": "ram", "size": 16, "boot": true }] }"#),
+        "Component 0: 'boot' is set but the machine has no boot image"
+    );
+
+    assert_eq!(
+        check(r#"{ "components": [{ "type": "ram", "size": 16, "colour": "red" }] }"#),
+        "Component 0: unknown field `colour`, expected `poison`"
+    );
+
+    assert_eq!(
+        check(r#"{ "components": [{ "type": "ram", "size": 16, "poison": "loud" }] }"#),
+        "Component 0: unknown variant `loud`, expected `exception` or `warn`"
+    );
+
+    assert_eq!(
+        check(r#"{ "components": [{ "type": "framebuffer", "width": 320 }] }"#),
+        "Component 0: missing field `height`"
+    );
+
+    assert_eq!(
+        check(r#"{ "components": [{ "type": "realtime_clock", "seed": 1 }] }"#),
+        "Component 0: unknown field `seed`, there are no fields"
+    );
+
+    assert!(
+        check(r#"{ "components": [{ "type": "ram", "size": "big" }] }"#)
+            .starts_with("Invalid JSON machine description: invalid type")
+    );
+}
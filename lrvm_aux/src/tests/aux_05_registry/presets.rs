@@ -0,0 +1,120 @@
+use lrvm_tools::{
+    asm::{ExtInstr, Instr, Program, Reg},
+    debug::{run_vm, RunConfig},
+    presets::Preset,
+};
+
+use crate::registry::registry;
+
+#[test]
+fn presets() {
+    let registry = registry();
+
+    for preset in Preset::all() {
+        let mut board = preset
+            .build(vec![Instr::Halt().encode_word()], &registry)
+            .unwrap();
+
+        let memory_map = board.memory_map();
+        assert_eq!(board.motherboard().count(), memory_map.len());
+
+        for (aux_id, region) in memory_map.iter().enumerate() {
+            let mapping = board
+                .motherboard()
+                .map(|mem| mem.get_mapping(aux_id).copied())
+                .unwrap();
+
+            assert_eq!(
+                mapping.addr, region.addr,
+                "Invalid mapping for {}",
+                region.name
+            );
+            assert_eq!(
+                mapping.addr + mapping.size - 4,
+                region.end_addr(),
+                "Invalid mapping for {}",
+                region.name
+            );
+        }
+
+        assert_eq!(board.cpu().regs.ssp, preset.supervisor_stack_top());
+        assert_eq!(board.cpu().regs.usp, preset.user_stack_top());
+        assert!(preset.user_stack_top() < preset.supervisor_stack_top());
+
+        assert_eq!(board.keyboard().is_some(), preset != Preset::Minimal);
+        assert_eq!(board.debug().is_some(), preset == Preset::DevKit);
+
+        assert_eq!(preset.name().parse::<Preset>(), Ok(preset));
+    }
+}
+
+#[test]
+fn console_preset() {
+    // Read a line from the keyboard, display its first word, then push a value on the stack
+    let mut prog = Program::from_instr(ExtInstr::WriteAddrLit(0x0020_10FC, 0xAA).to_instr());
+    prog.append_all(ExtInstr::ReadAddr(0x0020_1000).to_prog_words());
+    prog.append_all(ExtInstr::SetReg(Reg::rr0, 0x0020_0000).to_prog_words());
+    prog.append(Instr::Wea(Reg::rr0.into(), 0_u8.into(), 0_u8.into()).into());
+    prog.append_all(ExtInstr::WriteAddrLit(0x0020_00FC, 0xAA).to_prog_words());
+    prog.append(Instr::Push(0x2A_u16.into()).into());
+    prog.append(Instr::Halt().into());
+
+    let mut board = Preset::Console
+        .build(prog.encode_words(), &registry())
+        .unwrap();
+
+    let keyboard = board.keyboard().unwrap();
+    keyboard.push_line("Hi!!");
+    keyboard.push_line("Unread line");
+
+    let state = run_vm(board.cpu(), RunConfig::halt_on_ex());
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+
+    assert_eq!(board.display().take().trim_end_matches('\0'), "Hi!!");
+    assert_eq!(board.display().text(), "");
+    assert_eq!(board.keyboard().unwrap().remaining(), "Unread line\n".len());
+
+    let stack_top = Preset::Console.supervisor_stack_top();
+    assert_eq!(board.cpu().regs.ssp, stack_top - 4);
+
+    let mut ex = 0;
+    assert_eq!(
+        board
+            .motherboard()
+            .map(|mem| mem.read(stack_top - 4, &mut ex)),
+        0x2A
+    );
+    assert_eq!(ex, 0);
+}
+
+#[test]
+fn preset_stacks() {
+    // Push a value on the supervisor stack, then switch to userland and push another one on the user stack
+    let mut prog = Program::from_instr(vec![Instr::Push(0x11_u16.into())]);
+    prog.append(Instr::Cpy(Reg::smt, 0_u16.into()).into());
+    prog.append(Instr::Push(0x22_u16.into()).into());
+    prog.append(Instr::Jpr(0_u16.into()).into());
+
+    let preset = Preset::Minimal;
+    let mut board = preset.build(prog.encode_words(), &registry()).unwrap();
+
+    let state = run_vm(board.cpu(), RunConfig::new().with_cycles_limit(Some(100)));
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+
+    let (ssp, usp) = (preset.supervisor_stack_top(), preset.user_stack_top());
+    assert_eq!(board.cpu().regs.ssp, ssp - 4);
+    assert_eq!(board.cpu().regs.usp, usp - 4);
+
+    let mut ex = 0;
+    board.motherboard().map(|mem| {
+        assert_eq!(mem.read(ssp - 4, &mut ex), 0x11);
+        assert_eq!(mem.read(usp - 4, &mut ex), 0x22);
+    });
+    assert_eq!(ex, 0);
+}
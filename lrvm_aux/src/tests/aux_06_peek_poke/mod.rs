@@ -0,0 +1 @@
+pub mod peek_poke;
@@ -0,0 +1,55 @@
+use lrvm_tools::debug::prepare_vm;
+
+use crate::{
+    display::BufferedDisplay, keyboard::SyncLineKeyboard, storage::BootRom, volatile_mem::Ram,
+};
+
+#[test]
+fn peek_poke() {
+    let mut vm = prepare_vm(vec![
+        Box::new(BootRom::with_size(vec![0x01234567], 0x10, 0x0).unwrap()),
+        Box::new(Ram::new(0x10, 0x1).unwrap()),
+        Box::new(
+            BufferedDisplay::new(0x10, Box::new(|_| panic!("Display was triggered")), 0x2).unwrap(),
+        ),
+        Box::new(
+            SyncLineKeyboard::new(0x10, Box::new(|| panic!("Keyboard was triggered")), 0x3)
+                .unwrap(),
+        ),
+    ]);
+
+    vm.map(|mem| {
+        // BootROM is readonly for the guest, but can be patched
+        assert_eq!(mem.peek(0x00), Some(0x01234567));
+        assert_eq!(mem.peek(0x0C), Some(0));
+        assert!(mem.poke(0x0C, 0x89ABCDEF));
+        assert_eq!(mem.peek(0x0C), Some(0x89ABCDEF));
+
+        // Byte ranges can be unaligned and span multiple components
+        assert_eq!(mem.peek_bytes(0x0D, 4), Ok(vec![0xAB, 0xCD, 0xEF, 0x00]));
+
+        mem.poke_bytes(0x0E, b"Hello").unwrap();
+        assert_eq!(mem.peek(0x0C), Some(0x89AB4865));
+        assert_eq!(mem.peek_bytes(0x0E, 5), Ok(b"Hello".to_vec()));
+
+        // Display's buffer can be inspected while it is not readable by the guest, without triggering it
+        assert!(mem.poke(0x20, 0x41424344));
+        assert_eq!(mem.peek(0x20), Some(0x41424344));
+        assert_eq!(mem.peek(0x2C), None);
+        assert!(!mem.poke(0x2C, 0xAA));
+
+        // Same for the keyboard's action word
+        assert_eq!(mem.peek(0x30), Some(0));
+        assert!(!mem.poke(0x3C, 0xAA));
+
+        // Failures report the faulty word
+        assert_eq!(mem.peek_bytes(0x28, 8), Err(0x2C));
+        assert_eq!(mem.peek(0x1000), None);
+        assert_eq!(mem.poke_bytes(0x2A, b"abcd"), Err(0x2C));
+
+        // Ranges beyond the end of the address space report their first word
+        assert_eq!(mem.peek_bytes(0xFFFF_FFF1, 0x20), Err(0xFFFF_FFF0));
+        assert_eq!(mem.peek_bytes(0x10, u32::MAX), Err(0x10));
+        assert_eq!(mem.poke_bytes(0xFFFF_FFFE, b"abcd"), Err(0xFFFF_FFFC));
+    });
+}
@@ -0,0 +1,227 @@
+use lrvm_tools::{
+    asm::{ExtInstr, Instr, Program, Reg},
+    debug::{exec_vm, prepare_vm, run_vm_with_hook, ProfileMode, Profiler, RunConfig},
+};
+
+use crate::{
+    storage::BootRom,
+    time::{
+        IntervalTimer, TIMER_ENABLE, TIMER_FIRED, TIMER_INTERRUPT, TIMER_MISSED, TIMER_PERIODIC,
+    },
+};
+
+/// Address the timer is mapped at
+const TIMER: u32 = 0x100;
+
+/// Address of the exception handler
+const HANDLER: u32 = 0x80;
+
+#[test]
+fn periodic_timer() {
+    let mut program = Program::new();
+    program.append_all(ExtInstr::WriteAddrLit(TIMER + 0x08, 10).to_prog_words());
+    program
+        .append_all(ExtInstr::WriteAddrLit(TIMER, TIMER_ENABLE | TIMER_PERIODIC).to_prog_words());
+
+    for _ in 0..25 {
+        program.append(Instr::Cpy(Reg::a0, 0u16.into()).into());
+    }
+
+    program.append(Instr::Halt().into());
+
+    let (mut vm, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), TIMER, 0x0).unwrap()),
+            Box::new(IntervalTimer::new(0x1)),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+
+    // The timer fired twice without being acknowledged, and is still running
+    vm.map(|mem| {
+        let mut ex = 0;
+
+        assert_eq!(mem.peek(TIMER + 0x10), Some(TIMER_FIRED | TIMER_MISSED));
+        assert_eq!(mem.read(TIMER, &mut ex), TIMER_ENABLE | TIMER_PERIODIC);
+
+        mem.write(TIMER + 0x10, TIMER_FIRED | TIMER_MISSED, &mut ex);
+        assert_eq!(mem.read(TIMER + 0x10, &mut ex), 0);
+        assert_eq!(ex, 0);
+    });
+}
+
+#[test]
+fn timer_interruption() {
+    let mut program = Program::new();
+    program.append_all(ExtInstr::SetReg(Reg::ev, HANDLER).to_prog_words());
+    program.append_all(ExtInstr::WriteAddrLit(TIMER + 0x08, 5).to_prog_words());
+    program.append_all(ExtInstr::WriteAddrLit(TIMER + 0x14, 0x42).to_prog_words());
+    program
+        .append_all(ExtInstr::WriteAddrLit(TIMER, TIMER_ENABLE | TIMER_INTERRUPT).to_prog_words());
+
+    // Switch to userland and loop forever, until the timer interrupts the loop
+    program.append(Instr::Cpy(Reg::smt, 0u16.into()).into());
+    let loop_addr = program.size() as u32 * 4;
+    program.append(Instr::Jpr(0u16.into()).into());
+
+    // The exception handler halts the processor
+    let mut words = program.encode_words();
+    words.resize(HANDLER as usize / 4, 0);
+    words.extend(Program::from_instr(vec![Instr::Halt()]).encode_words());
+
+    let (mut vm, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(words, TIMER, 0x0).unwrap()),
+            Box::new(IntervalTimer::new(0x1)),
+        ],
+        RunConfig::new().with_cycles_limit(Some(1000)),
+    );
+
+    assert_eq!(state.addr, HANDLER, "Exception handler was not run");
+
+    let cpu = vm.cpu();
+
+    assert_eq!(cpu.regs.et, (0xF0 << 16) + 0x42);
+    assert_eq!(cpu.regs.era, loop_addr);
+    assert!(cpu.pending_interruptions().is_empty());
+
+    // One-shot timers are disabled after firing
+    vm.map(|mem| {
+        assert_eq!(mem.peek(TIMER), Some(TIMER_INTERRUPT));
+        assert_eq!(mem.peek(TIMER + 0x10), Some(TIMER_FIRED));
+    });
+}
+
+#[test]
+fn timer_interruption_halting_on_exceptions() {
+    let mut program = Program::new();
+    program.append_all(ExtInstr::SetReg(Reg::ev, HANDLER).to_prog_words());
+    program.append_all(ExtInstr::WriteAddrLit(TIMER + 0x08, 5).to_prog_words());
+    program
+        .append_all(ExtInstr::WriteAddrLit(TIMER, TIMER_ENABLE | TIMER_INTERRUPT).to_prog_words());
+
+    program.append(Instr::Cpy(Reg::smt, 0u16.into()).into());
+    program.append(Instr::Jpr(0u16.into()).into());
+
+    // The exception handler runs a few instructions before halting the processor
+    let mut words = program.encode_words();
+    words.resize(HANDLER as usize / 4, 0);
+    words.extend(
+        Program::from_instr(vec![
+            Instr::Cpy(Reg::a0, 1u16.into()),
+            Instr::Cpy(Reg::a1, 2u16.into()),
+            Instr::Halt(),
+        ])
+        .encode_words(),
+    );
+
+    // Hardware interruptions are not exceptions of the program, so they must not stop the VM
+    let (mut vm, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(words, TIMER, 0x0).unwrap()),
+            Box::new(IntervalTimer::new(0x1)),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    assert!(
+        state.ex.is_none(),
+        "Hardware interruption was reported as an exception!"
+    );
+    assert_eq!(state.addr, HANDLER + 8, "Exception handler was not run");
+
+    let cpu = vm.cpu();
+
+    assert!(cpu.halted());
+    assert_eq!(cpu.regs.et >> 16, 0xF0);
+    assert_eq!(cpu.regs.a[1], 2);
+}
+
+#[test]
+fn timer_interruption_pending_in_supervisor_mode() {
+    let mut program = Program::new();
+    program.append_all(ExtInstr::WriteAddrLit(TIMER + 0x08, 5).to_prog_words());
+    program.append_all(ExtInstr::WriteAddrLit(TIMER + 0x14, 0x42).to_prog_words());
+    program.append_all(
+        ExtInstr::WriteAddrLit(TIMER, TIMER_ENABLE | TIMER_PERIODIC | TIMER_INTERRUPT)
+            .to_prog_words(),
+    );
+
+    // Loop forever in supervisor mode, so the interruptions are never delivered
+    program.append(Instr::Jpr(0u16.into()).into());
+
+    let mut vm = prepare_vm(vec![
+        Box::new(BootRom::with_size(program.encode_words(), TIMER, 0x0).unwrap()),
+        Box::new(IntervalTimer::new(0x1)),
+    ]);
+
+    for _ in 0..10_000 {
+        vm.cpu().next();
+    }
+
+    // The timer fired about 2000 times, but its interruption is only pending once
+    assert_eq!(vm.cpu().regs.et, 0);
+    assert_eq!(vm.cpu().pending_interruptions(), &[0x42]);
+}
+
+#[test]
+fn timer_interruption_profile() {
+    let mut program = Program::new();
+    program.append_all(ExtInstr::SetReg(Reg::ev, HANDLER).to_prog_words());
+    program.append_all(ExtInstr::WriteAddrLit(TIMER + 0x08, 5).to_prog_words());
+    program
+        .append_all(ExtInstr::WriteAddrLit(TIMER, TIMER_ENABLE | TIMER_INTERRUPT).to_prog_words());
+
+    program.append(Instr::Cpy(Reg::smt, 0u16.into()).into());
+    let loop_addr = program.size() as u32 * 4;
+    program.append(Instr::Jpr(0u16.into()).into());
+
+    let mut words = program.encode_words();
+    words.resize(HANDLER as usize / 4, 0);
+    words.extend(Program::from_instr(vec![Instr::Halt()]).encode_words());
+
+    let mut vm = prepare_vm(vec![
+        Box::new(BootRom::with_size(words, TIMER, 0x0).unwrap()),
+        Box::new(IntervalTimer::new(0x1)),
+    ]);
+
+    let mut profiler = Profiler::new(ProfileMode::Count);
+    let mut cycles_without_instr = 0;
+
+    let state = run_vm_with_hook(
+        vm.cpu(),
+        RunConfig::halt_on_ex().with_cycles_limit(Some(1000)),
+        |cpu, step| {
+            if step.instr.is_none() {
+                cycles_without_instr += 1;
+            }
+
+            profiler.record(cpu, step)
+        },
+    );
+
+    assert!(state.ex.is_none());
+    assert_eq!(state.addr, HANDLER, "Exception handler was not run");
+
+    // The cycle replaced by the interruption didn't run the loop's instruction
+    assert_eq!(cycles_without_instr, 1);
+    assert_eq!(profiler.instructions() as u128, state.cycles - 1);
+
+    let loop_hits = profiler
+        .hot_spots()
+        .into_iter()
+        .find(|(addr, _)| *addr == loop_addr)
+        .map(|(_, hits)| hits)
+        .unwrap();
+
+    assert_eq!(
+        profiler.instructions() - loop_hits,
+        loop_addr as u64 / 4 + 1,
+        "Only the loop and the instructions before it, plus the handler, should have run"
+    );
+}
@@ -0,0 +1,137 @@
+use lrvm::board::{BoardRequest, Bus, ResetKind};
+use lrvm_tools::{
+    asm::{ExtInstr, HwInfo, Instr, Program, Reg},
+    debug::{exec_vm, prepare_vm, RunConfig},
+};
+
+use crate::{
+    storage::{BootRom, PersistentMem},
+    time::{Watchdog, WDT_ENABLE, WDT_HALT, WDT_INTERRUPT, WDT_RESET, WDT_TIMED_OUT},
+};
+
+/// Address the watchdog is mapped at
+const WDT: u32 = 0x200;
+
+#[test]
+fn watchdog_reset() {
+    // Store the reason of the last reset, then hang (the reset reason doesn't depend on the component ID)
+    let mut program = Program::from_instr(vec![Instr::Hwd(
+        Reg::a0,
+        1u8.into(),
+        HwInfo::ResetReason.code().into(),
+    )]);
+    program.append_all(ExtInstr::WriteAddr(0x1000, Reg::a0).to_prog_words());
+    program.append(Instr::Jpr(0u16.into()).into());
+
+    let mut words = program.encode_words();
+    words.resize(WDT as usize / 4, 0);
+
+    let mut vm = prepare_vm(vec![
+        Box::new(BootRom::with_size(words, WDT, 0x0).unwrap()),
+        Box::new(Watchdog::armed(20, WDT_RESET, 0x1)),
+        Box::new(PersistentMem::new(0x1000 - WDT - 0x20, 0x2).unwrap()),
+        Box::new(PersistentMem::new(0x10, 0x3).unwrap()),
+    ]);
+
+    // The program never halts, so run a few loops manually (the cycles counter is reset along with the CPU)
+    for _ in 0..50 {
+        vm.cpu().next();
+        assert_eq!(
+            vm.cpu().regs.et,
+            0,
+            "Unexpected exception occurred while running the VM!"
+        );
+    }
+
+    assert_eq!(vm.cpu().last_reset(), ResetKind::Hardware);
+
+    vm.map(|mem| {
+        assert_eq!(mem.peek(0x1000), Some(ResetKind::Hardware.encode()));
+
+        // The watchdog is armed again after each reset, and remembers it timed out
+        assert_eq!(mem.peek(WDT), Some(WDT_ENABLE));
+        assert_eq!(mem.peek(WDT + 0x14), Some(WDT_TIMED_OUT));
+        assert_eq!(mem.peek(WDT + 0x18), Some(2));
+    });
+}
+
+#[test]
+fn watchdog_halt() {
+    let mut program = Program::new();
+    program.append_all(ExtInstr::WriteAddrLit(WDT + 0x04, WDT_HALT).to_prog_words());
+    program.append_all(ExtInstr::WriteAddrLit(WDT + 0x08, 30).to_prog_words());
+    program.append_all(ExtInstr::WriteAddrLit(WDT, WDT_ENABLE).to_prog_words());
+
+    // Kick the watchdog a few times, then hang
+    for _ in 0..5 {
+        for _ in 0..10 {
+            program.append(Instr::Cpy(Reg::a0, 0u16.into()).into());
+        }
+
+        program.append_all(ExtInstr::WriteAddrLit(WDT + 0x10, 0).to_prog_words());
+    }
+
+    program.append(Instr::Jpr(0u16.into()).into());
+
+    let (mut vm, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), WDT, 0x0).unwrap()),
+            Box::new(Watchdog::new(0x1)),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+
+    assert!(vm.cpu().halted());
+    assert!(state.cycles > 5 * 10);
+
+    vm.map(|mem| {
+        assert_eq!(mem.peek(WDT), Some(0));
+        assert_eq!(mem.peek(WDT + 0x14), Some(WDT_TIMED_OUT));
+        assert_eq!(mem.peek(WDT + 0x18), Some(1));
+    });
+}
+
+#[test]
+fn watchdog_interruption() {
+    let mut wdt = Watchdog::armed(3, WDT_INTERRUPT, 0x1);
+    let mut ex = 0;
+
+    wdt.write(0x1C, 0x42, &mut ex);
+
+    for _ in 0..2 {
+        assert_eq!(wdt.tick(), None);
+        assert_eq!(wdt.tick(), None);
+        assert_eq!(wdt.tick(), Some(0x42));
+        assert_eq!(wdt.request(), None);
+    }
+
+    // Kicking restarts the countdown
+    assert_eq!(wdt.tick(), None);
+    wdt.write(0x10, 0, &mut ex);
+    assert_eq!(wdt.read(0x0C, &mut ex), 3);
+    assert_eq!(ex, 0);
+
+    // The status is only cleared by cold resets
+    wdt.reset(ResetKind::Warm);
+    assert_eq!(wdt.read(0x14, &mut ex), WDT_TIMED_OUT);
+    assert_eq!(wdt.timeouts(), 2);
+
+    wdt.reset(ResetKind::Cold);
+    assert_eq!(wdt.read(0x14, &mut ex), 0);
+    assert_eq!(wdt.timeouts(), 0);
+
+    // Timeouts with the reset action request a reset from the motherboard
+    wdt.write(0x04, WDT_RESET, &mut ex);
+
+    for _ in 0..3 {
+        assert_eq!(wdt.tick(), None);
+    }
+
+    assert_eq!(wdt.request(), Some(BoardRequest::Reset));
+    assert_eq!(wdt.request(), None);
+}
@@ -0,0 +1,188 @@
+use lrvm_tools::{
+    asm::{ExtInstr, Instr, Program, Reg},
+    debug::{exec_vm, RunConfig},
+};
+
+use crate::{
+    serial::{
+        LinkBackend, PipeBackend, Uart, UART_DEFAULT_FIFO_SIZE, UART_OVERRUN, UART_RX_READY,
+        UART_TX_EMPTY,
+    },
+    storage::BootRom,
+};
+
+/// Address of the first UART
+const UART: u32 = 0x100;
+
+/// Let the UARTs exchange bytes for a few cycles
+fn wait(program: &mut Program) {
+    for _ in 0..4 {
+        program.append(Instr::Cpy(Reg::a7, 0u16.into()).into());
+    }
+}
+
+#[test]
+fn uart_echo() {
+    let pipe = PipeBackend::new();
+    pipe.send(b"Hi");
+
+    let mut program = Program::new();
+    wait(&mut program);
+
+    // Read each byte into `avr` and write it back
+    for _ in 0..2 {
+        program.append_all(ExtInstr::ReadAddr(UART).to_prog_words());
+        program.append(Instr::Cpy(Reg::rr0, (UART as u16).into()).into());
+        program.append(Instr::Wea(Reg::rr0.into(), 0u8.into(), 0u8.into()).into());
+    }
+
+    wait(&mut program);
+    program.append(Instr::Halt().into());
+
+    let (mut vm, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), UART, 0x0).unwrap()),
+            Box::new(Uart::new(Box::new(pipe.clone()), 0x1)),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+    assert_eq!(pipe.take_output(), b"Hi");
+    assert_eq!(pipe.pending(), 0);
+    assert_eq!(vm.map(|mem| mem.peek(UART + 0x04)), Some(UART_TX_EMPTY));
+}
+
+#[test]
+fn uart_polling_long_message() {
+    let message = b"This message is longer than the receive FIFO";
+    assert!(message.len() > UART_DEFAULT_FIFO_SIZE);
+
+    let pipe = PipeBackend::new();
+    pipe.send(message);
+
+    // The program doesn't fit before the usual address
+    let uart = 0x1000;
+
+    // Let the receive FIFO fill up before reading it
+    let mut program = Program::new();
+
+    for _ in 0..message.len() {
+        program.append(Instr::Cpy(Reg::a7, 0u16.into()).into());
+    }
+
+    // Read each byte into `avr` and write it back
+    for _ in 0..message.len() {
+        program.append_all(ExtInstr::ReadAddr(uart).to_prog_words());
+        program.append(Instr::Cpy(Reg::rr0, (uart as u16).into()).into());
+        program.append(Instr::Wea(Reg::rr0.into(), 0u8.into(), 0u8.into()).into());
+    }
+
+    wait(&mut program);
+    program.append(Instr::Halt().into());
+
+    let mut words = program.encode_words();
+    words.resize(uart as usize / 4, 0);
+
+    let (mut vm, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(words, uart, 0x0).unwrap()),
+            Box::new(Uart::new(Box::new(pipe.clone()), 0x1)),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+
+    // The pipe kept the bytes the FIFO had no room for, so none of them was lost
+    assert_eq!(pipe.take_output(), message);
+    assert_eq!(pipe.pending(), 0);
+    assert_eq!(vm.map(|mem| mem.peek(uart + 0x04)), Some(UART_TX_EMPTY));
+}
+
+#[test]
+fn uart_link_flow_control() {
+    let (link_a, link_b) = LinkBackend::pair();
+
+    let mut program = Program::new();
+
+    for byte in b"abc" {
+        program.append_all(ExtInstr::WriteAddrLit(UART, (*byte).into()).to_prog_words());
+    }
+
+    wait(&mut program);
+    program.append(Instr::Halt().into());
+
+    let (mut vm, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), UART, 0x0).unwrap()),
+            Box::new(Uart::new(Box::new(link_a), 0x1)),
+            Box::new(Uart::with_fifo_size(Box::new(link_b), 2, 0x2).unwrap()),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+
+    // The second UART only had room for two bytes, the third one is still in the link
+    vm.map(|mem| {
+        let mut ex = 0;
+
+        assert_eq!(mem.peek(UART + 0x14), Some(UART_RX_READY | UART_TX_EMPTY));
+        assert_eq!(mem.read(UART + 0x10, &mut ex), u32::from(b'a'));
+        assert_eq!(mem.read(UART + 0x10, &mut ex), u32::from(b'b'));
+        assert_eq!(mem.read(UART + 0x10, &mut ex), 0);
+        assert_eq!(ex, 0);
+    });
+}
+
+#[test]
+fn uart_overrun() {
+    let pipe = PipeBackend::without_flow_control();
+    pipe.send(b"abc");
+
+    let mut program = Program::new();
+    wait(&mut program);
+    program.append(Instr::Halt().into());
+
+    let (mut vm, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), UART, 0x0).unwrap()),
+            Box::new(Uart::with_fifo_size(Box::new(pipe.clone()), 2, 0x1).unwrap()),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+    assert_eq!(pipe.pending(), 0);
+
+    // The UART only had room for two bytes, and the pipe didn't keep the third one
+    vm.map(|mem| {
+        let mut ex = 0;
+
+        assert_eq!(
+            mem.peek(UART + 0x04),
+            Some(UART_RX_READY | UART_TX_EMPTY | UART_OVERRUN)
+        );
+
+        assert_eq!(mem.read(UART, &mut ex), u32::from(b'a'));
+        assert_eq!(mem.read(UART, &mut ex), u32::from(b'b'));
+        assert_eq!(mem.read(UART, &mut ex), 0);
+
+        mem.write(UART + 0x04, UART_OVERRUN, &mut ex);
+        assert_eq!(mem.read(UART + 0x04, &mut ex), UART_TX_EMPTY);
+        assert_eq!(ex, 0);
+    });
+}
@@ -0,0 +1,99 @@
+use lrvm::board::{Bus, ResetKind};
+use lrvm_tools::{
+    asm::{ExtInstr, Instr, Program, Reg},
+    debug::{exec_vm, RunConfig},
+};
+
+use crate::{
+    entropy::{Rng, RNG_DETERMINISTIC, RNG_HOST_ENTROPY},
+    storage::BootRom,
+    volatile_mem::Ram,
+};
+
+/// Address of the RAM
+const RAM: u32 = 0x100;
+
+/// Address the generator is mapped at
+const RNG: u32 = 0x200;
+
+/// Get the first numbers of a generator
+fn numbers(rng: &mut Rng, count: usize) -> Vec<u32> {
+    let mut ex = 0;
+    let numbers = (0..count).map(|_| rng.read(0x00, &mut ex)).collect();
+    assert_eq!(ex, 0);
+    numbers
+}
+
+#[test]
+fn rng_deterministic() {
+    let expected = numbers(&mut Rng::seeded(0x1234, 0x0), 2);
+    assert_ne!(expected[0], expected[1]);
+
+    // Store two numbers in RAM
+    let mut program = Program::new();
+
+    for i in 0..2 {
+        program.append_all(ExtInstr::ReadAddr(RNG).to_prog_words());
+        program.append(Instr::Cpy(Reg::rr0, (RAM as u16 + i * 4).into()).into());
+        program.append(Instr::Wea(Reg::rr0.into(), 0u8.into(), 0u8.into()).into());
+    }
+
+    program.append(Instr::Halt().into());
+
+    let (mut vm, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), RAM, 0x0).unwrap()),
+            Box::new(Ram::new(RNG - RAM, 0x1).unwrap()),
+            Box::new(Rng::seeded(0x1234, 0x2)),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+
+    vm.map(|mem| {
+        assert_eq!(mem.peek(RAM), Some(expected[0]));
+        assert_eq!(mem.peek(RAM + 0x04), Some(expected[1]));
+    });
+}
+
+#[test]
+fn rng_seed_and_modes() {
+    let mut rng = Rng::seeded(1, 0x0);
+    let first = numbers(&mut rng, 4);
+    let mut ex = 0;
+
+    // Writing the seed restarts the generator
+    rng.write(0x08, 1, &mut ex);
+    assert_eq!(numbers(&mut rng, 4), first);
+
+    // Reseeding changes the numbers, deterministically
+    rng.write(0x08, 1, &mut ex);
+    rng.write(0x0C, 0xABCD, &mut ex);
+    let reseeded = numbers(&mut rng, 4);
+    assert_ne!(reseeded, first);
+
+    rng.write(0x08, 1, &mut ex);
+    rng.write(0x0C, 0xABCD, &mut ex);
+    assert_eq!(numbers(&mut rng, 4), reseeded);
+
+    // Host entropy mode
+    rng.write(0x04, RNG_HOST_ENTROPY, &mut ex);
+    let host = numbers(&mut rng, 4);
+    assert!(host.iter().any(|num| *num != host[0]));
+
+    // Resets restart the generator, cold resets also restore the initial mode and seed
+    rng.write(0x08, 2, &mut ex);
+    rng.reset(ResetKind::Warm);
+    assert_eq!(rng.peek(0x04), Some(RNG_HOST_ENTROPY));
+    assert_eq!(rng.peek(0x08), Some(2));
+
+    rng.reset(ResetKind::Cold);
+    assert_eq!(rng.peek(0x04), Some(RNG_DETERMINISTIC));
+    assert_eq!(numbers(&mut rng, 4), first);
+
+    assert_eq!(ex, 0);
+}
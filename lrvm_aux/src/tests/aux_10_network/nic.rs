@@ -0,0 +1,221 @@
+use std::collections::VecDeque;
+
+use lrvm::board::{Bus, MotherBoard, ResetKind};
+use lrvm_tools::{
+    asm::{ExtInstr, Instr, Program},
+    debug::{exec_vm, RunConfig},
+};
+
+use crate::{
+    network::{
+        Nic, NicBackend, VirtualSwitch, BROADCAST_MAC, NIC_DEFAULT_MTU, NIC_HEADER_SIZE,
+        NIC_RECEIVE, NIC_RX_OVERFLOW, NIC_RX_QUEUE_SIZE, NIC_RX_READY, NIC_SEND, NIC_TX_ERROR,
+        SWITCH_PORT_QUEUE_SIZE,
+    },
+    storage::BootRom,
+    tests::TempDir,
+};
+
+/// Address the NIC is mapped at
+const NIC: u32 = 0x100;
+
+/// Address of the NIC's RX buffer
+const RX_BUFFER: u32 = NIC + NIC_HEADER_SIZE + NIC_DEFAULT_MTU.div_ceil(4) * 4;
+
+const MAC_A: [u8; 6] = [0x02, 0x00, 0x00, 0x00, 0x00, 0x0A];
+const MAC_B: [u8; 6] = [0x02, 0x00, 0x00, 0x00, 0x00, 0x0B];
+
+/// Run a program with a NIC
+fn run(program: Program, nic: Nic) -> MotherBoard {
+    let (vm, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), NIC, 0x0).unwrap()),
+            Box::new(nic),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+
+    vm
+}
+
+#[test]
+fn nic_switch() {
+    let switch = VirtualSwitch::new();
+    let nic_a = Nic::new(Box::new(switch.connect(MAC_A)), MAC_A, 0x1);
+    let nic_b = Nic::new(Box::new(switch.connect(MAC_B)), MAC_B, 0x1);
+
+    // Send a 16-byte frame from A to B
+    let mut program = Program::new();
+
+    for (i, word) in [0x0200_0000, 0x000B_0200, 0x0000_000A, 0x1234_5678]
+        .into_iter()
+        .enumerate()
+    {
+        program.append_all(
+            ExtInstr::WriteAddrLit(NIC + NIC_HEADER_SIZE + i as u32 * 4, word).to_prog_words(),
+        );
+    }
+
+    program.append_all(ExtInstr::WriteAddrLit(NIC + 0x08, 16).to_prog_words());
+    program.append_all(ExtInstr::WriteAddrLit(NIC, NIC_SEND).to_prog_words());
+    program.append(Instr::Halt().into());
+
+    run(program, nic_a);
+
+    assert_eq!(switch.forwarded(), 1);
+    assert_eq!(switch.dropped(), 0);
+
+    // Receive it on B
+    let mut program = Program::new();
+    program.append_all(ExtInstr::WriteAddrLit(NIC, NIC_RECEIVE).to_prog_words());
+    program.append(Instr::Halt().into());
+
+    run(program, nic_b).map(|mem| {
+        assert_eq!(mem.peek(NIC + 0x04), Some(0));
+        assert_eq!(mem.peek(NIC + 0x0C), Some(16));
+        assert_eq!(mem.peek(NIC + 0x10), Some(0));
+        assert_eq!(mem.peek(RX_BUFFER), Some(0x0200_0000));
+        assert_eq!(mem.peek(RX_BUFFER + 0x0C), Some(0x1234_5678));
+    });
+}
+
+#[test]
+fn nic_queue() {
+    let switch = VirtualSwitch::new();
+    let mut port = switch.connect(MAC_A);
+    let mut nic = Nic::new(Box::new(switch.connect(MAC_B)), MAC_B, 0x1);
+
+    let mut ex = 0;
+
+    // Sending an empty packet fails
+    nic.write(0x00, NIC_SEND, &mut ex);
+    assert_eq!(ex, 0);
+    assert_eq!(nic.read(0x04, &mut ex), NIC_TX_ERROR);
+
+    nic.write(0x04, NIC_TX_ERROR, &mut ex);
+    assert_eq!(nic.read(0x04, &mut ex), 0);
+
+    // Fill the queue with broadcast packets, the switch keeping the ones the NIC has no room for
+    for i in 0..=NIC_RX_QUEUE_SIZE as u8 {
+        let mut packet = BROADCAST_MAC.to_vec();
+        packet.extend_from_slice(&MAC_A);
+        packet.push(i);
+
+        port.send(&packet);
+        assert_eq!(nic.tick(), None);
+    }
+
+    assert_eq!(nic.read(0x04, &mut ex), NIC_RX_READY);
+    assert_eq!(nic.read(0x10, &mut ex), NIC_RX_QUEUE_SIZE as u32);
+    assert_eq!(switch.dropped(), 0);
+
+    // Packets are received in order, and the RX buffer is read-only
+    nic.write(0x00, NIC_RECEIVE, &mut ex);
+    assert_eq!(nic.read(0x0C, &mut ex), 13);
+    assert_eq!(nic.read(RX_BUFFER - NIC, &mut ex), 0xFFFF_FFFF);
+    assert_eq!(nic.read(RX_BUFFER - NIC + 0x0C, &mut ex), 0x0000_0000);
+    assert_eq!(ex, 0);
+
+    nic.write(RX_BUFFER - NIC, 0, &mut ex);
+    assert_ne!(ex, 0);
+
+    // The packet kept by the switch is queued once there is room for it
+    assert_eq!(nic.read(0x10, &mut ex), NIC_RX_QUEUE_SIZE as u32 - 1);
+    nic.tick();
+    assert_eq!(nic.read(0x10, &mut ex), NIC_RX_QUEUE_SIZE as u32);
+
+    // Packets sent to unknown addresses are dropped
+    let mut packet = vec![0x02, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+    packet.extend_from_slice(&MAC_A);
+    port.send(&packet);
+    assert_eq!(port.receive(), None);
+    assert_eq!(switch.dropped(), 1);
+
+    nic.reset(ResetKind::Cold);
+    assert_eq!(nic.peek(0x04), Some(0));
+    assert_eq!(nic.peek(0x10), Some(0));
+}
+
+/// Backend without flow control, handing its packets to the NIC even when it has no room for them
+struct LossyBackend(VecDeque<Vec<u8>>);
+
+impl NicBackend for LossyBackend {
+    fn send(&mut self, _packet: &[u8]) {}
+
+    fn receive(&mut self) -> Option<Vec<u8>> {
+        self.0.pop_front()
+    }
+
+    fn flow_control(&self) -> bool {
+        false
+    }
+}
+
+#[test]
+fn nic_overflow() {
+    let packets = (0..=NIC_RX_QUEUE_SIZE as u8).map(|i| vec![i]).collect();
+    let mut nic = Nic::new(Box::new(LossyBackend(packets)), MAC_B, 0x1);
+
+    for _ in 0..=NIC_RX_QUEUE_SIZE {
+        nic.tick();
+    }
+
+    let mut ex = 0;
+
+    assert_eq!(nic.read(0x04, &mut ex), NIC_RX_READY | NIC_RX_OVERFLOW);
+    assert_eq!(nic.read(0x10, &mut ex), NIC_RX_QUEUE_SIZE as u32);
+
+    nic.write(0x04, NIC_RX_OVERFLOW, &mut ex);
+    assert_eq!(nic.read(0x04, &mut ex), NIC_RX_READY);
+    assert_eq!(ex, 0);
+}
+
+#[test]
+fn switch_port_queue() {
+    let switch = VirtualSwitch::new();
+    let mut port_a = switch.connect(MAC_A);
+    let mut port_b = switch.connect(MAC_B);
+
+    let mut packet = MAC_B.to_vec();
+    packet.extend_from_slice(&MAC_A);
+
+    // Ports only keep a limited number of packets for their NIC
+    for _ in 0..=SWITCH_PORT_QUEUE_SIZE {
+        port_a.send(&packet);
+    }
+
+    assert_eq!(switch.forwarded(), SWITCH_PORT_QUEUE_SIZE as u64);
+    assert_eq!(switch.dropped(), 1);
+
+    for _ in 0..SWITCH_PORT_QUEUE_SIZE {
+        assert_eq!(port_b.receive().as_deref(), Some(packet.as_slice()));
+    }
+
+    assert_eq!(port_b.receive(), None);
+}
+
+#[test]
+#[cfg(unix)]
+fn nic_unix_datagram() {
+    use crate::network::UnixDatagramBackend;
+
+    let dir = TempDir::new("nic");
+
+    let (path_a, path_b) = (dir.join("a.sock"), dir.join("b.sock"));
+
+    let mut a = UnixDatagramBackend::bind(&path_a, &path_b).unwrap();
+    let mut b = UnixDatagramBackend::bind(&path_b, &path_a).unwrap();
+
+    assert_eq!(b.receive(), None);
+
+    a.send(&[0x01, 0x02, 0x03]);
+    assert_eq!(b.receive(), Some(vec![0x01, 0x02, 0x03]));
+
+    b.send(&[0x04]);
+    assert_eq!(a.receive(), Some(vec![0x04]));
+}
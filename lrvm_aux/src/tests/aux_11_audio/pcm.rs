@@ -0,0 +1,177 @@
+use std::fs;
+
+use lrvm::board::{Bus, ResetKind};
+use lrvm_tools::{
+    asm::{ExtInstr, Instr, Program},
+    debug::{exec_vm, RunConfig},
+};
+
+use crate::{
+    audio::{
+        AudioBackend, AudioFormat, Pcm, SampleCapture, WavFileBackend, PCM_FIFO_SIZE, PCM_OVERRUN,
+        PCM_PLAY, PCM_PLAYING, PCM_UNDERRUN,
+    },
+    storage::BootRom,
+    tests::TempDir,
+};
+
+/// Address the PCM audio component is mapped at
+const PCM: u32 = 0x200;
+
+#[test]
+fn pcm_playback() {
+    let capture = SampleCapture::new();
+
+    let mut program = Program::new();
+
+    let mut write = |addr: u32, word: u32| {
+        program.append_all(ExtInstr::WriteAddrLit(PCM + addr, word).to_prog_words());
+    };
+
+    // Play two mono 16-bit samples at 8 kHz
+    write(0x08, 8000);
+    write(0x0C, 1);
+    write(0x00, PCM_PLAY);
+    write(0x18, 0x7FFF);
+    write(0x18, 0x8000);
+    write(0x00, 0);
+
+    // Then a stereo 8-bit frame
+    write(0x0C, 2);
+    write(0x10, 8);
+    write(0x18, 0xFF);
+    write(0x18, 0x00);
+    write(0x00, PCM_PLAY);
+    write(0x00, 0);
+
+    program.append(Instr::Halt().into());
+
+    let (_, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), PCM, 0x0).unwrap()),
+            Box::new(Pcm::new(Box::new(capture.clone()), 0x1)),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+
+    let recordings = capture.recordings();
+    assert_eq!(recordings.len(), 2);
+
+    assert_eq!(recordings[0].format, AudioFormat::new(8000, 1, 16).unwrap());
+    assert_eq!(recordings[0].samples, vec![i16::MAX, i16::MIN]);
+    assert!(recordings[0].stopped);
+
+    assert_eq!(recordings[1].format, AudioFormat::new(8000, 2, 8).unwrap());
+    assert_eq!(recordings[1].samples, vec![0x7F00, i16::MIN]);
+
+    let wav = recordings[1].to_wav();
+    assert_eq!(&wav[0..4], b"RIFF");
+    assert_eq!(&wav[44..], &[0xFF, 0x00]);
+}
+
+#[test]
+fn pcm_fifo() {
+    let capture = SampleCapture::new();
+    let mut pcm = Pcm::new(Box::new(capture.clone()), 0x1);
+    let mut ex = 0;
+
+    for _ in 0..=PCM_FIFO_SIZE {
+        pcm.write(0x18, 0x1234, &mut ex);
+    }
+
+    assert_eq!(pcm.read(0x14, &mut ex), PCM_FIFO_SIZE as u32);
+    assert_eq!(pcm.read(0x04, &mut ex), PCM_OVERRUN);
+
+    // The format cannot be changed during a playback
+    pcm.write(0x00, PCM_PLAY, &mut ex);
+    assert_eq!(pcm.read(0x04, &mut ex), PCM_PLAYING | PCM_OVERRUN);
+    assert_eq!(ex, 0);
+
+    pcm.write(0x08, 22_050, &mut ex);
+    assert_ne!(ex, 0);
+
+    for _ in 0..PCM_FIFO_SIZE / 2 {
+        assert_eq!(pcm.tick(), None);
+    }
+
+    assert_eq!(pcm.read(0x14, &mut ex), 0);
+    assert_eq!(pcm.tick(), None);
+    assert_eq!(
+        pcm.read(0x04, &mut ex),
+        PCM_PLAYING | PCM_OVERRUN | PCM_UNDERRUN
+    );
+
+    pcm.write(0x04, PCM_OVERRUN | PCM_UNDERRUN, &mut ex);
+    assert_eq!(pcm.read(0x04, &mut ex), PCM_PLAYING);
+
+    pcm.reset(ResetKind::Cold);
+    assert_eq!(pcm.read(0x04, &mut ex), 0);
+
+    let recording = capture.last_recording().unwrap();
+    assert_eq!(recording.samples.len(), PCM_FIFO_SIZE);
+    assert!(recording.stopped);
+
+    // Invalid formats are rejected
+    ex = 0;
+    pcm.write(0x10, 24, &mut ex);
+    assert_ne!(ex, 0);
+    assert_eq!(pcm.format(), AudioFormat::default());
+}
+
+#[test]
+fn pcm_prescaler() {
+    let capture = SampleCapture::new();
+    let mut pcm = Pcm::new(Box::new(capture.clone()), 0x1);
+    let mut ex = 0;
+
+    // Take a stereo frame every 3 cycles
+    pcm.write(0x20, 3, &mut ex);
+    assert_eq!(pcm.read(0x20, &mut ex), 3);
+
+    for sample in 0..4 {
+        pcm.write(0x18, sample, &mut ex);
+    }
+
+    pcm.write(0x00, PCM_PLAY, &mut ex);
+    assert_eq!(ex, 0);
+
+    let mut played = vec![];
+
+    for _ in 0..8 {
+        pcm.tick();
+        played.push(capture.last_recording().unwrap().samples.len());
+    }
+
+    assert_eq!(played, [0, 0, 2, 2, 2, 4, 4, 4]);
+
+    // The FIFO only lacks a frame when one is due
+    assert_eq!(pcm.read(0x04, &mut ex), PCM_PLAYING);
+
+    pcm.tick();
+    assert_eq!(pcm.read(0x04, &mut ex), PCM_PLAYING | PCM_UNDERRUN);
+
+    pcm.reset(ResetKind::Cold);
+    assert_eq!(pcm.read(0x20, &mut ex), 0);
+}
+
+#[test]
+fn pcm_wav_file() {
+    let dir = TempDir::new("pcm");
+    let path = dir.join("out.wav");
+    let format = AudioFormat::new(8000, 1, 16).unwrap();
+
+    let mut backend = WavFileBackend::new(path.to_string_lossy());
+    backend.start(format);
+    backend.play(&[0x0102]);
+    backend.play(&[-2]);
+    backend.stop();
+
+    let wav = fs::read(&path).unwrap();
+    assert_eq!(&wav[..44], &format.wav_header(4));
+    assert_eq!(&wav[44..], &[0x02, 0x01, 0xFE, 0xFF]);
+}
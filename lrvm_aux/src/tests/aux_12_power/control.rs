@@ -0,0 +1,143 @@
+use lrvm::board::{Bus, ResetKind, ShutdownReason};
+use lrvm_tools::{
+    asm::{ExtInstr, Instr, Program},
+    debug::{exec_vm, prepare_vm, run_vm_with_hook, Coverage, RunConfig, StoppedState},
+    exceptions::AuxHwException,
+};
+
+use crate::{
+    power::{PowerControl, PWR_REBOOT, PWR_SHUTDOWN, PWR_TEST_FAIL, PWR_TEST_PASS},
+    storage::BootRom,
+};
+
+/// Address the power controller is mapped at
+const PWR: u32 = 0x100;
+
+/// Run a program writing the provided exit code and command to the power controller
+fn run_command(exit_code: u32, command: u32) -> StoppedState {
+    let mut program = Program::new();
+    program.append_all(ExtInstr::WriteAddrLit(PWR, exit_code).to_prog_words());
+    program.append_all(ExtInstr::WriteAddrLit(PWR + 0x04, command).to_prog_words());
+    program.append(Instr::Halt().into());
+
+    let (_, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), PWR, 0x0).unwrap()),
+            Box::new(PowerControl::new(0x1)),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+
+    state
+}
+
+#[test]
+fn power_shutdown() {
+    let state = run_command(0x1234_5678, PWR_SHUTDOWN);
+    assert_eq!(state.shutdown, Some(ShutdownReason::Exit(0x1234_5678)));
+    assert_eq!(state.guest_exit_code(), Some(0x78));
+
+    // Truncating a nonzero code must not make it look like a success
+    let state = run_command(0x100, PWR_SHUTDOWN);
+    assert_eq!(state.shutdown, Some(ShutdownReason::Exit(0x100)));
+    assert_eq!(state.guest_exit_code(), Some(1));
+
+    let state = run_command(0, PWR_SHUTDOWN);
+    assert_eq!(state.guest_exit_code(), Some(0));
+}
+
+#[test]
+fn power_test_result() {
+    let state = run_command(7, PWR_TEST_PASS);
+    assert_eq!(state.shutdown, Some(ShutdownReason::TestPassed));
+    assert_eq!(state.guest_exit_code(), Some(0));
+
+    let state = run_command(7, PWR_TEST_FAIL);
+    assert_eq!(state.shutdown, Some(ShutdownReason::TestFailed(7)));
+    assert_eq!(state.guest_exit_code(), Some(7));
+
+    // A failure must never look like a success
+    let state = run_command(0x100, PWR_TEST_FAIL);
+    assert_eq!(state.guest_exit_code(), Some(1));
+
+    // Halting normally is not a shutdown
+    let (_, state) = exec_vm(
+        vec![Box::new(
+            BootRom::new(Program::from_instr(vec![Instr::Halt()]).encode_words(), 0x0).unwrap(),
+        )],
+        RunConfig::halt_on_ex(),
+    );
+
+    assert_eq!(state.shutdown, None);
+    assert_eq!(state.guest_exit_code(), None);
+}
+
+#[test]
+fn power_reboot() {
+    let mut program = Program::new();
+    program.append_all(ExtInstr::WriteAddrLit(PWR + 0x04, PWR_REBOOT).to_prog_words());
+    program.append(Instr::Jpr(0u16.into()).into());
+
+    let mut vm = prepare_vm(vec![
+        Box::new(BootRom::with_size(program.encode_words(), PWR, 0x0).unwrap()),
+        Box::new(PowerControl::new(0x1)),
+    ]);
+
+    // The program reboots the machine endlessly, so run a few cycles manually
+    for _ in 0..10 {
+        vm.cpu().next();
+    }
+
+    assert!(!vm.cpu().halted());
+    assert_eq!(vm.cpu().last_reset(), ResetKind::Hardware);
+    assert_eq!(vm.cpu().shutdown_reason(), None);
+}
+
+#[test]
+fn power_unknown_command() {
+    let mut pwr = PowerControl::new(0x1);
+    let mut ex = 0;
+
+    pwr.write(0x04, 0xFF, &mut ex);
+    assert!(matches!(
+        AuxHwException::decode(ex),
+        Ok(AuxHwException::UnknownOperation(0xFF))
+    ));
+    assert_eq!(pwr.request(), None);
+
+    ex = 0;
+    pwr.read(0x04, &mut ex);
+    assert!(matches!(
+        AuxHwException::decode(ex),
+        Ok(AuxHwException::MemoryNotReadable)
+    ));
+}
+
+#[test]
+fn power_shutdown_coverage() {
+    let mut program = Program::new();
+    program.append_all(ExtInstr::WriteAddrLit(PWR + 0x04, PWR_SHUTDOWN).to_prog_words());
+    let halt_addr = program.size() as u32 * 4;
+    program.append(Instr::Halt().into());
+
+    let mut vm = prepare_vm(vec![
+        Box::new(BootRom::with_size(program.encode_words(), PWR, 0x0).unwrap()),
+        Box::new(PowerControl::new(0x1)),
+    ]);
+
+    let mut coverage = Coverage::new();
+    let state = run_vm_with_hook(vm.cpu(), RunConfig::halt_on_ex(), |cpu, step| {
+        coverage.record(cpu, step)
+    });
+
+    assert_eq!(state.shutdown, Some(ShutdownReason::Exit(0)));
+
+    // The shutdown replaced the cycle, so the instruction after the command never ran
+    assert_eq!(coverage.hits(halt_addr), 0);
+    assert_eq!(coverage.executed_addrs().count(), halt_addr as usize / 4);
+}
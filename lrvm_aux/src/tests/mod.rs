@@ -2,3 +2,48 @@ pub mod aux_01_storage;
 pub mod aux_02_volatile_mem;
 pub mod aux_03_display;
 pub mod aux_04_keyboard;
+pub mod aux_05_registry;
+pub mod aux_06_peek_poke;
+pub mod aux_07_time;
+pub mod aux_08_serial;
+pub mod aux_09_entropy;
+pub mod aux_10_network;
+pub mod aux_11_audio;
+pub mod aux_12_power;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Temporary directory for the tests using host files, removed along with its content when dropped
+/// (even if the test failed)
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// Create an empty directory, named after the test using it and the running process
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("lrvm-{}-{}", name, std::process::id()));
+
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+
+        Self(path)
+    }
+
+    /// Get the directory's path
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+
+    /// Get the path of an entry of the directory
+    pub fn join(&self, path: impl AsRef<Path>) -> PathBuf {
+        self.0.join(path)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
@@ -1,3 +1,5 @@
 mod realtime;
+mod timer;
+mod watchdog;
 
-pub use self::realtime::RealtimeClock;
+pub use self::{realtime::RealtimeClock, timer::*, watchdog::*};
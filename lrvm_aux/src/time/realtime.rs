@@ -3,7 +3,7 @@
 
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
-use lrvm::board::Bus;
+use lrvm::board::{Bus, ResetKind};
 use lrvm_tools::{
     exceptions::AuxHwException,
     metadata::{ClockType, DeviceMetadata},
@@ -28,31 +28,18 @@ impl RealtimeClock {
             reset_at: Instant::now(),
         }
     }
-}
-
-impl Bus for RealtimeClock {
-    fn name(&self) -> &'static str {
-        "Realtime Clock"
-    }
-
-    fn metadata(&self) -> [u32; 8] {
-        DeviceMetadata::new(self.hw_id, 24, ClockType::Realtime.wrap(), None, None).encode()
-    }
 
-    fn read(&mut self, addr: u32, ex: &mut u16) -> u32 {
+    /// (Internal) Get the word at a given address, or the exception to raise
+    fn word_at(&self, addr: u32) -> Result<u32, AuxHwException> {
         let time = if addr < 14 {
-            match SystemTime::now().duration_since(UNIX_EPOCH) {
-                Ok(duration) => duration,
-                Err(_) => {
-                    *ex = AuxHwException::TimeSynchronizationError.encode();
-                    return 0;
-                }
-            }
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_err(|_| AuxHwException::TimeSynchronizationError)?
         } else {
             self.reset_at.elapsed()
         };
 
-        match addr % 3 {
+        Ok(match addr % 3 {
             0x00 => (time.as_secs() >> 32) as u32,
             0x01 => (time.as_secs() & 0xFFFF_FFFF) as u32,
             0x02 => {
@@ -61,6 +48,26 @@ impl Bus for RealtimeClock {
                     + time.subsec_nanos()
             }
             _ => unreachable!(),
+        })
+    }
+}
+
+impl Bus for RealtimeClock {
+    fn name(&self) -> &'static str {
+        "Realtime Clock"
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        DeviceMetadata::new(self.hw_id, 24, ClockType::Realtime.wrap(), None, None).encode()
+    }
+
+    fn read(&mut self, addr: u32, ex: &mut u16) -> u32 {
+        match self.word_at(addr) {
+            Ok(word) => word,
+            Err(err) => {
+                *ex = err.encode();
+                0
+            }
         }
     }
 
@@ -68,7 +75,11 @@ impl Bus for RealtimeClock {
         *ex = AuxHwException::MemoryNotWritable.encode();
     }
 
-    fn reset(&mut self) {
+    fn reset(&mut self, _kind: ResetKind) {
         self.reset_at = Instant::now();
     }
+
+    fn peek(&self, addr: u32) -> Option<u32> {
+        self.word_at(addr).ok()
+    }
 }
@@ -0,0 +1,192 @@
+//! The interval timer component allows to be notified after a given number of CPU cycles or a given amount of time.
+//! See [`IntervalTimer`] for more details.
+
+use std::time::{Duration, Instant};
+
+use lrvm::board::{Bus, ResetKind};
+use lrvm_tools::metadata::{ClockType, DeviceMetadata};
+
+/// Control flag: the timer is counting
+pub const TIMER_ENABLE: u32 = 0x01;
+/// Control flag: the timer restarts after firing (otherwise it is disabled after firing once)
+pub const TIMER_PERIODIC: u32 = 0x02;
+/// Control flag: count microseconds of wall time instead of CPU cycles
+pub const TIMER_WALL_TIME: u32 = 0x04;
+/// Control flag: raise a hardware interruption when the timer fires
+pub const TIMER_INTERRUPT: u32 = 0x08;
+
+/// Status flag: the timer fired since the last acknowledgement
+pub const TIMER_FIRED: u32 = 0x01;
+/// Status flag: the timer fired again before the previous firing was acknowledged
+pub const TIMER_MISSED: u32 = 0x02;
+
+/// The interval timer is a 6-word-long programmable timer, counting either CPU cycles or microseconds of wall time.
+///
+/// * Word 1: control flags (`TIMER_ENABLE`, `TIMER_PERIODIC`, `TIMER_WALL_TIME` and `TIMER_INTERRUPT`)
+/// * Word 2: prescaler, number of cycles or microseconds per counter increment (`0` behaves like `1`)
+/// * Word 3: compare value, the timer fires when the counter reaches it (`0` disables firing)
+/// * Word 4: counter
+/// * Word 5: status flags (`TIMER_FIRED` and `TIMER_MISSED`) ; writing to it acknowledges the flags set in the written word
+/// * Word 6: interruption code (only the weakest byte is kept)
+///
+/// When the timer fires, its counter goes back to `0` and the `TIMER_FIRED` status flag is set.
+/// In one-shot mode the timer is then disabled, while in periodic mode it keeps counting.
+/// If the `TIMER_INTERRUPT` control flag is set, a hardware interruption is also raised with the interruption code
+/// (it is handled as soon as the CPU is in userland mode).
+///
+/// All words are readable and writable, and are set to `0` when the component is reset.
+pub struct IntervalTimer {
+    hw_id: u64,
+    control: u32,
+    prescaler: u32,
+    compare: u32,
+    counter: u32,
+    status: u32,
+    itr_code: u8,
+    /// Cycles or microseconds that did not make the counter increment yet
+    elapsed: u64,
+    /// Last time the wall time was accounted for
+    since: Instant,
+}
+
+impl IntervalTimer {
+    /// Create a new interval timer
+    pub fn new(hw_id: u64) -> Self {
+        Self {
+            hw_id,
+            control: 0,
+            prescaler: 0,
+            compare: 0,
+            counter: 0,
+            status: 0,
+            itr_code: 0,
+            elapsed: 0,
+            since: Instant::now(),
+        }
+    }
+
+    /// (Internal) Advance the counter, returning how many times the timer fired
+    fn advance(&mut self, increments: u64) -> u64 {
+        if self.compare == 0 {
+            self.counter = self.counter.wrapping_add(increments as u32);
+            return 0;
+        }
+
+        // Number of increments before the counter reaches the compare value (wrapping if it's already beyond it)
+        let remaining = u64::from(self.compare.wrapping_sub(self.counter));
+
+        if increments < remaining {
+            self.counter = self.counter.wrapping_add(increments as u32);
+            0
+        } else if self.control & TIMER_PERIODIC != 0 {
+            let rest = increments - remaining;
+            self.counter = (rest % u64::from(self.compare)) as u32;
+            1 + rest / u64::from(self.compare)
+        } else {
+            self.counter = 0;
+            self.control &= !TIMER_ENABLE;
+            1
+        }
+    }
+}
+
+impl Bus for IntervalTimer {
+    fn name(&self) -> &'static str {
+        "Interval Timer"
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        DeviceMetadata::new(self.hw_id, 24, ClockType::IntervalTimer.wrap(), None, None).encode()
+    }
+
+    fn read(&mut self, addr: u32, _ex: &mut u16) -> u32 {
+        self.peek(addr).unwrap()
+    }
+
+    fn write(&mut self, addr: u32, word: u32, _ex: &mut u16) {
+        match addr / 4 {
+            0x00 => {
+                let word =
+                    word & (TIMER_ENABLE | TIMER_PERIODIC | TIMER_WALL_TIME | TIMER_INTERRUPT);
+
+                // Start counting from now when the timer is enabled or its source changes
+                if (word ^ self.control) & (TIMER_ENABLE | TIMER_WALL_TIME) != 0 {
+                    self.elapsed = 0;
+                    self.since = Instant::now();
+                }
+
+                self.control = word;
+            }
+            0x01 => self.prescaler = word,
+            0x02 => self.compare = word,
+            0x03 => {
+                self.counter = word;
+                self.elapsed = 0;
+            }
+            0x04 => self.status &= !word,
+            0x05 => self.itr_code = word as u8,
+            _ => unreachable!(),
+        }
+    }
+
+    fn reset(&mut self, _kind: ResetKind) {
+        *self = Self::new(self.hw_id);
+    }
+
+    fn peek(&self, addr: u32) -> Option<u32> {
+        Some(match addr / 4 {
+            0x00 => self.control,
+            0x01 => self.prescaler,
+            0x02 => self.compare,
+            0x03 => self.counter,
+            0x04 => self.status,
+            0x05 => self.itr_code.into(),
+            _ => unreachable!(),
+        })
+    }
+
+    fn ticks(&self) -> bool {
+        true
+    }
+
+    fn tick(&mut self) -> Option<u8> {
+        if self.control & TIMER_ENABLE == 0 {
+            return None;
+        }
+
+        if self.control & TIMER_WALL_TIME == 0 {
+            self.elapsed += 1;
+        } else {
+            let micros = self.since.elapsed().as_micros() as u64;
+            self.since += Duration::from_micros(micros);
+            self.elapsed += micros;
+        }
+
+        let prescaler = u64::from(self.prescaler.max(1));
+
+        if self.elapsed < prescaler {
+            return None;
+        }
+
+        let increments = self.elapsed / prescaler;
+        self.elapsed %= prescaler;
+
+        let fired = self.advance(increments);
+
+        if fired == 0 {
+            return None;
+        }
+
+        if fired > 1 || self.status & TIMER_FIRED != 0 {
+            self.status |= TIMER_MISSED;
+        }
+
+        self.status |= TIMER_FIRED;
+
+        if self.control & TIMER_INTERRUPT != 0 {
+            Some(self.itr_code)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,212 @@
+//! The watchdog timer component recovers from a hung guest if it is not kicked regularly.
+//! See [`Watchdog`] for more details.
+
+use lrvm::board::{BoardRequest, Bus, ResetKind};
+use lrvm_tools::{
+    exceptions::AuxHwException,
+    metadata::{ClockType, DeviceMetadata},
+};
+
+/// Control flag: the watchdog is counting down
+pub const WDT_ENABLE: u32 = 0x01;
+
+/// Action: reset the whole machine (the reset reason is `ResetKind::Hardware`)
+pub const WDT_RESET: u32 = 0x00;
+/// Action: halt the CPU
+pub const WDT_HALT: u32 = 0x01;
+/// Action: raise a hardware interruption, then start counting down again
+pub const WDT_INTERRUPT: u32 = 0x02;
+
+/// Status flag: the watchdog timed out since the last acknowledgement (kept through all resets except cold ones)
+pub const WDT_TIMED_OUT: u32 = 0x01;
+
+/// The watchdog timer is an 8-word-long countdown of CPU cycles, which the guest must restart ("kick") before it reaches zero.
+///
+/// * Word 1: control flags (`WDT_ENABLE`)
+/// * Word 2: action to take on timeout, `WDT_RESET` (also used for unknown values), `WDT_HALT` or `WDT_INTERRUPT`
+/// * Word 3: timeout, in CPU cycles (`0` behaves like `1`)
+/// * Word 4: remaining cycles before the timeout (read-only)
+/// * Word 5: kick (write-only), any write restarts the countdown from the timeout
+/// * Word 6: status flags (`WDT_TIMED_OUT`) ; writing to it acknowledges the flags set in the written word
+/// * Word 7: number of timeouts since the machine was powered on (read-only)
+/// * Word 8: interruption code (only the weakest byte is kept)
+///
+/// Changing the control, action or timeout words also restarts the countdown.
+/// As the status and the number of timeouts are kept through resets (except cold ones), the guest can tell after rebooting
+/// that the watchdog timed out, along with the `HWD_RESET_REASON` hardware information.
+///
+/// A watchdog can be armed from the start with [`Watchdog::armed`], in which case it is enabled again after each reset.
+pub struct Watchdog {
+    hw_id: u64,
+    boot: Option<(u32, u32)>,
+    control: u32,
+    action: u32,
+    timeout: u32,
+    remaining: u32,
+    status: u32,
+    timeouts: u32,
+    itr_code: u8,
+    request: Option<BoardRequest>,
+}
+
+impl Watchdog {
+    /// Create a disabled watchdog, to be configured by the guest
+    pub fn new(hw_id: u64) -> Self {
+        Self::with_boot_config(None, hw_id)
+    }
+
+    /// Create a watchdog that is enabled right after each reset, with the provided timeout (in CPU cycles) and action
+    pub fn armed(timeout: u32, action: u32, hw_id: u64) -> Self {
+        Self::with_boot_config(Some((timeout, action)), hw_id)
+    }
+
+    /// (Internal) Create a watchdog with the configuration to restore on each reset
+    fn with_boot_config(boot: Option<(u32, u32)>, hw_id: u64) -> Self {
+        let mut watchdog = Self {
+            hw_id,
+            boot,
+            control: 0,
+            action: 0,
+            timeout: 0,
+            remaining: 0,
+            status: 0,
+            timeouts: 0,
+            itr_code: 0,
+            request: None,
+        };
+
+        watchdog.restore_boot_config();
+        watchdog
+    }
+
+    /// Get the number of timeouts since the machine was powered on
+    pub fn timeouts(&self) -> u32 {
+        self.timeouts
+    }
+
+    /// (Internal) Restore the configuration the watchdog has after a reset
+    fn restore_boot_config(&mut self) {
+        let (control, (timeout, action)) = match self.boot {
+            Some(config) => (WDT_ENABLE, config),
+            None => (0, (0, WDT_RESET)),
+        };
+
+        self.control = control;
+        self.action = action;
+        self.timeout = timeout;
+        self.kick();
+    }
+
+    /// (Internal) Restart the countdown
+    fn kick(&mut self) {
+        self.remaining = self.timeout.max(1);
+    }
+}
+
+impl Bus for Watchdog {
+    fn name(&self) -> &'static str {
+        "Watchdog Timer"
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        DeviceMetadata::new(self.hw_id, 8 * 4, ClockType::Watchdog.wrap(), None, None).encode()
+    }
+
+    fn read(&mut self, addr: u32, ex: &mut u16) -> u32 {
+        if addr == 0x10 {
+            *ex = AuxHwException::MemoryNotReadable.into();
+            return 0;
+        }
+
+        self.peek(addr).unwrap()
+    }
+
+    fn write(&mut self, addr: u32, word: u32, ex: &mut u16) {
+        match addr / 4 {
+            0x00 => self.control = word & WDT_ENABLE,
+            0x01 => self.action = word,
+            0x02 => self.timeout = word,
+            0x03 | 0x06 => {
+                *ex = AuxHwException::MemoryNotWritable.into();
+                return;
+            }
+            0x04 => {}
+            0x05 => {
+                self.status &= !word;
+                return;
+            }
+            0x07 => {
+                self.itr_code = word as u8;
+                return;
+            }
+            _ => unreachable!(),
+        }
+
+        self.kick();
+    }
+
+    fn reset(&mut self, kind: ResetKind) {
+        if kind == ResetKind::Cold {
+            self.status = 0;
+            self.timeouts = 0;
+        }
+
+        self.itr_code = 0;
+        self.request = None;
+        self.restore_boot_config();
+    }
+
+    fn peek(&self, addr: u32) -> Option<u32> {
+        Some(match addr / 4 {
+            0x00 => self.control,
+            0x01 => self.action,
+            0x02 => self.timeout,
+            0x03 => self.remaining,
+            0x04 => 0,
+            0x05 => self.status,
+            0x06 => self.timeouts,
+            0x07 => self.itr_code.into(),
+            _ => unreachable!(),
+        })
+    }
+
+    fn ticks(&self) -> bool {
+        true
+    }
+
+    fn tick(&mut self) -> Option<u8> {
+        if self.control & WDT_ENABLE == 0 {
+            return None;
+        }
+
+        self.remaining -= 1;
+
+        if self.remaining > 0 {
+            return None;
+        }
+
+        self.status |= WDT_TIMED_OUT;
+        self.timeouts = self.timeouts.wrapping_add(1);
+
+        match self.action {
+            WDT_INTERRUPT => {
+                self.kick();
+                Some(self.itr_code)
+            }
+            WDT_HALT => {
+                self.control &= !WDT_ENABLE;
+                self.request = Some(BoardRequest::Halt);
+                None
+            }
+            _ => {
+                self.control &= !WDT_ENABLE;
+                self.request = Some(BoardRequest::Reset);
+                None
+            }
+        }
+    }
+
+    fn request(&mut self) -> Option<BoardRequest> {
+        self.request.take()
+    }
+}
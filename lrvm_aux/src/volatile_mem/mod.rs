@@ -1,3 +1,5 @@
 mod ram;
+mod sparse;
 
-pub use self::ram::Ram;
+pub use self::ram::{PoisonAction, Ram};
+pub use self::sparse::{ResidentUsage, SparseRam, SPARSE_RAM_PAGE_SIZE};
@@ -1,17 +1,38 @@
 //! The RAM component offers a simple RAM that resets with the motherboard.
 //! See [`RAM`] for more details.
 
-use lrvm::board::Bus;
-use lrvm_tools::metadata::{DeviceMetadata, MemoryType};
+use lrvm::board::{Bus, ResetKind};
+use lrvm_tools::{
+    exceptions::AuxHwException,
+    metadata::{DeviceMetadata, MemoryType},
+};
+
+/// Action taken by a poisoned [`Ram`] when a word that was never written is read
+pub enum PoisonAction {
+    /// Raise an `UninitializedRead` hardware exception
+    Exception,
+    /// Call the provided handler with the word's offset in the RAM, in bytes, and read the word normally
+    Report(Box<dyn FnMut(u32)>),
+}
 
 /// The RAM component offers a simple non-persistent storage.
 /// When it receives a RESET request from the motherboard, all the storage is zeroed.
+///
+/// A RAM can also be poisoned with [`Ram::poison`] to detect reads of words that were not written since the last reset.
+/// Peeking is never reported, and poking counts as writing.
 pub struct Ram {
     storage: Vec<u32>,
     size: u32,
+    poison: Option<Poison>,
     hw_id: u64,
 }
 
+/// (Internal) Tracking of the written words of a poisoned RAM
+struct Poison {
+    written: Vec<bool>,
+    action: PoisonAction,
+}
+
 impl Ram {
     /// Create a new RAM component
     /// Returns an error message if the capacity is 0, not a multiple or 4 bytes or too large for the running CPU architecture.
@@ -29,6 +50,7 @@ impl Ram {
                     })?
                 ],
                 size: size / 4,
+                poison: None,
                 hw_id,
             })
         }
@@ -45,6 +67,7 @@ impl Ram {
         Ok(Self {
             storage,
             size: size / 4,
+            poison: None,
             hw_id,
         })
     }
@@ -83,6 +106,7 @@ impl Ram {
         Ok(Self {
             storage,
             size,
+            poison: None,
             hw_id,
         })
     }
@@ -91,6 +115,27 @@ impl Ram {
     pub fn size(&self) -> u32 {
         self.size
     }
+
+    /// Poison the RAM: from now on, reading a word that was not written since this call or since the last reset
+    /// triggers the provided action. The words the RAM already contains are considered as never written.
+    pub fn poison(&mut self, action: PoisonAction) {
+        self.poison = Some(Poison {
+            written: vec![false; self.storage.len()],
+            action,
+        });
+    }
+
+    /// Check if the RAM is poisoned
+    pub fn is_poisoned(&self) -> bool {
+        self.poison.is_some()
+    }
+
+    /// (Internal) Mark a word as written, if the RAM is poisoned
+    fn mark_written(&mut self, addr: u32) {
+        if let Some(poison) = &mut self.poison {
+            poison.written[addr as usize / 4] = true;
+        }
+    }
 }
 
 impl Bus for Ram {
@@ -109,15 +154,42 @@ impl Bus for Ram {
         .encode()
     }
 
-    fn read(&mut self, addr: u32, _ex: &mut u16) -> u32 {
+    fn read(&mut self, addr: u32, ex: &mut u16) -> u32 {
+        if let Some(poison) = &mut self.poison {
+            if !poison.written[addr as usize / 4] {
+                match &mut poison.action {
+                    PoisonAction::Exception => {
+                        *ex = AuxHwException::UninitializedRead.into();
+                        return 0;
+                    }
+                    PoisonAction::Report(handler) => handler(addr),
+                }
+            }
+        }
+
         self.storage[addr as usize / 4]
     }
 
     fn write(&mut self, addr: u32, word: u32, _ex: &mut u16) {
         self.storage[addr as usize / 4] = word;
+        self.mark_written(addr);
     }
 
-    fn reset(&mut self) {
+    fn reset(&mut self, _kind: ResetKind) {
         self.storage = vec![0; self.storage.len()];
+
+        if let Some(poison) = &mut self.poison {
+            poison.written = vec![false; self.storage.len()];
+        }
+    }
+
+    fn peek(&self, addr: u32) -> Option<u32> {
+        Some(self.storage[addr as usize / 4])
+    }
+
+    fn poke(&mut self, addr: u32, word: u32) -> bool {
+        self.storage[addr as usize / 4] = word;
+        self.mark_written(addr);
+        true
     }
 }
@@ -0,0 +1,182 @@
+//! The sparse RAM component offers a large RAM whose host memory is only allocated when it is written.
+//! See [`SparseRam`] for more details.
+
+use std::{cell::Cell, collections::HashMap, rc::Rc};
+
+use lrvm::board::{Bus, ResetKind};
+use lrvm_tools::{
+    exceptions::AuxHwException,
+    metadata::{DeviceMetadata, MemoryType},
+};
+
+/// Size of the pages allocated by a [`SparseRam`], in bytes
+pub const SPARSE_RAM_PAGE_SIZE: u32 = 0x1000;
+
+/// (Internal) Number of words in a page
+const PAGE_WORDS: usize = SPARSE_RAM_PAGE_SIZE as usize / 4;
+
+/// The sparse RAM component behaves like a [`Ram`](super::Ram), but only allocates host memory for the pages of
+/// [`SPARSE_RAM_PAGE_SIZE`] bytes that are written to. All other words read as zero, and writing a zero to a page
+/// that was not allocated yet does not allocate it.
+///
+/// This allows mapping a RAM covering a large part of the address space without paying for it on the host.
+/// The total resident memory can also be capped, in which case writes requiring a new page beyond the cap
+/// raise a `ResidentCapExceeded` hardware exception, and are ignored.
+///
+/// When it receives a RESET request from the motherboard, all pages are released.
+pub struct SparseRam {
+    pages: HashMap<u32, Box<[u32]>>,
+    size: u32,
+    max_pages: Option<u32>,
+    usage: ResidentUsage,
+    hw_id: u64,
+}
+
+impl SparseRam {
+    /// Create a new sparse RAM component, without any resident memory cap
+    /// Returns an error message if the capacity is 0 or not a multiple of 4 bytes.
+    pub fn new(size: u32, hw_id: u64) -> Result<Self, &'static str> {
+        Self::with_pages_cap(size, None, hw_id)
+    }
+
+    /// Create a new sparse RAM component whose resident memory cannot exceed the provided number of bytes,
+    /// rounded down to a multiple of [`SPARSE_RAM_PAGE_SIZE`]
+    /// Returns an error message if the capacity is 0 or not a multiple of 4 bytes.
+    pub fn with_resident_cap(
+        size: u32,
+        resident_cap: u32,
+        hw_id: u64,
+    ) -> Result<Self, &'static str> {
+        Self::with_pages_cap(size, Some(resident_cap / SPARSE_RAM_PAGE_SIZE), hw_id)
+    }
+
+    /// (Internal) Create a new sparse RAM component with an optional maximum number of pages
+    fn with_pages_cap(size: u32, max_pages: Option<u32>, hw_id: u64) -> Result<Self, &'static str> {
+        if size == 0 {
+            Err("RAM's size cannot be 0")
+        } else if size % 4 != 0 {
+            Err("RAM's size must be a multiple of 4 bytes")
+        } else {
+            Ok(Self {
+                pages: HashMap::new(),
+                size,
+                max_pages,
+                usage: ResidentUsage::default(),
+                hw_id,
+            })
+        }
+    }
+
+    /// Get the RAM's size, in bytes
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Get the resident memory cap, in bytes
+    pub fn resident_cap(&self) -> Option<u64> {
+        self.max_pages
+            .map(|pages| u64::from(pages) * u64::from(SPARSE_RAM_PAGE_SIZE))
+    }
+
+    /// Get a handle to the resident memory usage, which can still be used once the component is given to the motherboard
+    pub fn usage(&self) -> ResidentUsage {
+        self.usage.clone()
+    }
+
+    /// (Internal) Write a word, returning `false` if a page was required but the resident memory cap was reached
+    fn write_word(&mut self, addr: u32, word: u32) -> bool {
+        let page_addr = addr / SPARSE_RAM_PAGE_SIZE;
+        let offset = (addr % SPARSE_RAM_PAGE_SIZE) as usize / 4;
+
+        if let Some(page) = self.pages.get_mut(&page_addr) {
+            page[offset] = word;
+            return true;
+        }
+
+        if word == 0 {
+            return true;
+        }
+
+        if matches!(self.max_pages, Some(max_pages) if self.pages.len() as u32 >= max_pages) {
+            return false;
+        }
+
+        let mut page = vec![0; PAGE_WORDS].into_boxed_slice();
+        page[offset] = word;
+
+        self.pages.insert(page_addr, page);
+        self.usage.set_pages(self.pages.len() as u32);
+        true
+    }
+}
+
+impl Bus for SparseRam {
+    fn name(&self) -> &'static str {
+        "Sparse RAM"
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        DeviceMetadata::new(self.hw_id, self.size, MemoryType::Ram.into(), None, None).encode()
+    }
+
+    fn read(&mut self, addr: u32, _ex: &mut u16) -> u32 {
+        self.peek(addr).unwrap()
+    }
+
+    fn write(&mut self, addr: u32, word: u32, ex: &mut u16) {
+        if !self.write_word(addr, word) {
+            *ex = AuxHwException::ResidentCapExceeded.into();
+        }
+    }
+
+    fn reset(&mut self, _kind: ResetKind) {
+        self.pages = HashMap::new();
+        self.usage.set_pages(0);
+    }
+
+    fn peek(&self, addr: u32) -> Option<u32> {
+        Some(match self.pages.get(&(addr / SPARSE_RAM_PAGE_SIZE)) {
+            Some(page) => page[(addr % SPARSE_RAM_PAGE_SIZE) as usize / 4],
+            None => 0,
+        })
+    }
+
+    fn poke(&mut self, addr: u32, word: u32) -> bool {
+        self.write_word(addr, word)
+    }
+}
+
+/// Host-side handle to the resident memory usage of a [`SparseRam`], obtained with [`SparseRam::usage`].
+/// Clones of a handle all share the same counters.
+#[derive(Debug, Clone, Default)]
+pub struct ResidentUsage(Rc<ResidentCounters>);
+
+/// (Internal) Counters shared by the [`ResidentUsage`] handles
+#[derive(Debug, Default)]
+struct ResidentCounters {
+    pages: Cell<u32>,
+    peak_pages: Cell<u32>,
+}
+
+impl ResidentUsage {
+    /// Get the number of allocated pages
+    pub fn resident_pages(&self) -> u32 {
+        self.0.pages.get()
+    }
+
+    /// Get the allocated memory, in bytes
+    pub fn resident_bytes(&self) -> u64 {
+        u64::from(self.resident_pages()) * u64::from(SPARSE_RAM_PAGE_SIZE)
+    }
+
+    /// Get the highest allocated memory since the component was created, in bytes
+    pub fn peak_resident_bytes(&self) -> u64 {
+        u64::from(self.0.peak_pages.get()) * u64::from(SPARSE_RAM_PAGE_SIZE)
+    }
+
+    /// (Internal) Update the number of allocated pages
+    fn set_pages(&self, pages: u32) {
+        self.0.pages.set(pages);
+        self.0.peak_pages.set(self.0.peak_pages.get().max(pages));
+    }
+}
@@ -0,0 +1,70 @@
+use std::path::PathBuf;
+
+use clap::{Args, ValueEnum};
+use lrvm_tools::lasm::assemble;
+
+use crate::{
+    program::{read_text_file, write_output},
+    EXIT_SUCCESS,
+};
+
+#[derive(Args)]
+pub struct AsmArgs {
+    /// LASM source file
+    input: PathBuf,
+
+    /// Output file (standard output if omitted)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = AsmFormat::Bin)]
+    format: AsmFormat,
+}
+
+/// Output format of the assembler
+#[derive(Clone, Copy, ValueEnum)]
+enum AsmFormat {
+    /// Raw binary
+    Bin,
+    /// Hexadecimal bytes (16 bytes per line)
+    Hex,
+    /// Hexadecimal words (one instruction per line)
+    Words,
+}
+
+/// Assemble a LASM source file
+pub fn asm(args: AsmArgs) -> Result<u8, String> {
+    let source = read_text_file(&args.input)?;
+
+    let bytes = assemble(&source)
+        .map_err(|err| format!("Failed to assemble '{}':\n{}", args.input.display(), err))?;
+
+    let output = match args.format {
+        AsmFormat::Bin => bytes,
+
+        AsmFormat::Hex => bytes
+            .chunks(16)
+            .map(|line| {
+                let mut line: String = line.iter().map(|byte| format!("{:02X}", byte)).collect();
+                line.push('\n');
+                line
+            })
+            .collect::<String>()
+            .into_bytes(),
+
+        AsmFormat::Words => bytes
+            .chunks(4)
+            .map(|word| {
+                let mut padded = [0; 4];
+                padded[..word.len()].copy_from_slice(word);
+                format!("{:#010X}\n", u32::from_be_bytes(padded))
+            })
+            .collect::<String>()
+            .into_bytes(),
+    };
+
+    write_output(args.output.as_deref(), &output)?;
+
+    Ok(EXIT_SUCCESS)
+}
@@ -0,0 +1,72 @@
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use lrvm::board::MotherBoard;
+use lrvm_aux::registry::registry;
+use lrvm_tools::{
+    config::{BootConfig, MachineConfig},
+    presets::Preset,
+};
+
+use crate::program::{is_lasm, load_program};
+
+#[derive(Args)]
+pub struct BoardArgs {
+    /// Preset board to use when no machine description is provided (minimal, console or dev-kit)
+    #[arg(short, long, default_value_t = Preset::Console, conflicts_with = "machine")]
+    pub preset: Preset,
+
+    /// Machine description (`.toml` or `.json`) to use instead of a preset board
+    #[arg(short, long)]
+    pub machine: Option<PathBuf>,
+}
+
+/// Build the board to run a program on.
+/// When a machine description is provided, the program (if any) replaces its boot image.
+/// Otherwise, the program runs on the selected preset, whose display and keyboard use the standard output and input.
+pub fn build_board(args: &BoardArgs, program: Option<&Path>) -> Result<MotherBoard, String> {
+    match &args.machine {
+        Some(machine) => build_machine(machine, program),
+        None => args
+            .preset
+            .build_with_stdio(
+                match program {
+                    Some(program) => load_program(program)?,
+                    None => vec![],
+                },
+                &registry(),
+            )
+            .map_err(|err| err.to_string()),
+    }
+}
+
+/// Build a board from a machine description
+fn build_machine(machine: &Path, program: Option<&Path>) -> Result<MotherBoard, String> {
+    let mut config = MachineConfig::load(machine).map_err(|err| err.to_string())?;
+
+    if let Some(program) = program {
+        let program = program
+            .canonicalize()
+            .map_err(|err| format!("Failed to open '{}': {}", program.display(), err))?
+            .to_string_lossy()
+            .into_owned();
+
+        config.boot = Some(if is_lasm(Path::new(&program)) {
+            BootConfig {
+                lasm: Some(program),
+                binary: None,
+            }
+        } else {
+            BootConfig {
+                lasm: None,
+                binary: Some(program),
+            }
+        });
+    }
+
+    let base_dir = machine.parent().unwrap_or_else(|| Path::new("."));
+
+    config
+        .build(&registry(), base_dir)
+        .map_err(|err| format!("Invalid machine '{}': {}", machine.display(), err))
+}
@@ -0,0 +1,47 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use lrvm_tools::asm::Program;
+
+use crate::{
+    program::{read_file, write_output},
+    EXIT_SUCCESS,
+};
+
+#[derive(Args)]
+pub struct DisasmArgs {
+    /// Binary program
+    input: PathBuf,
+
+    /// Output file (standard output if omitted)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Annotate each instruction with its address
+    #[arg(short, long)]
+    annotate: bool,
+
+    /// Fail on words that are not valid instructions instead of outputting them as raw data
+    #[arg(long)]
+    strict: bool,
+}
+
+/// Disassemble a binary program
+pub fn disasm(args: DisasmArgs) -> Result<u8, String> {
+    let bytes = read_file(&args.input)?;
+
+    let program = Program::decode(bytes, args.strict).map_err(|(instr, err)| {
+        format!(
+            "Failed to decode instruction at address {:#010X}: {}",
+            instr * 4,
+            err
+        )
+    })?;
+
+    let mut lasm = program.to_lasm(args.annotate);
+    lasm.push('\n');
+
+    write_output(args.output.as_deref(), lasm.as_bytes())?;
+
+    Ok(EXIT_SUCCESS)
+}
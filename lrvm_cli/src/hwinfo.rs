@@ -0,0 +1,27 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use lrvm_tools::debug::print_mapping;
+
+use crate::{
+    board::{build_board, BoardArgs},
+    EXIT_SUCCESS,
+};
+
+#[derive(Args)]
+pub struct HwInfoArgs {
+    /// Program to load in the board (LASM source or binary)
+    program: Option<PathBuf>,
+
+    #[command(flatten)]
+    board: BoardArgs,
+}
+
+/// Display the components of a board and their mapping
+pub fn hwinfo(args: HwInfoArgs) -> Result<u8, String> {
+    let mut motherboard = build_board(&args.board, args.program.as_deref())?;
+
+    print_mapping(&mut motherboard);
+
+    Ok(EXIT_SUCCESS)
+}
@@ -0,0 +1,86 @@
+//! Command-line interface for LRVM: assemble and disassemble programs, run them and inspect machines.
+
+mod asm;
+mod board;
+mod disasm;
+mod hwinfo;
+mod program;
+mod run;
+
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+
+/// Exit code when the command succeeded (or when the VM halted normally)
+pub const EXIT_SUCCESS: u8 = 0;
+
+/// Highest exit code a program can provide when shutting the machine down, higher ones being reserved for the CLI
+pub const EXIT_GUEST_MAX: u8 = 249;
+
+/// Exit code when the command failed (invalid input file, assembly error, invalid machine, ...)
+pub const EXIT_ERROR: u8 = 250;
+
+/// Exit code when the command-line arguments are invalid
+pub const EXIT_USAGE: u8 = 251;
+
+/// Exit code when the VM was stopped by an exception
+pub const EXIT_EXCEPTION: u8 = 252;
+
+/// Exit code when the VM reached the cycles limit without halting
+pub const EXIT_CYCLES_LIMIT: u8 = 253;
+
+/// Lightweight Rust Virtual Machine
+#[derive(Parser)]
+#[command(name = "lrvm", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Assemble a LASM source file
+    Asm(asm::AsmArgs),
+    /// Disassemble a binary program to LASM source code
+    Disasm(disasm::DisasmArgs),
+    /// Run a program on a preset board or on a described machine
+    ///
+    /// Exits with 0 when the VM halts normally, 250 on error, 251 on invalid arguments, 252 when the VM is stopped
+    /// by an exception and 253 when the cycles limit is reached.
+    /// If the program shuts the machine down through a power controller, its own exit code is used instead,
+    /// lowered to 249 if it is higher so it can't be mistaken for one of the codes above.
+    Run(run::RunArgs),
+    /// Display the components of a preset board or of a described machine, and their mapping
+    Hwinfo(hwinfo::HwInfoArgs),
+}
+
+fn main() -> ExitCode {
+    // Invalid arguments get their own exit code, while help and version requests succeed
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(err) => {
+            let _ = err.print();
+
+            return ExitCode::from(if err.use_stderr() {
+                EXIT_USAGE
+            } else {
+                EXIT_SUCCESS
+            });
+        }
+    };
+
+    let result = match cli.command {
+        Command::Asm(args) => asm::asm(args),
+        Command::Disasm(args) => disasm::disasm(args),
+        Command::Run(args) => run::run(args),
+        Command::Hwinfo(args) => hwinfo::hwinfo(args),
+    };
+
+    match result {
+        Ok(code) => ExitCode::from(code),
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            ExitCode::from(EXIT_ERROR)
+        }
+    }
+}
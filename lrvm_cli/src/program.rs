@@ -0,0 +1,58 @@
+use std::{
+    fs,
+    io::{stdout, Write},
+    path::Path,
+};
+
+use lrvm_tools::{bytes::bytes_to_words, lasm::assemble_words};
+
+/// Check if a file is a LASM source file (from its extension)
+pub fn is_lasm(path: &Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()) == Some("lasm")
+}
+
+/// Read a file
+pub fn read_file(path: &Path) -> Result<Vec<u8>, String> {
+    fs::read(path).map_err(|err| format!("Failed to read '{}': {}", path.display(), err))
+}
+
+/// Read a text file
+pub fn read_text_file(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|err| format!("Failed to read '{}': {}", path.display(), err))
+}
+
+/// Load a program: LASM source files (`.lasm` extension) are assembled, other files are read as raw binary
+pub fn load_program(path: &Path) -> Result<Vec<u32>, String> {
+    if is_lasm(path) {
+        assemble_words(&read_text_file(path)?)
+            .map_err(|err| format!("Failed to assemble '{}':\n{}", path.display(), err))
+    } else {
+        let bytes = read_file(path)?;
+
+        if bytes.len() % 4 != 0 {
+            return Err(format!(
+                "Size of binary program '{}' is not a multiple of 4 bytes",
+                path.display()
+            ));
+        }
+
+        Ok(bytes_to_words(bytes))
+    }
+}
+
+/// Write data to a file, or to the standard output if no path is provided
+pub fn write_output(path: Option<&Path>, data: &[u8]) -> Result<(), String> {
+    match path {
+        Some(path) => fs::write(path, data)
+            .map_err(|err| format!("Failed to write '{}': {}", path.display(), err)),
+
+        None => {
+            let mut stdout = stdout();
+
+            stdout
+                .write_all(data)
+                .and_then(|()| stdout.flush())
+                .map_err(|err| format!("Failed to write to standard output: {}", err))
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use lrvm_tools::debug::{run_vm, RunConfig};
+
+use crate::{
+    board::{build_board, BoardArgs},
+    EXIT_CYCLES_LIMIT, EXIT_EXCEPTION, EXIT_GUEST_MAX, EXIT_SUCCESS,
+};
+
+#[derive(Args)]
+pub struct RunArgs {
+    /// Program to run (LASM source or binary), optional if the machine description has a boot image
+    program: Option<PathBuf>,
+
+    #[command(flatten)]
+    board: BoardArgs,
+
+    /// Stop the VM after this number of cycles
+    #[arg(short, long)]
+    cycles: Option<u128>,
+
+    /// Keep running when an exception occurs instead of stopping the VM
+    #[arg(long)]
+    continue_on_ex: bool,
+
+    /// Print a message on each CPU cycle
+    #[arg(long)]
+    print_cycles: bool,
+
+    /// Don't print exceptions nor the final state of the VM
+    #[arg(short, long, conflicts_with = "print_cycles")]
+    quiet: bool,
+}
+
+/// Run a program.
+/// The exit code tells if the VM halted normally, was stopped by an exception or reached the cycles limit,
+/// unless the program shut the machine down with its own exit code (lowered to [`EXIT_GUEST_MAX`]).
+pub fn run(args: RunArgs) -> Result<u8, String> {
+    if args.program.is_none() && args.board.machine.is_none() {
+        return Err("A program is required to run a preset board".to_string());
+    }
+
+    let mut motherboard = build_board(&args.board, args.program.as_deref())?;
+
+    let config = if args.quiet {
+        RunConfig::quiet()
+    } else {
+        RunConfig::new()
+    }
+    .with_cycles_limit(args.cycles)
+    .with_halt_on_exception(!args.continue_on_ex)
+    .with_print_cycles(args.print_cycles);
+
+    let cpu = motherboard.cpu();
+    let state = run_vm(cpu, config);
+
+    if let Some(code) = state.guest_exit_code() {
+        return Ok(code.min(EXIT_GUEST_MAX));
+    }
+
+    Ok(if state.ex.is_some() {
+        EXIT_EXCEPTION
+    } else if !cpu.halted() {
+        EXIT_CYCLES_LIMIT
+    } else {
+        EXIT_SUCCESS
+    })
+}
@@ -0,0 +1,252 @@
+use std::{
+    fs,
+    path::PathBuf,
+    process::{Command, Output},
+};
+
+use lrvm_aux::power::PWR_SHUTDOWN;
+use lrvm_tools::{
+    asm::{ExtInstr, Instr, Program, Reg},
+    bytes::words_to_bytes,
+};
+
+/// Address the power controller is mapped at in the machine running the tests' programs
+const PWR: u32 = 0x1000;
+
+/// Machine with a power controller, to run the tests' programs on
+const MACHINE: &str = r#"
+[[components]]
+type = "bootrom"
+size = 0x1000
+boot = true
+
+[[components]]
+type = "power_control"
+addr = 0x1000
+"#;
+
+/// Temporary directory for the files provided to the CLI, removed along with its content when dropped
+struct TempDir(PathBuf);
+
+impl TempDir {
+    /// Create an empty directory, named after the test using it and the running process
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("lrvm-cli-{}-{}", name, std::process::id()));
+
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+
+        Self(path)
+    }
+
+    /// Write a file in the directory and get its path
+    fn write(&self, name: &str, content: impl AsRef<[u8]>) -> String {
+        let path = self.0.join(name);
+        fs::write(&path, content).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Run the CLI with the provided arguments
+fn lrvm(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_lrvm"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// Run the CLI with the provided arguments and get its exit code
+fn exit_code(args: &[&str]) -> i32 {
+    lrvm(args).status.code().unwrap()
+}
+
+/// Get a binary program
+fn binary(program: Program) -> Vec<u8> {
+    words_to_bytes(program.encode_words())
+}
+
+/// Get a binary program shutting the machine down with the provided exit code
+fn shutdown(code: u32) -> Vec<u8> {
+    let mut program = Program::new();
+    program.append_all(ExtInstr::WriteAddrLit(PWR, code).to_prog_words());
+    program.append_all(ExtInstr::WriteAddrLit(PWR + 0x04, PWR_SHUTDOWN).to_prog_words());
+    program.append(Instr::Halt().into());
+
+    binary(program)
+}
+
+#[test]
+fn cli_guest_exit_codes() {
+    let dir = TempDir::new("guest-exit-codes");
+    let machine = dir.write("machine.toml", MACHINE);
+
+    for (code, expected) in [
+        (0, 0),
+        (2, 2),
+        (249, 249),
+        (250, 249),
+        (0xFF, 249),
+        (0x100, 1),
+    ] {
+        let program = dir.write("prog.bin", shutdown(code));
+
+        assert_eq!(
+            exit_code(&["run", "-q", &program, "--machine", &machine]),
+            expected,
+            "Invalid exit code for guest code {}",
+            code
+        );
+    }
+}
+
+#[test]
+fn cli_usage_exit_codes() {
+    assert_eq!(exit_code(&["--version"]), 0);
+    assert_eq!(exit_code(&["run", "--invalid-flag"]), 251);
+    assert_eq!(exit_code(&["unknown-command"]), 251);
+}
+
+#[test]
+fn cli_run_exit_codes() {
+    let dir = TempDir::new("run-exit-codes");
+    let machine = dir.write("machine.toml", MACHINE);
+
+    let run = |program: Vec<u8>, extra_args: &[&str]| {
+        let program = dir.write("prog.bin", program);
+
+        let mut args = vec!["run", "-q", &program, "--machine", &machine];
+        args.extend_from_slice(extra_args);
+
+        exit_code(&args)
+    };
+
+    // Halting normally
+    let program = binary(Program::from_instr(vec![Instr::Halt()]));
+    assert_eq!(run(program, &[]), 0);
+
+    // Running an invalid instruction
+    assert_eq!(run(vec![0xFF; 4], &[]), 252);
+
+    // Looping forever
+    let program = binary(Program::from_instr(vec![Instr::Jpr(0_u16.into())]));
+    assert_eq!(run(program, &["--cycles", "100"]), 253);
+
+    // Shutting the machine down
+    assert_eq!(run(shutdown(7), &[]), 7);
+
+    // Missing program
+    let missing = dir.0.join("missing.bin");
+    assert_eq!(
+        exit_code(&[
+            "run",
+            "-q",
+            missing.to_str().unwrap(),
+            "--machine",
+            &machine
+        ]),
+        250
+    );
+}
+
+#[test]
+fn cli_asm_formats() {
+    let dir = TempDir::new("asm-formats");
+    let source = dir.write(
+        "prog.lasm",
+        "cpy a0, 0x1234\nadd a0, 1\nsub a0, 1\ncmp a0, 0\nhalt\n",
+    );
+
+    let expected = binary(Program::from_instr(vec![
+        Instr::Cpy(Reg::a0, 0x1234_u16.into()),
+        Instr::Add(Reg::a0, 1_u16.into()),
+        Instr::Sub(Reg::a0, 1_u16.into()),
+        Instr::Cmp(Reg::a0, 0_u16.into()),
+        Instr::Halt(),
+    ]));
+
+    let asm = |format| {
+        let output = lrvm(&["asm", &source, "--format", format]);
+        assert!(output.status.success(), "Failed to assemble the program");
+        output.stdout
+    };
+
+    assert_eq!(asm("bin"), expected);
+
+    // 16 bytes per line
+    let hex = String::from_utf8(asm("hex")).unwrap();
+    let hex_lines: Vec<_> = hex.lines().collect();
+    assert_eq!(hex_lines.len(), 2);
+    assert_eq!(hex_lines[0].len(), 32);
+    assert_eq!(hex_lines[1].len(), 8);
+    assert!(hex.ends_with('\n'));
+
+    let hex_bytes: Vec<u8> = hex_lines
+        .concat()
+        .as_bytes()
+        .chunks(2)
+        .map(|byte| u8::from_str_radix(std::str::from_utf8(byte).unwrap(), 16).unwrap())
+        .collect();
+    assert_eq!(hex_bytes, expected);
+
+    // One instruction per line
+    let words = String::from_utf8(asm("words")).unwrap();
+    let expected_words: String = expected
+        .chunks(4)
+        .map(|word| format!("{:#010X}\n", u32::from_be_bytes(word.try_into().unwrap())))
+        .collect();
+    assert_eq!(words, expected_words);
+
+    // Output file
+    let output = dir.0.join("prog.bin");
+    let output = output.to_str().unwrap();
+    assert_eq!(exit_code(&["asm", &source, "--output", output]), 0);
+    assert_eq!(fs::read(output).unwrap(), expected);
+
+    // Invalid source
+    let invalid = dir.write("invalid.lasm", "not_an_instruction a0\n");
+    assert_eq!(exit_code(&["asm", &invalid]), 250);
+}
+
+#[test]
+fn cli_disasm_annotations() {
+    let dir = TempDir::new("disasm-annotations");
+
+    let mut bytes = binary(Program::from_instr(vec![
+        Instr::Cpy(Reg::a0, 0x1234_u16.into()),
+        Instr::Halt(),
+    ]));
+    bytes.extend_from_slice(&[0xFF; 4]);
+
+    let program = dir.write("prog.bin", bytes);
+
+    let output = lrvm(&["disasm", &program, "--annotate"]);
+    assert!(output.status.success(), "Failed to disassemble the program");
+
+    let lasm = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<_> = lasm.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            "0x00000000: cpy a0, 0x1234",
+            "0x00000004: halt",
+            "0x00000008: #d32 0xFF_FF_FF_FF",
+        ]
+    );
+
+    // Without annotations
+    let output = lrvm(&["disasm", &program]);
+    let lasm = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        lasm.lines().collect::<Vec<_>>(),
+        ["cpy a0, 0x1234", "halt", "#d32 0xFF_FF_FF_FF"]
+    );
+
+    // Invalid words are rejected in strict mode
+    assert_eq!(exit_code(&["disasm", &program, "--strict"]), 250);
+}
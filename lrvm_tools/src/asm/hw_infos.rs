@@ -19,6 +19,7 @@ pub enum HwInfo {
     Model,
     DataUpper,
     DataLower,
+    ResetReason,
     IsMapped,
     MapStart,
     MapEnd,
@@ -46,6 +47,7 @@ impl HwInfo {
             0x23 => Ok(Self::Model),
             0x24 => Ok(Self::DataUpper),
             0x25 => Ok(Self::DataLower),
+            0x30 => Ok(Self::ResetReason),
             0xA0 => Ok(Self::IsMapped),
             0xA1 => Ok(Self::MapStart),
             0xA2 => Ok(Self::MapEnd),
@@ -74,6 +76,7 @@ impl HwInfo {
             Self::Model => 0x23,
             Self::DataUpper => 0x24,
             Self::DataLower => 0x25,
+            Self::ResetReason => 0x30,
             Self::IsMapped => 0xA0,
             Self::MapStart => 0xA1,
             Self::MapEnd => 0xA2,
@@ -101,6 +104,7 @@ impl HwInfo {
             Self::Model => "HWD_MODEL",
             Self::DataUpper => "HWD_DATA_UPPER",
             Self::DataLower => "HWD_DATA_LOWER",
+            Self::ResetReason => "HWD_RESET_REASON",
             Self::IsMapped => "HWD_IS_MAPPED",
             Self::MapStart => "HWD_MAP_START",
             Self::MapEnd => "HWD_MAP_END",
@@ -5,7 +5,7 @@ pub fn bytes_to_words(bytes: impl AsRef<[u8]>) -> Vec<u32> {
     let bytes = bytes.as_ref();
 
     let rem = bytes.len() % 4;
-    let mut words = Vec::with_capacity(bytes.len() / 4 + usize::from(rem == 0));
+    let mut words = Vec::with_capacity(bytes.len() / 4 + usize::from(rem != 0));
     let mut word = 0;
 
     for (i, byte) in bytes.iter().enumerate() {
@@ -18,7 +18,7 @@ pub fn bytes_to_words(bytes: impl AsRef<[u8]>) -> Vec<u32> {
     }
 
     if rem != 0 {
-        words.push(word << ((4 - rem) * 8));
+        words.push(word);
     }
 
     words
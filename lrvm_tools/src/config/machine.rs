@@ -0,0 +1,501 @@
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use lrvm::{
+    board::{Bus, MotherBoard, ResetKind},
+    mem::MappingRange,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{bytes::bytes_to_words, lasm::assemble_words};
+
+use super::{BuildContext, ComponentRegistry, InputTarget, OutputTarget};
+
+/// Declarative description of a machine: its components, their mapping and the boot image.
+///
+/// Machine descriptions can be written in TOML:
+///
+/// ```toml
+/// [boot]
+/// lasm = "src/source.lasm"
+///
+/// [[components]]
+/// type = "bootrom"
+/// size = 0x1000
+/// boot = true
+///
+/// [[components]]
+/// type = "ram"
+/// size = 0x1000
+///
+/// [[components]]
+/// type = "buffered_display"
+/// size = 0x100
+/// output = "stdout"
+/// addr = 0x2000
+/// ```
+///
+/// Or in JSON, with the same structure.
+/// Components are mapped in order: those with an explicit `addr` are mapped at this address, the others right after the previous component.
+/// Besides `type`, `hw_id`, `addr`, `size` and `boot`, a component's fields are options specific to its type (see [`ComponentOptions`]).
+/// Relative paths are resolved from the configuration file's directory.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MachineConfig {
+    /// Boot image
+    pub boot: Option<BootConfig>,
+    /// Components, in mapping order
+    pub components: Vec<ComponentConfig>,
+}
+
+/// Boot image of a machine
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BootConfig {
+    /// Path to a LASM source file to assemble
+    pub lasm: Option<String>,
+    /// Path to a binary file (big-endian words)
+    pub binary: Option<String>,
+}
+
+/// Description of a single component.
+///
+/// Only the fields shared by all component types are described here. All other fields are options specific to the
+/// component's type, which are parsed and validated by its entry in the [`ComponentRegistry`].
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ComponentConfig {
+    /// Type of the component, as known by the [`ComponentRegistry`]
+    #[serde(rename = "type")]
+    pub typ: String,
+    /// Hardware identifier (derived from the component's type and position if not provided)
+    pub hw_id: Option<u64>,
+    /// Mapping address (mapped right after the previous component if not provided)
+    pub addr: Option<u32>,
+    /// Size of the component, in bytes
+    pub size: Option<u32>,
+    /// Should the boot image be loaded in this component?
+    pub boot: Option<bool>,
+    /// Options specific to the component's type
+    #[serde(flatten)]
+    pub options: ComponentOptions,
+    /// Output target set from code, taking precedence over the `output` option
+    #[serde(skip)]
+    pub output_target: Option<OutputTarget>,
+    /// Input target set from code, taking precedence over the `input` option
+    #[serde(skip)]
+    pub input_target: Option<InputTarget>,
+}
+
+impl ComponentConfig {
+    /// Create a component description with only a type
+    pub fn new(typ: impl Into<String>) -> Self {
+        Self {
+            typ: typ.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Get the name of the shared fields that are set (`output` and `input` for the targets set from code)
+    pub fn set_fields(&self) -> Vec<&'static str> {
+        [
+            ("hw_id", self.hw_id.is_some()),
+            ("addr", self.addr.is_some()),
+            ("size", self.size.is_some()),
+            ("boot", self.boot.is_some()),
+            ("output", self.output_target.is_some()),
+            ("input", self.input_target.is_some()),
+        ]
+        .into_iter()
+        .filter(|(_, set)| *set)
+        .map(|(name, _)| name)
+        .collect()
+    }
+
+    /// Get the component's size, failing if it was not provided
+    pub fn require_size(&self) -> Result<u32, String> {
+        self.size
+            .ok_or_else(|| "missing required field 'size'".to_string())
+    }
+}
+
+/// Options specific to a component's type, as written in the machine description.
+/// They are parsed into a type-specific structure with [`ComponentOptions::parse`].
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub struct ComponentOptions(Map<String, Value>);
+
+impl ComponentOptions {
+    /// Create an empty set of options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set an option, replacing any existing one with the same name
+    pub fn set(&mut self, name: impl Into<String>, value: impl Serialize) -> &mut Self {
+        self.0.insert(
+            name.into(),
+            serde_json::to_value(value).unwrap_or(Value::Null),
+        );
+
+        self
+    }
+
+    /// Get the name of the options that are set
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.0.keys().map(String::as_str)
+    }
+
+    /// Check if no option is set
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Parse the options into a type-specific structure.
+    /// Returns an error message if an option is unknown, missing or invalid.
+    pub fn parse<T: DeserializeOwned>(&self) -> Result<T, String> {
+        serde_json::from_value(Value::Object(self.0.clone())).map_err(|err| err.to_string())
+    }
+}
+
+/// Options of the component types which don't have any
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NoOptions {}
+
+/// Error raised while loading or building a machine description
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    /// Index of the faulty component (if the error is related to a component)
+    pub component: Option<usize>,
+    /// Error message
+    pub message: String,
+}
+
+impl ConfigError {
+    /// Create an error related to the whole machine
+    pub fn machine(message: impl Into<String>) -> Self {
+        Self {
+            component: None,
+            message: message.into(),
+        }
+    }
+
+    /// Create an error related to a single component
+    pub fn component(id: usize, message: impl Into<String>) -> Self {
+        Self {
+            component: Some(id),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.component {
+            Some(id) => write!(f, "Component {}: {}", id, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl MachineConfig {
+    /// Parse a TOML machine description
+    pub fn from_toml(source: &str) -> Result<Self, ConfigError> {
+        toml::from_str(source).map_err(|err| {
+            ConfigError::machine(format!("Invalid TOML machine description: {}", err))
+        })
+    }
+
+    /// Parse a JSON machine description
+    pub fn from_json(source: &str) -> Result<Self, ConfigError> {
+        serde_json::from_str(source).map_err(|err| {
+            ConfigError::machine(format!("Invalid JSON machine description: {}", err))
+        })
+    }
+
+    /// Load a machine description from a `.toml` or `.json` file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+
+        let source = fs::read_to_string(path).map_err(|err| {
+            ConfigError::machine(format!(
+                "Failed to read machine description '{}': {}",
+                path.display(),
+                err
+            ))
+        })?;
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml(&source),
+            Some("json") => Self::from_json(&source),
+            _ => Err(ConfigError::machine(format!(
+                "Unknown machine description format for '{}' (expected a '.toml' or '.json' file)",
+                path.display()
+            ))),
+        }
+    }
+
+    /// Load a machine description file and build it.
+    /// Relative paths are resolved from the file's directory.
+    pub fn load_and_build(
+        path: impl AsRef<Path>,
+        registry: &ComponentRegistry,
+    ) -> Result<MotherBoard, ConfigError> {
+        let path = path.as_ref();
+        let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+
+        Self::load(path)?.build(registry, base_dir)
+    }
+
+    /// Check the machine description is valid without building it
+    pub fn validate(&self, registry: &ComponentRegistry) -> Result<(), ConfigError> {
+        if let Some(boot) = &self.boot {
+            match (&boot.lasm, &boot.binary) {
+                (Some(_), Some(_)) => {
+                    return Err(ConfigError::machine(
+                        "Boot image must be either a 'lasm' source or a 'binary' file, not both",
+                    ))
+                }
+                (None, None) => {
+                    return Err(ConfigError::machine(
+                        "Boot image requires either a 'lasm' source or a 'binary' file",
+                    ))
+                }
+                _ => {}
+            }
+        }
+
+        self.validate_components(registry, self.boot.is_some())
+    }
+
+    /// Check the components are valid, knowing if the machine has a boot image
+    fn validate_components(
+        &self,
+        registry: &ComponentRegistry,
+        has_boot_image: bool,
+    ) -> Result<(), ConfigError> {
+        if self.components.is_empty() {
+            return Err(ConfigError::machine("Machine has no component"));
+        }
+
+        let boot_components: Vec<_> = self
+            .components
+            .iter()
+            .enumerate()
+            .filter(|(_, component)| component.boot == Some(true))
+            .map(|(id, _)| id)
+            .collect();
+
+        match (has_boot_image, boot_components.as_slice()) {
+            (false, [id, ..]) => {
+                return Err(ConfigError::component(
+                    *id,
+                    "'boot' is set but the machine has no boot image",
+                ))
+            }
+            (true, []) => {
+                return Err(ConfigError::machine(
+                    "Boot image is not loaded in any component (set 'boot = true' on one of them)",
+                ))
+            }
+            (true, [_, second, ..]) => {
+                return Err(ConfigError::component(
+                    *second,
+                    "Boot image can only be loaded in a single component",
+                ))
+            }
+            _ => {}
+        }
+
+        for (id, component) in self.components.iter().enumerate() {
+            let entry = registry.get(&component.typ).ok_or_else(|| {
+                ConfigError::component(
+                    id,
+                    format!(
+                        "Unknown component type '{}' (known types: {})",
+                        component.typ,
+                        registry.types().collect::<Vec<_>>().join(", ")
+                    ),
+                )
+            })?;
+
+            for field in component.set_fields() {
+                if field != "hw_id" && field != "addr" && !entry.fields.contains(&field) {
+                    return Err(ConfigError::component(
+                        id,
+                        format!(
+                            "Field '{}' is not supported by components of type '{}'",
+                            field, component.typ
+                        ),
+                    ));
+                }
+            }
+
+            (entry.validate)(&component.options).map_err(|err| ConfigError::component(id, err))?;
+
+            if let Some(addr) = component.addr {
+                if addr % 4 != 0 {
+                    return Err(ConfigError::component(
+                        id,
+                        format!("Mapping address {:#010X} is not aligned on 4 bytes", addr),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Build the machine: create its components, map them and reset the motherboard.
+    /// Relative paths are resolved from the provided base directory.
+    pub fn build(
+        &self,
+        registry: &ComponentRegistry,
+        base_dir: impl AsRef<Path>,
+    ) -> Result<MotherBoard, ConfigError> {
+        let base_dir = base_dir.as_ref();
+
+        self.validate(registry)?;
+
+        let boot_image = self.load_boot_image(base_dir)?;
+
+        self.build_components(registry, base_dir, boot_image.as_deref())
+    }
+
+    /// Build the machine like [`MachineConfig::build`], with a boot image provided by the caller.
+    /// The `boot` section of the description is ignored.
+    pub fn build_with_boot_image(
+        &self,
+        registry: &ComponentRegistry,
+        base_dir: impl AsRef<Path>,
+        boot_image: &[u32],
+    ) -> Result<MotherBoard, ConfigError> {
+        self.validate_components(registry, true)?;
+        self.build_components(registry, base_dir.as_ref(), Some(boot_image))
+    }
+
+    /// Create, map and reset the components (the description must have been validated)
+    fn build_components(
+        &self,
+        registry: &ComponentRegistry,
+        base_dir: &Path,
+        boot_image: Option<&[u32]>,
+    ) -> Result<MotherBoard, ConfigError> {
+        let mut components: Vec<Box<dyn Bus>> = vec![];
+
+        for (id, component) in self.components.iter().enumerate() {
+            let ctx = BuildContext {
+                hw_id: component
+                    .hw_id
+                    .unwrap_or_else(|| default_hw_id(&component.typ, id)),
+                base_dir,
+                boot_image,
+            };
+
+            // Registered types were checked during validation
+            let entry = registry.get(&component.typ).unwrap();
+
+            components.push(
+                (entry.constructor)(component, &ctx)
+                    .map_err(|err| ConfigError::component(id, err))?,
+            );
+        }
+
+        let mut motherboard = MotherBoard::new(components);
+
+        motherboard.map(|mem| -> Result<(), ConfigError> {
+            let mut next_addr = 0;
+
+            for (id, component) in self.components.iter().enumerate() {
+                let addr = component.addr.unwrap_or(next_addr);
+
+                let MappingRange { end_addr, .. } = mem.map(addr, id).map_err(|err| {
+                    ConfigError::component(
+                        id,
+                        format!(
+                            "Failed to map component at address {:#010X}: {:?}",
+                            addr, err
+                        ),
+                    )
+                })?;
+
+                next_addr = end_addr.wrapping_add(4);
+            }
+
+            Ok(())
+        })?;
+
+        motherboard.reset(ResetKind::Cold);
+
+        Ok(motherboard)
+    }
+
+    /// Load the boot image, if any
+    fn load_boot_image(&self, base_dir: &Path) -> Result<Option<Vec<u32>>, ConfigError> {
+        let boot = match &self.boot {
+            Some(boot) => boot,
+            None => return Ok(None),
+        };
+
+        let read = |path: &str| -> Result<(PathBuf, Vec<u8>), ConfigError> {
+            let path = base_dir.join(path);
+
+            let content = fs::read(&path).map_err(|err| {
+                ConfigError::machine(format!(
+                    "Failed to read boot image '{}': {}",
+                    path.display(),
+                    err
+                ))
+            })?;
+
+            Ok((path, content))
+        };
+
+        if let Some(lasm) = &boot.lasm {
+            let (path, source) = read(lasm)?;
+
+            let source = String::from_utf8(source).map_err(|_| {
+                ConfigError::machine(format!(
+                    "Boot image '{}' is not valid UTF-8",
+                    path.display()
+                ))
+            })?;
+
+            let words = assemble_words(&source).map_err(|err| {
+                ConfigError::machine(format!(
+                    "Failed to assemble boot image '{}': {}",
+                    path.display(),
+                    err
+                ))
+            })?;
+
+            Ok(Some(words))
+        } else if let Some(binary) = &boot.binary {
+            let (path, bytes) = read(binary)?;
+
+            if bytes.len() % 4 != 0 {
+                return Err(ConfigError::machine(format!(
+                    "Boot image '{}' size is not a multiple of 4 bytes",
+                    path.display()
+                )));
+            }
+
+            Ok(Some(bytes_to_words(bytes)))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Derive a stable hardware identifier from a component's type and position.
+/// The FNV-1a hash function is used, so identifiers don't change between builds or Rust releases.
+fn default_hw_id(typ: &str, id: usize) -> u64 {
+    typ.bytes()
+        .chain((id as u64).to_be_bytes())
+        .fold(0xCBF2_9CE4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01B3)
+        })
+}
@@ -0,0 +1,7 @@
+//! This module allows to describe machines declaratively (in TOML or JSON) and to build them into ready-to-run motherboards.
+
+mod machine;
+mod registry;
+mod target;
+
+pub use self::{machine::*, registry::*, target::*};
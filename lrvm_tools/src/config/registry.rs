@@ -0,0 +1,94 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use lrvm::board::Bus;
+use serde::de::DeserializeOwned;
+
+use super::{ComponentConfig, ComponentOptions};
+
+/// Constructor of a component type
+pub type ComponentConstructor =
+    Box<dyn Fn(&ComponentConfig, &BuildContext) -> Result<Box<dyn Bus>, String>>;
+
+/// Validator of a component type's options
+pub type OptionsValidator = Box<dyn Fn(&ComponentOptions) -> Result<(), String>>;
+
+/// A component type registered in a [`ComponentRegistry`]
+pub struct RegisteredComponent {
+    /// Shared fields supported by this type: `size`, `boot`, and `output` or `input` if it accepts targets set from code
+    /// (`hw_id` and `addr` are always supported)
+    pub fields: &'static [&'static str],
+    /// Validator of the type-specific options
+    pub validate: OptionsValidator,
+    /// Constructor
+    pub constructor: ComponentConstructor,
+}
+
+/// Context provided to component constructors
+pub struct BuildContext<'a> {
+    /// Hardware identifier of the component
+    pub hw_id: u64,
+    /// Directory relative paths are resolved from
+    pub base_dir: &'a Path,
+    /// Boot image, if the machine has one
+    pub boot_image: Option<&'a [u32]>,
+}
+
+impl BuildContext<'_> {
+    /// Resolve a path relatively to the machine description
+    pub fn resolve(&self, path: impl AsRef<Path>) -> PathBuf {
+        self.base_dir.join(path)
+    }
+}
+
+/// Registry mapping component type names (as used in machine descriptions) to their constructor.
+///
+/// The registry is empty by default: components crates provide their own registrations
+/// (see `lrvm_aux::registry()` for the auxiliary components).
+#[derive(Default)]
+pub struct ComponentRegistry {
+    types: BTreeMap<String, RegisteredComponent>,
+}
+
+impl ComponentRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a component type, replacing any existing type with the same name.
+    /// The type-specific options are parsed into `O` (use [`NoOptions`](super::NoOptions) if there are none),
+    /// which is provided to the constructor.
+    pub fn register<O: DeserializeOwned + 'static>(
+        &mut self,
+        name: impl Into<String>,
+        fields: &'static [&'static str],
+        constructor: impl Fn(&ComponentConfig, O, &BuildContext) -> Result<Box<dyn Bus>, String>
+            + 'static,
+    ) -> &mut Self {
+        self.types.insert(
+            name.into(),
+            RegisteredComponent {
+                fields,
+                validate: Box::new(|options| options.parse::<O>().map(|_| ())),
+                constructor: Box::new(move |config, ctx| {
+                    constructor(config, config.options.parse()?, ctx)
+                }),
+            },
+        );
+
+        self
+    }
+
+    /// Get a registered component type
+    pub fn get(&self, name: &str) -> Option<&RegisteredComponent> {
+        self.types.get(name)
+    }
+
+    /// Get the name of all registered types, in alphabetical order
+    pub fn types(&self) -> impl Iterator<Item = &str> {
+        self.types.keys().map(String::as_str)
+    }
+}
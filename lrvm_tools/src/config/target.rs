@@ -0,0 +1,242 @@
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    fs::File,
+    io::{self, stderr, stdin, stdout, BufRead, BufReader, Read, Write},
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+/// Destination of a component's output, as written in machine descriptions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OutputTarget {
+    /// Standard output (`stdout`)
+    Stdout,
+    /// Standard error (`stderr`)
+    Stderr,
+    /// Discarded output (`null`)
+    Null,
+    /// File, truncated when opened (`file:<path>`)
+    File(PathBuf),
+    /// In-memory capture (only available when building machines from code)
+    Capture(OutputCapture),
+}
+
+impl OutputTarget {
+    /// Parse an output target, resolving file paths from the provided directory
+    pub fn parse(target: &str, base_dir: &Path) -> Result<Self, String> {
+        match target {
+            "stdout" => Ok(Self::Stdout),
+            "stderr" => Ok(Self::Stderr),
+            "null" => Ok(Self::Null),
+            _ => match target.strip_prefix("file:") {
+                Some(path) if !path.is_empty() => Ok(Self::File(base_dir.join(path))),
+                _ => Err(format!(
+                    "Invalid output target '{}' (expected 'stdout', 'stderr', 'null' or 'file:<path>')",
+                    target
+                )),
+            },
+        }
+    }
+
+    /// Open the target for writing
+    pub fn open(&self) -> Result<Box<dyn Write>, String> {
+        Ok(match self {
+            Self::Stdout => Box::new(stdout()),
+            Self::Stderr => Box::new(stderr()),
+            Self::Null => Box::new(io::sink()),
+            Self::File(path) => Box::new(File::create(path).map_err(|err| {
+                format!("Failed to create output file '{}': {}", path.display(), err)
+            })?),
+            Self::Capture(capture) => Box::new(capture.clone()),
+        })
+    }
+}
+
+/// Source of a component's input, as written in machine descriptions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputTarget {
+    /// Standard input (`stdin`)
+    Stdin,
+    /// File (`file:<path>`)
+    File(PathBuf),
+    /// Scripted input (only available when building machines from code)
+    Script(InputScript),
+}
+
+impl InputTarget {
+    /// Parse an input target, resolving file paths from the provided directory
+    pub fn parse(target: &str, base_dir: &Path) -> Result<Self, String> {
+        match target {
+            "stdin" => Ok(Self::Stdin),
+            _ => match target.strip_prefix("file:") {
+                Some(path) if !path.is_empty() => Ok(Self::File(base_dir.join(path))),
+                _ => Err(format!(
+                    "Invalid input target '{}' (expected 'stdin' or 'file:<path>')",
+                    target
+                )),
+            },
+        }
+    }
+
+    /// Open the target for reading
+    pub fn open(&self) -> Result<Box<dyn BufRead>, String> {
+        Ok(match self {
+            Self::Stdin => Box::new(BufReader::new(stdin())),
+            Self::File(path) => Box::new(BufReader::new(File::open(path).map_err(|err| {
+                format!("Failed to open input file '{}': {}", path.display(), err)
+            })?)),
+            Self::Script(script) => Box::new(BufReader::new(script.clone())),
+        })
+    }
+}
+
+/// In-memory output, shared between the component writing to it and the code reading it.
+/// The output can also be copied to the standard output as it is written (see [`OutputCapture::tee_stdout`]).
+#[derive(Debug, Clone, Default)]
+pub struct OutputCapture(Rc<RefCell<CapturedOutput>>);
+
+/// (Internal) Content of an [`OutputCapture`]
+#[derive(Debug, Default)]
+struct CapturedOutput {
+    bytes: Vec<u8>,
+    tee: bool,
+}
+
+impl OutputCapture {
+    /// Create an empty capture
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Copy (or stop copying) the output written from now on to the standard output
+    pub fn tee_stdout(&self, tee: bool) {
+        self.0.borrow_mut().tee = tee;
+    }
+
+    /// Get the captured bytes
+    pub fn bytes(&self) -> Vec<u8> {
+        self.0.borrow().bytes.clone()
+    }
+
+    /// Get the captured output as a string, converting invalid UTF-8 sequences lossily
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.0.borrow().bytes).into_owned()
+    }
+
+    /// Get the captured output as a string and clear the capture
+    pub fn take(&self) -> String {
+        let bytes = std::mem::take(&mut self.0.borrow_mut().bytes);
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+
+    /// Clear the capture
+    pub fn clear(&self) {
+        self.0.borrow_mut().bytes.clear();
+    }
+}
+
+impl Write for OutputCapture {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut output = self.0.borrow_mut();
+        output.bytes.extend_from_slice(buf);
+
+        if output.tee {
+            let mut stdout = stdout();
+            stdout.write_all(buf)?;
+            stdout.flush()?;
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl PartialEq for OutputCapture {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for OutputCapture {}
+
+/// Scripted input, shared between the code feeding it and the component reading from it.
+/// Once all the scripted input has been consumed, reading it behaves like an exhausted file.
+#[derive(Debug, Clone, Default)]
+pub struct InputScript(Rc<RefCell<VecDeque<u8>>>);
+
+impl InputScript {
+    /// Create an empty script
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append some input
+    pub fn push_str(&self, input: &str) {
+        self.0.borrow_mut().extend(input.bytes());
+    }
+
+    /// Append a line of input (a newline is added at its end)
+    pub fn push_line(&self, line: &str) {
+        self.push_str(line);
+        self.0.borrow_mut().push_back(b'\n');
+    }
+
+    /// Get the number of bytes that were not consumed yet
+    pub fn remaining(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    /// Consume the next line of input, with its newline (or all the remaining input if it has none).
+    /// Returns `None` if all the input was consumed.
+    pub fn pop_line(&self) -> Option<String> {
+        let mut queue = self.0.borrow_mut();
+
+        if queue.is_empty() {
+            return None;
+        }
+
+        let len = match queue.iter().position(|byte| *byte == b'\n') {
+            Some(pos) => pos + 1,
+            None => queue.len(),
+        };
+
+        let line = queue.drain(..len).collect::<Vec<_>>();
+        Some(String::from_utf8_lossy(&line).into_owned())
+    }
+}
+
+impl Read for InputScript {
+    // Never reads past a newline, so pending input stays in the script until a line is actually requested
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut queue = self.0.borrow_mut();
+        let mut len = 0;
+
+        while len < buf.len() {
+            match queue.pop_front() {
+                Some(byte) => {
+                    buf[len] = byte;
+                    len += 1;
+
+                    if byte == b'\n' {
+                        break;
+                    }
+                }
+                None => break,
+            }
+        }
+
+        Ok(len)
+    }
+}
+
+impl PartialEq for InputScript {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for InputScript {}
@@ -0,0 +1,313 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use lrvm::cpu::Cpu;
+
+use crate::lasm::SourceMap;
+
+use super::ExecStep;
+
+/// Opcode of the `IF` instruction
+const IF_OPCODE: u8 = 0x11;
+
+/// Opcode of the `IF2` instruction
+const IF2_OPCODE: u8 = 0x13;
+
+/// Outcomes of a conditional instruction (`IF`, `IFN` or `IF2`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BranchCoverage {
+    /// Number of times the condition was met (the next instruction was run)
+    pub taken: u64,
+    /// Number of times the condition was not met (the next instruction was skipped)
+    pub skipped: u64,
+}
+
+impl BranchCoverage {
+    /// Get the number of directions (taken and skipped) that were followed at least once
+    pub fn directions_hit(&self) -> usize {
+        usize::from(self.taken > 0) + usize::from(self.skipped > 0)
+    }
+}
+
+/// Coverage of a single source line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineCoverage {
+    /// Line number (starting at 1)
+    pub line: usize,
+    /// Number of times the instruction written on this line was run
+    pub hits: u64,
+    /// Outcomes of the conditional instruction written on this line, if any
+    pub branch: Option<BranchCoverage>,
+}
+
+/// Guest code coverage collector, recording the instructions run by the CPU and the outcomes of conditional instructions.
+///
+/// When a [`SourceMap`] is provided, results are mapped back to the LASM source lines
+/// and can be exported as an lcov tracefile.
+///
+/// The collector is fed through [`run_vm_with_hook`](super::run_vm_with_hook):
+///
+/// ```no_run
+/// # use lrvm_tools::{debug::*, lasm::assemble_with_map};
+/// # let mut motherboard = prepare_vm(vec![]);
+/// # let (_, map) = assemble_with_map("").unwrap();
+/// let mut coverage = Coverage::with_source_map(map);
+/// run_vm_with_hook(motherboard.cpu(), RunConfig::new(), |cpu, step| coverage.record(cpu, step));
+/// println!("{}", coverage.format_summary());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Coverage {
+    map: Option<SourceMap>,
+    hits: BTreeMap<u32, u64>,
+    branches: BTreeMap<u32, BranchCoverage>,
+}
+
+impl Coverage {
+    /// Create a new coverage collector, working on addresses only
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new coverage collector mapping its results to the lines of a source map
+    pub fn with_source_map(map: SourceMap) -> Self {
+        Self {
+            map: Some(map),
+            ..Self::default()
+        }
+    }
+
+    /// Record an instruction run by the CPU.
+    /// Cycles that didn't run an instruction (see [`ExecStep::instr`]) are ignored.
+    pub fn record(&mut self, cpu: &Cpu, step: ExecStep) {
+        let opcode = match step.instr {
+            Some(instr) => instr.to_be_bytes()[0] >> 3,
+            None => return,
+        };
+
+        *self.hits.entry(step.addr).or_insert(0) += 1;
+
+        if !(IF_OPCODE..=IF2_OPCODE).contains(&opcode) {
+            return;
+        }
+
+        // Conditional instructions either go to the next instruction or skip it.
+        // Any other address means an exception was raised.
+        let branch = self.branches.entry(step.addr).or_default();
+
+        if cpu.regs.pc == step.addr.wrapping_add(4) {
+            branch.taken += 1;
+        } else if cpu.regs.pc == step.addr.wrapping_add(8) {
+            branch.skipped += 1;
+        }
+    }
+
+    /// Get the number of times the instruction at a given address was run
+    pub fn hits(&self, addr: u32) -> u64 {
+        self.hits.get(&addr).copied().unwrap_or(0)
+    }
+
+    /// Get the addresses of all the instructions that were run at least once
+    pub fn executed_addrs(&self) -> impl Iterator<Item = u32> + '_ {
+        self.hits.keys().copied()
+    }
+
+    /// Get the outcomes of all the conditional instructions that were run, by address
+    pub fn branches(&self) -> &BTreeMap<u32, BranchCoverage> {
+        &self.branches
+    }
+
+    /// Get the coverage of each source line containing an instruction.
+    /// Returns an empty list if no source map was provided.
+    pub fn lines(&self) -> Vec<LineCoverage> {
+        let map = match &self.map {
+            Some(map) => map,
+            None => return vec![],
+        };
+
+        let mut lines: Vec<_> = map
+            .instr_lines()
+            .map(|(addr, line)| {
+                let is_conditional = map
+                    .mnemonic_of(addr)
+                    .is_some_and(|mnemonic| mnemonic.starts_with("if"));
+
+                LineCoverage {
+                    line,
+                    hits: self.hits(addr),
+                    branch: if is_conditional {
+                        Some(self.branches.get(&addr).copied().unwrap_or_default())
+                    } else {
+                        None
+                    },
+                }
+            })
+            .collect();
+
+        lines.sort_by_key(|line| line.line);
+        lines
+    }
+
+    /// Export the coverage as an lcov tracefile, for the source file at the provided path.
+    /// Returns `None` if no source map was provided.
+    pub fn to_lcov(&self, test_name: &str, source_path: &str) -> Option<String> {
+        let map = self.map.as_ref()?;
+
+        let mut out = format!("TN:{}\nSF:{}\n", test_name, source_path);
+
+        let functions: Vec<_> = map.global_labels().collect();
+
+        for func in &functions {
+            out.push_str(&format!("FN:{},{}\n", func.line, func.name));
+        }
+
+        for func in &functions {
+            out.push_str(&format!("FNDA:{},{}\n", self.hits(func.addr), func.name));
+        }
+
+        out.push_str(&format!(
+            "FNF:{}\nFNH:{}\n",
+            functions.len(),
+            functions
+                .iter()
+                .filter(|func| self.hits(func.addr) > 0)
+                .count()
+        ));
+
+        let lines = self.lines();
+        let (mut branches_found, mut branches_hit) = (0, 0);
+
+        for line in &lines {
+            if let Some(branch) = line.branch {
+                for (id, count) in [branch.taken, branch.skipped].into_iter().enumerate() {
+                    let count = if line.hits == 0 {
+                        "-".to_string()
+                    } else {
+                        count.to_string()
+                    };
+
+                    out.push_str(&format!("BRDA:{},0,{},{}\n", line.line, id, count));
+                }
+
+                branches_found += 2;
+                branches_hit += branch.directions_hit();
+            }
+        }
+
+        out.push_str(&format!("BRF:{}\nBRH:{}\n", branches_found, branches_hit));
+
+        for line in &lines {
+            out.push_str(&format!("DA:{},{}\n", line.line, line.hits));
+        }
+
+        out.push_str(&format!(
+            "LF:{}\nLH:{}\nend_of_record\n",
+            lines.len(),
+            lines.iter().filter(|line| line.hits > 0).count()
+        ));
+
+        Some(out)
+    }
+
+    /// Format a summary of the coverage, listing the lines that were never run
+    pub fn format_summary(&self) -> String {
+        let map = match &self.map {
+            Some(map) => map,
+            None => {
+                let branches_hit: usize = self
+                    .branches
+                    .values()
+                    .map(BranchCoverage::directions_hit)
+                    .sum();
+
+                return format!(
+                    "Instructions: {} executed\nBranches:     {}/{} directions taken\n",
+                    self.hits.len(),
+                    branches_hit,
+                    self.branches.len() * 2
+                );
+            }
+        };
+
+        let lines = self.lines();
+        let lines_hit = lines.iter().filter(|line| line.hits > 0).count();
+
+        let branches: Vec<_> = lines.iter().filter_map(|line| line.branch).collect();
+        let branches_hit: usize = branches.iter().map(BranchCoverage::directions_hit).sum();
+
+        let functions: Vec<_> = map.global_labels().collect();
+        let functions_hit = functions
+            .iter()
+            .filter(|func| self.hits(func.addr) > 0)
+            .count();
+
+        let mut out = format!(
+            "Lines:     {}\nBranches:  {}\nFunctions: {}\n",
+            ratio(lines_hit, lines.len()),
+            ratio(branches_hit, branches.len() * 2),
+            ratio(functions_hit, functions.len())
+        );
+
+        let missed: BTreeSet<_> = lines
+            .iter()
+            .filter(|line| line.hits == 0)
+            .map(|line| line.line)
+            .collect();
+
+        if !missed.is_empty() {
+            out.push_str(&format!("Lines never run: {}\n", format_ranges(&missed)));
+        }
+
+        let partial: Vec<_> = lines
+            .iter()
+            .filter(|line| {
+                line.hits > 0
+                    && line
+                        .branch
+                        .is_some_and(|branch| branch.directions_hit() < 2)
+            })
+            .map(|line| line.line.to_string())
+            .collect();
+
+        if !partial.is_empty() {
+            out.push_str(&format!(
+                "Branches going a single way: {}\n",
+                partial.join(", ")
+            ));
+        }
+
+        out
+    }
+}
+
+/// Format a coverage ratio
+fn ratio(hit: usize, found: usize) -> String {
+    let percent = if found == 0 {
+        100.0
+    } else {
+        hit as f64 * 100.0 / found as f64
+    };
+    format!("{}/{} ({:.2}%)", hit, found, percent)
+}
+
+/// Format a set of line numbers as a list of ranges (e.g. `3-5, 8`)
+fn format_ranges(lines: &BTreeSet<usize>) -> String {
+    let mut ranges: Vec<(usize, usize)> = vec![];
+
+    for line in lines {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == *line => *end = *line,
+            _ => ranges.push((*line, *line)),
+        }
+    }
+
+    ranges
+        .iter()
+        .map(|(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{}-{}", start, end)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -1,6 +1,8 @@
+mod coverage;
 mod exec;
 mod prepare;
+mod profile;
 mod run;
 mod run_config;
 
-pub use self::{exec::*, prepare::*, run::*, run_config::*};
+pub use self::{coverage::*, exec::*, prepare::*, profile::*, run::*, run_config::*};
@@ -1,6 +1,6 @@
 use lrvm::{
-    board::{Bus, MotherBoard},
-    mem::{ContiguousMappingResult, MappingRange},
+    board::{Bus, MotherBoard, ResetKind},
+    mem::ContiguousMappingResult,
 };
 
 /// Prepare a motherboard from a list of components.
@@ -21,28 +21,16 @@ pub fn prepare_vm(components: Vec<Box<dyn Bus>>) -> MotherBoard {
 
         for result in aux_mapping {
             println!(
-                "=> Component {:04} '{:32}': {} {} (HW ID: 0x{})",
-                result.aux_id,
-                result.aux_name,
-                if result.aux_mapping.is_ok() {
-                    "✓"
-                } else {
-                    "✗"
-                },
-                match result.aux_mapping {
-                    Ok(MappingRange {
-                        start_addr,
-                        end_addr,
-                    }) => format!("{:#010X} -> {:#010X}", start_addr, end_addr),
-                    Err(err) => format!("{:?}", err),
-                },
-                result
-                    .aux_hw_id
-                    .to_be_bytes()
-                    .iter()
-                    .map(|byte| format!("{:002X}", byte))
-                    .collect::<Vec<String>>()
-                    .join(" "),
+                "{}",
+                format_mapping_status(
+                    result.aux_id,
+                    &result.aux_name,
+                    result.aux_hw_id,
+                    result
+                        .aux_mapping
+                        .map(|range| (range.start_addr, range.end_addr))
+                        .map_err(|err| format!("{:?}", err))
+                )
             );
         }
 
@@ -55,6 +43,54 @@ pub fn prepare_vm(components: Vec<Box<dyn Bus>>) -> MotherBoard {
         }
     });
 
-    motherboard.reset();
+    motherboard.reset(ResetKind::Cold);
     motherboard
 }
+
+/// Display the mapping status of all the components of a motherboard, using the same format as [`prepare_vm`]
+pub fn print_mapping(motherboard: &mut MotherBoard) {
+    for aux_id in 0..motherboard.count() {
+        let name = motherboard.aux_name(aux_id).unwrap();
+        let metadata = motherboard.aux_metadata(aux_id).unwrap();
+        let hw_id = ((metadata[0] as u64) << 32) + metadata[1] as u64;
+
+        let mapping = motherboard.map(|mem| mem.get_mapping(aux_id).copied());
+
+        println!(
+            "{}",
+            format_mapping_status(
+                aux_id,
+                name,
+                hw_id,
+                mapping
+                    .map(|mapping| (mapping.addr, mapping.addr + mapping.size - 4))
+                    .ok_or_else(|| "Not mapped".to_string())
+            )
+        );
+    }
+}
+
+/// Format the mapping status of a single component
+fn format_mapping_status(
+    aux_id: usize,
+    name: &str,
+    hw_id: u64,
+    mapping: Result<(u32, u32), String>,
+) -> String {
+    format!(
+        "=> Component {:04} '{:32}': {} {} (HW ID: 0x{})",
+        aux_id,
+        name,
+        if mapping.is_ok() { "✓" } else { "✗" },
+        match mapping {
+            Ok((start_addr, end_addr)) => format!("{:#010X} -> {:#010X}", start_addr, end_addr),
+            Err(err) => err,
+        },
+        hw_id
+            .to_be_bytes()
+            .iter()
+            .map(|byte| format!("{:002X}", byte))
+            .collect::<Vec<String>>()
+            .join(" "),
+    )
+}
@@ -0,0 +1,409 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use lrvm::cpu::Cpu;
+
+use crate::lasm::SourceMap;
+
+use super::ExecStep;
+
+/// Mnemonic of each opcode
+static OPCODE_NAMES: [&str; 32] = [
+    "???", "cpy", "ex", "add", "sub", "mul", "div", "mod", "and", "bor", "xor", "shl", "shr",
+    "cmp", "jpr", "lsm", "itr", "if", "ifn", "if2", "lsa", "lea", "wsa", "wea", "srm", "push",
+    "pop", "call", "hwd", "cycles", "halt", "reset",
+];
+
+/// Opcode of the `CALL` instruction
+const CALL_OPCODE: u8 = 0x1B;
+
+/// Opcode of the `POP` instruction
+const POP_OPCODE: u8 = 0x1A;
+
+/// Code of the `pc` register
+const PC_REG_CODE: u8 = 0x16;
+
+/// How the profiler records the instructions run by the CPU
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileMode {
+    /// Record every instruction
+    Count,
+    /// Record one instruction every N instructions
+    Sample(u64),
+}
+
+/// A guest call frame
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+    /// Address of the called function
+    entry: u32,
+    /// Address the function will return to
+    ret_addr: u32,
+}
+
+/// Statistics of a single function, computed by the [`Profiler`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionStats {
+    /// Name of the function (its label if available, its address otherwise)
+    pub name: String,
+    /// Address of the function (`None` for the code that isn't part of any known function)
+    pub addr: Option<u32>,
+    /// Number of times the function was called
+    pub calls: u64,
+    /// Number of samples taken while running the function's own code
+    pub self_samples: u64,
+    /// Number of samples taken while running the function or one of its callees
+    pub total_samples: u64,
+}
+
+/// Guest profiler, counting the instructions run by the CPU and tracking the guest's call stack.
+///
+/// Call stacks are built from `CALL` instructions and `pop pc` (`ret`) return sequences.
+/// Functions are named after the global labels of the program's [`SourceMap`] when one is provided.
+///
+/// The profiler is fed through [`run_vm_with_hook`](super::run_vm_with_hook):
+///
+/// ```no_run
+/// # use lrvm_tools::debug::*;
+/// # let mut motherboard = prepare_vm(vec![]);
+/// let mut profiler = Profiler::new(ProfileMode::Count);
+/// run_vm_with_hook(motherboard.cpu(), RunConfig::new(), |cpu, step| profiler.record(cpu, step));
+/// println!("{}", profiler.format_report());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Profiler {
+    mode: ProfileMode,
+    map: Option<SourceMap>,
+    steps: u64,
+    samples: u64,
+    pc_hits: BTreeMap<u32, u64>,
+    opcode_hits: BTreeMap<u8, u64>,
+    frames: Vec<Frame>,
+    calls: BTreeMap<Option<u32>, u64>,
+    stacks: BTreeMap<Vec<Option<u32>>, u64>,
+}
+
+impl Profiler {
+    /// Create a new profiler
+    pub fn new(mode: ProfileMode) -> Self {
+        Self {
+            mode,
+            map: None,
+            steps: 0,
+            samples: 0,
+            pc_hits: BTreeMap::new(),
+            opcode_hits: BTreeMap::new(),
+            frames: vec![],
+            calls: BTreeMap::new(),
+            stacks: BTreeMap::new(),
+        }
+    }
+
+    /// Create a new profiler naming functions after the labels of a source map
+    pub fn with_source_map(mode: ProfileMode, map: SourceMap) -> Self {
+        let mut profiler = Self::new(mode);
+        profiler.map = Some(map);
+        profiler
+    }
+
+    /// Record an instruction run by the CPU.
+    /// Cycles that didn't run an instruction (see [`ExecStep::instr`]) are ignored.
+    pub fn record(&mut self, cpu: &Cpu, step: ExecStep) {
+        if step.instr.is_none() {
+            return;
+        }
+
+        self.steps += 1;
+
+        let sampled = match self.mode {
+            ProfileMode::Count => true,
+            ProfileMode::Sample(interval) => self.steps % interval.max(1) == 0,
+        };
+
+        if sampled {
+            self.samples += 1;
+
+            *self.pc_hits.entry(step.addr).or_insert(0) += 1;
+
+            if let Some(instr) = step.instr {
+                *self
+                    .opcode_hits
+                    .entry(instr.to_be_bytes()[0] >> 3)
+                    .or_insert(0) += 1;
+            }
+
+            let stack = self.current_stack(step.addr);
+            *self.stacks.entry(stack).or_insert(0) += 1;
+        }
+
+        self.track_calls(cpu, step);
+    }
+
+    /// Update the call stack after an instruction was run
+    fn track_calls(&mut self, cpu: &Cpu, step: ExecStep) {
+        let instr = match step.instr {
+            Some(instr) => instr.to_be_bytes(),
+            None => return,
+        };
+
+        let sv_mode = cpu.regs.smt != 0;
+
+        // Instructions that raised an exception may have switched to supervisor mode
+        if sv_mode != step.sv_mode_before {
+            return;
+        }
+
+        let sp = if sv_mode { cpu.regs.ssp } else { cpu.regs.usp };
+
+        match instr[0] >> 3 {
+            CALL_OPCODE if sp == step.sp_before.wrapping_sub(4) => {
+                let entry = cpu.regs.pc;
+
+                self.frames.push(Frame {
+                    entry,
+                    ret_addr: step.addr.wrapping_add(4),
+                });
+
+                *self.calls.entry(self.function_of(entry)).or_insert(0) += 1;
+            }
+
+            POP_OPCODE if instr[1] == PC_REG_CODE && sp == step.sp_before.wrapping_add(4) => {
+                if let Some(index) = self
+                    .frames
+                    .iter()
+                    .rposition(|frame| frame.ret_addr == cpu.regs.pc)
+                {
+                    self.frames.truncate(index);
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    /// Get the function an address belongs to.
+    /// Without a source map, the address is assumed to be a function entry.
+    fn function_of(&self, addr: u32) -> Option<u32> {
+        match &self.map {
+            Some(map) => map.function_of(addr).map(|label| label.addr),
+            None => Some(addr),
+        }
+    }
+
+    /// Get the call stack (from the outermost to the innermost function) for an instruction address
+    fn current_stack(&self, addr: u32) -> Vec<Option<u32>> {
+        let mut stack = vec![];
+
+        match self.frames.first() {
+            Some(first) if self.map.is_some() => {
+                stack.push(self.function_of(first.ret_addr.wrapping_sub(4)))
+            }
+            _ if self.map.is_some() => {}
+            _ => stack.push(None),
+        }
+
+        stack.extend(
+            self.frames
+                .iter()
+                .map(|frame| self.function_of(frame.entry)),
+        );
+
+        // Code may jump to another function without calling it
+        if self.map.is_some() {
+            let leaf = self.function_of(addr);
+
+            if stack.last() != Some(&leaf) {
+                stack.push(leaf);
+            }
+        }
+
+        stack
+    }
+
+    /// Get the name of a function
+    fn function_name(&self, func: Option<u32>) -> String {
+        let func = match func {
+            Some(func) => func,
+            None => return "[root]".to_string(),
+        };
+
+        self.map
+            .as_ref()
+            .and_then(|map| map.global_labels().find(|label| label.addr == func))
+            .map(|label| label.name.clone())
+            .unwrap_or_else(|| format!("{:#010X}", func))
+    }
+
+    /// Get the number of instructions the profiler saw
+    pub fn instructions(&self) -> u64 {
+        self.steps
+    }
+
+    /// Get the number of samples the profiler took
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    /// Get the current depth of the guest's call stack
+    pub fn call_depth(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Get the number of samples for each instruction address, from the most to the least sampled one
+    pub fn hot_spots(&self) -> Vec<(u32, u64)> {
+        let mut hits: Vec<_> = self
+            .pc_hits
+            .iter()
+            .map(|(addr, count)| (*addr, *count))
+            .collect();
+        hits.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        hits
+    }
+
+    /// Get the number of samples for each opcode, from the most to the least sampled one
+    pub fn opcode_totals(&self) -> Vec<(u8, &'static str, u64)> {
+        let mut totals: Vec<_> = self
+            .opcode_hits
+            .iter()
+            .map(|(opcode, count)| (*opcode, OPCODE_NAMES[usize::from(*opcode)], *count))
+            .collect();
+        totals.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(&b.0)));
+        totals
+    }
+
+    /// Get the statistics of each function, from the one with the most self samples to the one with the fewest
+    pub fn functions(&self) -> Vec<FunctionStats> {
+        let mut stats = BTreeMap::new();
+
+        for (func, calls) in &self.calls {
+            self.function_stats(&mut stats, *func).calls = *calls;
+        }
+
+        for (stack, count) in &self.stacks {
+            for func in stack.iter().collect::<BTreeSet<_>>() {
+                self.function_stats(&mut stats, *func).total_samples += count;
+            }
+
+            if let Some(leaf) = stack.last() {
+                self.function_stats(&mut stats, *leaf).self_samples += count;
+            }
+        }
+
+        let mut stats: Vec<_> = stats.into_values().collect();
+        stats.sort_by(|a, b| {
+            b.self_samples
+                .cmp(&a.self_samples)
+                .then(b.total_samples.cmp(&a.total_samples))
+                .then(a.addr.cmp(&b.addr))
+        });
+        stats
+    }
+
+    /// Get the statistics entry of a function, creating it if required
+    fn function_stats<'a>(
+        &self,
+        stats: &'a mut BTreeMap<Option<u32>, FunctionStats>,
+        func: Option<u32>,
+    ) -> &'a mut FunctionStats {
+        stats.entry(func).or_insert_with(|| FunctionStats {
+            name: self.function_name(func),
+            addr: func,
+            calls: 0,
+            self_samples: 0,
+            total_samples: 0,
+        })
+    }
+
+    /// Format a per-function report, followed by the most sampled instructions and opcodes
+    pub fn format_report(&self) -> String {
+        let mut out = format!(
+            "Profiled {} instructions ({} samples)\n\n{:>10} {:>10} {:>7} {:>10}  Function\n",
+            self.steps, self.samples, "Self", "Total", "Self %", "Calls"
+        );
+
+        for func in self.functions() {
+            out.push_str(&format!(
+                "{:>10} {:>10} {:>6.2}% {:>10}  {}\n",
+                func.self_samples,
+                func.total_samples,
+                percent(func.self_samples, self.samples),
+                func.calls,
+                func.name
+            ));
+        }
+
+        out.push_str(&format!("\n{:>10} {:>7}  Address\n", "Samples", "%"));
+
+        for (addr, count) in self.hot_spots().into_iter().take(10) {
+            out.push_str(&format!(
+                "{:>10} {:>6.2}%  {:#010X}{}\n",
+                count,
+                percent(count, self.samples),
+                addr,
+                self.locate(addr)
+            ));
+        }
+
+        out.push_str(&format!("\n{:>10} {:>7}  Opcode\n", "Samples", "%"));
+
+        for (_, name, count) in self.opcode_totals() {
+            out.push_str(&format!(
+                "{:>10} {:>6.2}%  {}\n",
+                count,
+                percent(count, self.samples),
+                name
+            ));
+        }
+
+        out
+    }
+
+    /// Describe the location of an address in the source code, if a source map is available
+    fn locate(&self, addr: u32) -> String {
+        let map = match &self.map {
+            Some(map) => map,
+            None => return String::new(),
+        };
+
+        let mut out = String::new();
+
+        if let Some(func) = map.function_of(addr) {
+            out.push_str(&format!(" ({}+{:#X}", func.name, addr - func.addr));
+
+            if let Some(line) = map.line_of(addr) {
+                out.push_str(&format!(", line {}", line));
+            }
+
+            out.push(')');
+        }
+
+        out
+    }
+
+    /// Export the sampled call stacks in the folded-stack format consumed by flamegraph tools
+    /// (one `outer;inner;leaf <samples>` line per distinct stack)
+    pub fn folded_stacks(&self) -> String {
+        let mut lines: Vec<_> = self
+            .stacks
+            .iter()
+            .map(|(stack, count)| {
+                let names: Vec<_> = stack.iter().map(|func| self.function_name(*func)).collect();
+                format!("{} {}", names.join(";"), count)
+            })
+            .collect();
+
+        lines.sort();
+
+        let mut out = lines.join("\n");
+        out.push('\n');
+        out
+    }
+}
+
+/// Compute a percentage, handling empty totals
+fn percent(count: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 * 100.0 / total as f64
+    }
+}
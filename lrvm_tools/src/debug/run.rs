@@ -1,6 +1,6 @@
 use std::fmt;
 
-use lrvm::cpu::Cpu;
+use lrvm::{board::ShutdownReason, cpu::Cpu};
 
 use crate::exceptions::NativeException;
 
@@ -15,6 +15,28 @@ pub struct StoppedState {
     pub addr: u32,
     /// If the VM was stopped due to an exception, contains the faulty exception
     pub ex: Option<ExWithMode>,
+    /// If a component shut the machine down, contains the reason it provided
+    pub shutdown: Option<ShutdownReason>,
+}
+
+impl StoppedState {
+    /// Get the exit code the guest requested by shutting the machine down, if it did, as a process exit code:
+    /// the provided code for an exit, `0` for a passed test suite, and the failure code for a failed one
+    /// (`1` if it is `0`, as a failure must not look like a success).
+    /// Codes are truncated to their weakest byte, which is the only one processes can return on most platforms ;
+    /// nonzero codes whose weakest byte is `0` become `1` so they don't look like a success either.
+    pub fn guest_exit_code(&self) -> Option<u8> {
+        // Truncate a code, keeping it nonzero
+        let failure = |code: u32| match code as u8 {
+            0 => 1,
+            code => code,
+        };
+
+        self.shutdown.map(|reason| match reason {
+            ShutdownReason::Exit(0) | ShutdownReason::TestPassed => 0,
+            ShutdownReason::Exit(code) | ShutdownReason::TestFailed(code) => failure(code),
+        })
+    }
 }
 
 /// Native exception, with mode
@@ -31,14 +53,41 @@ pub struct ExWithMode {
     pub associated: u16,
 }
 
+/// Cycle run by the CPU, provided to the hook of [`run_vm_with_hook`]
+#[derive(Debug, Clone, Copy)]
+pub struct ExecStep {
+    /// Value of `pc` when the cycle started, which is the address the instruction was fetched from
+    pub addr: u32,
+    /// The instruction word (`None` if the cycle didn't run an instruction, because the fetch failed or because the cycle
+    /// was replaced by a hardware interruption or a component's request)
+    pub instr: Option<u32>,
+    /// Value of the stack pointer (`ssp` in supervisor mode, `usp` in userland mode) before the instruction ran
+    pub sp_before: u32,
+    /// Was the CPU in supervisor mode before the instruction ran?
+    pub sv_mode_before: bool,
+}
+
 /// Run a virtual machine until the CPU halt, eventually encounters an exception or reaches a given number of cycles.
 pub fn run_vm(cpu: &mut Cpu, config: RunConfig) -> StoppedState {
+    run_vm_with_hook(cpu, config, |_, _| {})
+}
+
+/// Run a virtual machine like [`run_vm`], calling a hook after each cycle run by the CPU,
+/// including the cycles that didn't run an instruction (see [`ExecStep::instr`]).
+pub fn run_vm_with_hook(
+    cpu: &mut Cpu,
+    config: RunConfig,
+    mut hook: impl FnMut(&Cpu, ExecStep),
+) -> StoppedState {
     // If the VM is stopped because of an exception, it will be put in here
     let mut stop_ex = None;
 
     // Address the CPU was at when the VM was stopped
     let mut was_at = cpu.regs.pc;
 
+    // `et` keeps the last exception until another one occurs, so remember if it was set by a hardware interruption
+    let mut itr_et = None;
+
     // Run the VM until it halts
     while !cpu.halted() {
         // Ensure cycles limit isn't exceeded yet
@@ -59,11 +108,33 @@ pub fn run_vm(cpu: &mut Cpu, config: RunConfig) -> StoppedState {
             );
         }
 
+        let sv_mode_before = cpu.regs.smt != 0;
+        let sp_before = if sv_mode_before {
+            cpu.regs.ssp
+        } else {
+            cpu.regs.usp
+        };
+
         // Run the next instruction
-        cpu.next();
+        let interrupted = cpu.next();
+
+        hook(
+            cpu,
+            ExecStep {
+                addr: was_at,
+                instr: cpu.last_instr(),
+                sp_before,
+                sv_mode_before,
+            },
+        );
+
+        // Hardware interruptions are expected to happen, so they are not reported as exceptions
+        if interrupted {
+            itr_et = Some(cpu.regs.et);
+        }
 
         // Check if an exception occurred
-        if cpu.regs.et != 0 {
+        if cpu.regs.et != 0 && itr_et != Some(cpu.regs.et) {
             let exception_bytes = cpu.regs.et.to_be_bytes();
 
             // Complete the exception with the mode it occurred in
@@ -93,6 +164,7 @@ pub fn run_vm(cpu: &mut Cpu, config: RunConfig) -> StoppedState {
         cycles: cpu.cycles(),
         addr: was_at,
         ex: stop_ex,
+        shutdown: cpu.shutdown_reason(),
     };
 
     if config.print_finish {
@@ -121,6 +193,19 @@ pub fn prettify_stop(state: &StoppedState) -> String {
         state.cycles, state.addr
     );
 
+    match state.shutdown {
+        Some(ShutdownReason::Exit(code)) => output.push_str(&format!(
+            " because the machine was shut down with exit code {}",
+            code
+        )),
+        Some(ShutdownReason::TestPassed) => output.push_str(" because the test suite passed"),
+        Some(ShutdownReason::TestFailed(code)) => output.push_str(&format!(
+            " because the test suite failed with code {}",
+            code
+        )),
+        None => {}
+    }
+
     if let Some(ex) = &state.ex {
         output.push_str(&format!(
             " because of exception in {} mode: {}",
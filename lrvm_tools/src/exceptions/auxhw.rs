@@ -19,6 +19,9 @@ pub enum AuxHwException {
     /// An unsupported operation was requested.
     UnsupportedOperation,
 
+    /// An input was requested but the component's input source is exhausted.
+    EndOfInput,
+
     /// A physical read error occurred.
     /// If none other exception code matches the type of error you want to raise, use this one as a fallback.
     GenericPhysicalReadError,
@@ -26,12 +29,40 @@ pub enum AuxHwException {
     /// Tried to read a non-readable address of the component.
     MemoryNotReadable,
 
+    /// Tried to read a word that was never written (only raised by components detecting uninitialized reads).
+    UninitializedRead,
+
     /// A physical write error occurred.
     /// If none other exception code matches the type of error you want to raise, use this one as a fallback.
     GenericPhysicalWriteError,
 
     /// Tried to write a non-writable address of the component.
     MemoryNotWritable,
+
+    /// Writing required allocating host memory beyond the component's resident memory cap.
+    ResidentCapExceeded,
+
+    /// A host I/O operation failed.
+    /// If none other filesystem exception code matches the type of error you want to raise, use this one as a fallback.
+    HostIoError,
+
+    /// The requested file or directory does not exist.
+    FileNotFound,
+
+    /// The host denied access to a file or directory, or the component is read-only.
+    PermissionDenied,
+
+    /// The requested path is invalid or leads outside the directory the component is restricted to.
+    PathOutsideSandbox,
+
+    /// The provided handle does not refer to an open file.
+    InvalidHandle,
+
+    /// No more files can be opened until one is closed.
+    TooManyOpenFiles,
+
+    /// The operation requires a file but got a directory, or the opposite.
+    WrongFileType,
 }
 
 impl AuxHwException {
@@ -54,12 +85,23 @@ impl AuxHwException {
 
             0x10 => Ok(Self::UnknownOperation(data_or_err?)),
             0x11 => Ok(Self::UnsupportedOperation),
+            0x12 => Ok(Self::EndOfInput),
 
             0x20 => Ok(Self::GenericPhysicalReadError),
             0x21 => Ok(Self::MemoryNotReadable),
+            0x22 => Ok(Self::UninitializedRead),
 
             0x30 => Ok(Self::GenericPhysicalWriteError),
             0x31 => Ok(Self::MemoryNotWritable),
+            0x32 => Ok(Self::ResidentCapExceeded),
+
+            0x40 => Ok(Self::HostIoError),
+            0x41 => Ok(Self::FileNotFound),
+            0x42 => Ok(Self::PermissionDenied),
+            0x43 => Ok(Self::PathOutsideSandbox),
+            0x44 => Ok(Self::InvalidHandle),
+            0x45 => Ok(Self::TooManyOpenFiles),
+            0x46 => Ok(Self::WrongFileType),
 
             _ => Err(()),
         }
@@ -74,12 +116,23 @@ impl AuxHwException {
 
             Self::UnknownOperation(_) => 0x10,
             Self::UnsupportedOperation => 0x11,
+            Self::EndOfInput => 0x12,
 
             Self::GenericPhysicalReadError => 0x20,
             Self::MemoryNotReadable => 0x21,
+            Self::UninitializedRead => 0x22,
 
             Self::GenericPhysicalWriteError => 0x30,
             Self::MemoryNotWritable => 0x31,
+            Self::ResidentCapExceeded => 0x32,
+
+            Self::HostIoError => 0x40,
+            Self::FileNotFound => 0x41,
+            Self::PermissionDenied => 0x42,
+            Self::PathOutsideSandbox => 0x43,
+            Self::InvalidHandle => 0x44,
+            Self::TooManyOpenFiles => 0x45,
+            Self::WrongFileType => 0x46,
         }
     }
 
@@ -92,12 +145,23 @@ impl AuxHwException {
 
             Self::UnknownOperation(op) => Some(*op),
             Self::UnsupportedOperation => None,
+            Self::EndOfInput => None,
 
             Self::GenericPhysicalReadError => None,
             Self::MemoryNotReadable => None,
+            Self::UninitializedRead => None,
 
             Self::GenericPhysicalWriteError => None,
             Self::MemoryNotWritable => None,
+            Self::ResidentCapExceeded => None,
+
+            Self::HostIoError => None,
+            Self::FileNotFound => None,
+            Self::PermissionDenied => None,
+            Self::PathOutsideSandbox => None,
+            Self::InvalidHandle => None,
+            Self::TooManyOpenFiles => None,
+            Self::WrongFileType => None,
         }
     }
 
@@ -125,12 +189,23 @@ impl fmt::Display for AuxHwException {
 
                 Self::UnknownOperation(op) => format!("Unknown operation {:#004X}", op),
                 Self::UnsupportedOperation => "Unsupported operation".to_string(),
+                Self::EndOfInput => "End of input".to_string(),
 
                 Self::GenericPhysicalReadError => "Generic physical read error".to_string(),
                 Self::MemoryNotReadable => "This memory address is not readable".to_string(),
+                Self::UninitializedRead => "Read of a never-written word".to_string(),
 
                 Self::GenericPhysicalWriteError => "Generic physical write error".to_string(),
                 Self::MemoryNotWritable => "This memory address is not writable".to_string(),
+                Self::ResidentCapExceeded => "Resident memory cap exceeded".to_string(),
+
+                Self::HostIoError => "Host I/O error".to_string(),
+                Self::FileNotFound => "File not found".to_string(),
+                Self::PermissionDenied => "Permission denied".to_string(),
+                Self::PathOutsideSandbox => "Path is invalid or outside the sandbox".to_string(),
+                Self::InvalidHandle => "Invalid file handle".to_string(),
+                Self::TooManyOpenFiles => "Too many open files".to_string(),
+                Self::WrongFileType => "Wrong file type".to_string(),
             }
         )
     }
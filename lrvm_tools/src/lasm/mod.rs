@@ -2,7 +2,7 @@
 //! This module allows to assemble LASM source code through the [CustomAsm](https://github.com/hlorenzi/customasm) library.
 
 use customasm::{
-    asm::{self, AssemblyOptions},
+    asm::{self, AssemblyOptions, AssemblyResult},
     diagn::Report,
     util::FileServerMock,
 };
@@ -12,11 +12,32 @@ use crate::{
     bytes::{bytes_to_words, words_to_bytes},
 };
 
+mod source_map;
+pub use self::source_map::*;
+
 static CUSTOMASM_HEADER: &str = include_str!("customasm.def");
 
 /// Assemble a LASM source code to machine code.
 /// Returns an error message in case of error.
 pub fn assemble(source: &str) -> Result<Vec<u8>, String> {
+    run_assembler(source).map(|(assembly, _)| assembly.output.unwrap().format_binary())
+}
+
+/// Assemble a LASM source code to machine code, and build its source map from the assembler's symbols.
+/// Returns an error message in case of error.
+pub fn assemble_with_map(source: &str) -> Result<(Vec<u8>, SourceMap), String> {
+    let (assembly, fileserver) = run_assembler(source)?;
+
+    // The source file starts with the header's inclusion
+    let map = SourceMap::from_assembly(&assembly, &fileserver, "src.lasm", 1)?;
+
+    Ok((assembly.output.unwrap().format_binary(), map))
+}
+
+/// (Internal) Run the assembler on a LASM source code.
+/// Returns the assembly (which is guaranteed to have an output) with the file server holding the sources,
+/// or an error message in case of error.
+fn run_assembler(source: &str) -> Result<(AssemblyResult, FileServerMock), String> {
     let mut src = String::from("#include \"header.lasm\"");
     src.push('\n');
     src.push_str(source);
@@ -30,14 +51,12 @@ pub fn assemble(source: &str) -> Result<Vec<u8>, String> {
 
     let assembly = asm::assemble(&mut report, &opts, &mut fileserver, &["src.lasm"]);
 
-    match assembly.output {
-        Some(output) if !report.has_errors() && !assembly.error => Ok(output.format_binary()),
-
-        Some(_) | None => {
-            let mut err = vec![];
-            report.print_all(&mut err, &fileserver, false);
-            Err(String::from_utf8_lossy(&err).into_owned())
-        }
+    if assembly.output.is_some() && !report.has_errors() && !assembly.error {
+        Ok((assembly, fileserver))
+    } else {
+        let mut err = vec![];
+        report.print_all(&mut err, &fileserver, false);
+        Err(String::from_utf8_lossy(&err).into_owned())
     }
 }
 
@@ -0,0 +1,190 @@
+use std::collections::BTreeMap;
+
+use customasm::{
+    asm::{AssemblyResult, AstAny, AstSymbolKind},
+    expr::Value,
+    util::{BigInt, FileServer, FileServerMock},
+};
+
+/// A label declared in a LASM source code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Label {
+    /// Full name of the label (local labels are prefixed by their parent, e.g. `main.loop`)
+    pub name: String,
+    /// Address the label points to
+    pub addr: u32,
+    /// Is this a local label (declared with a leading dot)?
+    pub local: bool,
+    /// Line the label is declared on (starting at 1)
+    pub line: usize,
+}
+
+/// An instruction declared in a LASM source code
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SourceInstr {
+    /// Line the instruction is written on (starting at 1)
+    line: usize,
+    /// Mnemonic of the instruction, in lowercase
+    mnemonic: String,
+}
+
+/// Map between a LASM source code and the program it assembles to, obtained with
+/// [`assemble_with_map`](super::assemble_with_map).
+///
+/// Label addresses come from the assembler's symbol table, and instruction addresses from the source spans
+/// the assembler attached to its output, so the map always matches the assembled program.
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    labels: Vec<Label>,
+    instrs: BTreeMap<u32, SourceInstr>,
+    size: u32,
+}
+
+impl SourceMap {
+    /// (Internal) Build the source map of an assembled program.
+    /// `file` is the name of the source file in the file server, and `skipped_lines` the number of lines the assembler
+    /// sees before the source code (e.g. the header's `#include`).
+    pub(super) fn from_assembly(
+        assembly: &AssemblyResult,
+        fileserver: &FileServerMock,
+        file: &str,
+        skipped_lines: usize,
+    ) -> Result<Self, String> {
+        let (ast, defs, output) = match (&assembly.ast, &assembly.defs, &assembly.output) {
+            (Some(ast), Some(defs), Some(output)) => (ast, defs, output),
+            _ => return Err("Assembler did not provide symbols for the program".to_string()),
+        };
+
+        let file_handle = fileserver.get_handle_unwrap(file);
+        let text = fileserver.get_str_unwrap(file_handle);
+
+        // Get the source line and text a span of the source file points to
+        let locate = |span: &customasm::diagn::Span| -> Option<(usize, &str)> {
+            if span.file_handle != file_handle {
+                return None;
+            }
+
+            let (start, end) = span.location()?;
+            let line = text[..start].matches('\n').count() + 1;
+
+            Some((line.checked_sub(skipped_lines)?, text[start..end].trim()))
+        };
+
+        let mut map = Self::default();
+        let mut parents: Vec<String> = vec![];
+        let mut instr_spans = BTreeMap::new();
+
+        for node in &ast.nodes {
+            let symbol = match node {
+                AstAny::Symbol(symbol) if matches!(symbol.kind, AstSymbolKind::Label) => symbol,
+
+                AstAny::Instruction(instr) => {
+                    if let Some((line, stmt)) = locate(&instr.span) {
+                        instr_spans.insert(instr.span.location(), (line, stmt));
+                    }
+
+                    continue;
+                }
+
+                _ => continue,
+            };
+
+            let line = match locate(&symbol.decl_span) {
+                Some((line, _)) => line,
+                None => continue,
+            };
+
+            let addr = symbol
+                .item_ref
+                .map(|item_ref| &defs.symbols.get(item_ref).value)
+                .and_then(|value| match value {
+                    Value::Integer(addr) => to_u32(addr),
+                    _ => None,
+                })
+                .ok_or_else(|| format!("Line {}: label '{}' has no address", line, symbol.name))?;
+
+            parents.truncate(symbol.hierarchy_level);
+            parents.push(symbol.name.clone());
+
+            map.labels.push(Label {
+                name: parents.join("."),
+                addr,
+                local: symbol.hierarchy_level > 0,
+                line,
+            });
+        }
+
+        // Only keep the output of instructions, not the one of data directives
+        for span in &output.spans {
+            let (line, stmt) = match instr_spans.get(&span.span.location()) {
+                Some(instr) if span.size > 0 => *instr,
+                _ => continue,
+            };
+
+            let addr = to_u32(&span.addr).ok_or_else(|| {
+                format!("Line {}: address is out of the 32-bit address space", line)
+            })?;
+
+            let mnemonic = stmt.split_whitespace().next().unwrap_or_default();
+
+            map.instrs.insert(
+                addr,
+                SourceInstr {
+                    line,
+                    mnemonic: mnemonic.to_lowercase(),
+                },
+            );
+        }
+
+        map.size = output.format_binary().len() as u32;
+
+        Ok(map)
+    }
+
+    /// Get all labels, in declaration order
+    pub fn labels(&self) -> &[Label] {
+        &self.labels
+    }
+
+    /// Get the global labels (functions), in declaration order
+    pub fn global_labels(&self) -> impl Iterator<Item = &Label> {
+        self.labels.iter().filter(|label| !label.local)
+    }
+
+    /// Get a label from its full name
+    pub fn label(&self, name: &str) -> Option<&Label> {
+        self.labels.iter().find(|label| label.name == name)
+    }
+
+    /// Get the global label an address belongs to (the closest global label placed before or at this address)
+    pub fn function_of(&self, addr: u32) -> Option<&Label> {
+        self.global_labels()
+            .filter(|label| label.addr <= addr)
+            .max_by_key(|label| label.addr)
+    }
+
+    /// Get the source line of the instruction at a given address
+    pub fn line_of(&self, addr: u32) -> Option<usize> {
+        self.instrs.get(&addr).map(|instr| instr.line)
+    }
+
+    /// Get the mnemonic (in lowercase) of the instruction at a given address
+    pub fn mnemonic_of(&self, addr: u32) -> Option<&str> {
+        self.instrs.get(&addr).map(|instr| instr.mnemonic.as_str())
+    }
+
+    /// Get the address and source line of every instruction in the program
+    pub fn instr_lines(&self) -> impl Iterator<Item = (u32, usize)> + '_ {
+        self.instrs.iter().map(|(addr, instr)| (*addr, instr.line))
+    }
+
+    /// Get the size of the program, in bytes
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+}
+
+/// (Internal) Convert an assembler integer to an address
+fn to_u32(value: &BigInt) -> Option<u32> {
+    value.checked_into::<u32>()
+}
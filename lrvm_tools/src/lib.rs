@@ -11,11 +11,13 @@ pub use lrvm;
 
 pub mod asm;
 pub mod bytes;
+pub mod config;
 pub mod debug;
 pub mod exceptions;
 pub mod ids;
 pub mod lasm;
 pub mod metadata;
+pub mod presets;
 
 #[cfg(test)]
 mod tests;
@@ -6,10 +6,15 @@ use super::types::*;
 pub enum DeviceCategory {
     Debug(DebugType),
     Clock(ClockType),
+    Power(PowerType),
     Display(DisplayType),
+    Audio(AudioType),
+    Entropy(EntropyType),
     Keyboard(KeyboardType),
     Memory(MemoryType),
     Storage(StorageType),
+    Serial(SerialType),
+    Network(NetworkType),
     PlatformSpecific(u32),
     Uncategorized,
 }
@@ -26,10 +31,15 @@ impl DeviceCategory {
         match cat {
             0x0000_0100 => Ok(Self::Debug(DebugType::decode(typ)?)),
             0x0000_1000 => Ok(Self::Clock(ClockType::decode(typ)?)),
+            0x0000_2000 => Ok(Self::Power(PowerType::decode(typ)?)),
             0x0001_1000 => Ok(Self::Display(DisplayType::decode(typ)?)),
+            0x0001_2000 => Ok(Self::Audio(AudioType::decode(typ)?)),
+            0x0001_3000 => Ok(Self::Entropy(EntropyType::decode(typ)?)),
             0x0001_6000 => Ok(Self::Keyboard(KeyboardType::decode(typ)?)),
             0x0002_1000 => Ok(Self::Memory(MemoryType::decode(typ)?)),
             0x0002_2000 => Ok(Self::Storage(StorageType::decode(typ)?)),
+            0x0003_1000 => Ok(Self::Serial(SerialType::decode(typ)?)),
+            0x0003_2000 => Ok(Self::Network(NetworkType::decode(typ)?)),
             0xEEEE_EEEE => Ok(Self::PlatformSpecific(typ)),
             0xFFFF_FFFF => Ok(Self::Uncategorized),
 
@@ -41,10 +51,15 @@ impl DeviceCategory {
         match self {
             Self::Debug(_) => 0x0000_0100,
             Self::Clock(_) => 0x0000_1000,
+            Self::Power(_) => 0x0000_2000,
             Self::Display(_) => 0x0001_1000,
+            Self::Audio(_) => 0x0001_2000,
+            Self::Entropy(_) => 0x0001_3000,
             Self::Keyboard(_) => 0x0001_6000,
             Self::Memory(_) => 0x0002_1000,
             Self::Storage(_) => 0x0002_2000,
+            Self::Serial(_) => 0x0003_1000,
+            Self::Network(_) => 0x0003_2000,
             Self::PlatformSpecific(_) => 0xEEEE_EEEE,
             Self::Uncategorized => 0xFFFF_FFFF,
         }
@@ -54,10 +69,15 @@ impl DeviceCategory {
         match self {
             Self::Debug(t) => t.code(),
             Self::Clock(t) => t.code(),
+            Self::Power(t) => t.code(),
             Self::Display(t) => t.code(),
+            Self::Audio(t) => t.code(),
+            Self::Entropy(t) => t.code(),
             Self::Keyboard(t) => t.code(),
             Self::Memory(t) => t.code(),
             Self::Storage(t) => t.code(),
+            Self::Serial(t) => t.code(),
+            Self::Network(t) => t.code(),
             Self::PlatformSpecific(typ) => typ,
             Self::Uncategorized => 0x0000_0000,
         }
@@ -76,10 +96,15 @@ impl fmt::Display for DeviceCategory {
             match self {
                 Self::Debug(d) => format!("Debug:{}", d),
                 Self::Clock(c) => format!("Clock:{}", c),
+                Self::Power(p) => format!("Power:{}", p),
                 Self::Display(d) => format!("Display:{}", d),
+                Self::Audio(a) => format!("Audio:{}", a),
+                Self::Entropy(e) => format!("Entropy:{}", e),
                 Self::Keyboard(k) => format!("Keyboard:{}", k),
                 Self::Memory(m) => format!("Memory:{}", m),
                 Self::Storage(s) => format!("Storage:{}", s),
+                Self::Serial(s) => format!("Serial:{}", s),
+                Self::Network(n) => format!("Network:{}", n),
                 Self::PlatformSpecific(code) => format!("PlatformSpecific:(Code={:#010X})", code),
                 Self::Uncategorized => "Uncategorized".to_string(),
             }
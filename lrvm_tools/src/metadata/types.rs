@@ -53,31 +53,59 @@ macro_rules! impl_device_type {
     };
 }
 
+impl_device_type!(Audio, as AudioType => {
+    Pcm => 0x0000_0100
+});
+
 impl_device_type!(Debug, as DebugType => {
     Basic => 0x0000_0100
 });
 
 impl_device_type!(Clock, as ClockType => {
-    Realtime => 0x0000_0001
+    Realtime      => 0x0000_0001,
+    IntervalTimer => 0x0000_0010,
+    Watchdog      => 0x0000_0100
+});
+
+impl_device_type!(Power, as PowerType => {
+    Controller => 0x0000_0100
 });
 
 impl_device_type!(Display, as DisplayType => {
-    Number    => 0x0000_0001,
-    Character => 0x0000_0010,
-    Buffered  => 0x0000_0100
+    Number      => 0x0000_0001,
+    Character   => 0x0000_0010,
+    Buffered    => 0x0000_0100,
+    Framebuffer => 0x0000_1000,
+    Text        => 0x0001_0000
+});
+
+impl_device_type!(Entropy, as EntropyType => {
+    Rng => 0x0000_0100
 });
 
 impl_device_type!(Keyboard, as KeyboardType => {
     ReadCharSynchronous => 0x0000_0100,
-    ReadLineSynchronous => 0x0000_1000
+    ReadLineSynchronous => 0x0000_1000,
+    Asynchronous        => 0x0001_0000
 });
 
 impl_device_type!(Memory, as MemoryType => {
     Ram => 0x0000_0100
 });
 
+impl_device_type!(Network, as NetworkType => {
+    Nic => 0x0000_0100
+});
+
+impl_device_type!(Serial, as SerialType => {
+    Uart => 0x0000_0100
+});
+
 impl_device_type!(Storage, as StorageType => {
-    Readonly   => 0x0000_0100,
-    Flash      => 0x0000_0011,
-    Persistent => 0x0000_0021
+    Readonly    => 0x0000_0100,
+    Flash       => 0x0000_0011,
+    Persistent  => 0x0000_0021,
+    DeviceTable => 0x0000_0200,
+    Block       => 0x0000_1000,
+    HostFs      => 0x0000_2000
 });
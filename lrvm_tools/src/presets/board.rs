@@ -0,0 +1,84 @@
+use lrvm::{
+    board::{MotherBoard, ResetKind},
+    cpu::Cpu,
+};
+
+use crate::config::{InputScript, OutputCapture};
+
+use super::{Preset, PresetRegion};
+
+/// A board built from a [`Preset`], giving access to its display output and keyboard input
+pub struct PresetBoard {
+    preset: Preset,
+    motherboard: MotherBoard,
+    display: OutputCapture,
+    keyboard: Option<InputScript>,
+    debug: Option<OutputCapture>,
+}
+
+impl PresetBoard {
+    pub(super) fn new(
+        preset: Preset,
+        motherboard: MotherBoard,
+        display: OutputCapture,
+        keyboard: Option<InputScript>,
+        debug: Option<OutputCapture>,
+    ) -> Self {
+        Self {
+            preset,
+            motherboard,
+            display,
+            keyboard,
+            debug,
+        }
+    }
+
+    /// Get the preset this board was built from
+    pub fn preset(&self) -> Preset {
+        self.preset
+    }
+
+    /// Get the board's memory map
+    pub fn memory_map(&self) -> &'static [PresetRegion] {
+        self.preset.memory_map()
+    }
+
+    /// Get the motherboard
+    pub fn motherboard(&mut self) -> &mut MotherBoard {
+        &mut self.motherboard
+    }
+
+    /// Get the CPU
+    pub fn cpu(&mut self) -> &mut Cpu {
+        self.motherboard.cpu()
+    }
+
+    /// Reset the motherboard and initialize the stack pointers again.
+    /// Display output and pending keyboard input are kept.
+    pub fn reset(&mut self, kind: ResetKind) {
+        self.motherboard.reset(kind);
+        self.preset.init_registers(&mut self.motherboard);
+    }
+
+    /// Get the output written to the display
+    pub fn display(&self) -> &OutputCapture {
+        &self.display
+    }
+
+    /// Get the keyboard's input script, to provide the lines the program will read
+    /// (`None` for presets without a keyboard).
+    /// Once the script is exhausted, the keyboard returns empty lines.
+    pub fn keyboard(&self) -> Option<&InputScript> {
+        self.keyboard.as_ref()
+    }
+
+    /// Get the output written to the debug interface (`None` for presets without a debug interface)
+    pub fn debug(&self) -> Option<&OutputCapture> {
+        self.debug.as_ref()
+    }
+
+    /// Get the motherboard, dropping the accessors
+    pub fn into_motherboard(self) -> MotherBoard {
+        self.motherboard
+    }
+}
@@ -0,0 +1,7 @@
+//! This module provides ready-made boards, to run programs without choosing and mapping components by hand.
+//! See [`Preset`] for the list of available boards and their memory map.
+
+mod board;
+mod preset;
+
+pub use self::{board::*, preset::*};
@@ -0,0 +1,296 @@
+use std::{fmt, str::FromStr};
+
+use lrvm::board::MotherBoard;
+
+use crate::config::{
+    ComponentConfig, ComponentRegistry, ConfigError, InputScript, InputTarget, MachineConfig,
+    OutputCapture, OutputTarget,
+};
+
+use super::PresetBoard;
+
+/// A ready-made board.
+///
+/// Presets are built from the components registered in a [`ComponentRegistry`]
+/// (use `lrvm_aux::registry::registry()` for the auxiliary components):
+///
+/// ```no_run
+/// # use lrvm_tools::{config::ComponentRegistry, debug::*, presets::Preset};
+/// # let registry = ComponentRegistry::new();
+/// # let program = vec![];
+/// let mut board = Preset::Console.build(program, &registry).unwrap();
+/// board.keyboard().unwrap().push_line("Hello!");
+/// run_vm(board.cpu(), RunConfig::halt_on_ex());
+/// println!("{}", board.display().text());
+/// ```
+///
+/// All presets share the same memory map, each one adding components to the previous one:
+///
+/// | Address      | Size        | Component                                       | Type                 | Presets                     |
+/// | ------------ | ----------- | ----------------------------------------------- | -------------------- | --------------------------- |
+/// | `0x00000000` | `0x10000`   | BootROM, containing the program                 | `bootrom`            | minimal, console, dev-kit   |
+/// | `0x00100000` | `0x100000`  | RAM                                             | `ram`                | minimal, console, dev-kit   |
+/// | `0x00200000` | `0x100`     | Buffered display (see [`PresetBoard::display`]) | `buffered_display`   | minimal, console, dev-kit   |
+/// | `0x00201000` | `0x100`     | Line keyboard (see [`PresetBoard::keyboard`])   | `sync_line_keyboard` | console, dev-kit            |
+/// | `0x00202000` | `0x18`      | Realtime clock                                  | `realtime_clock`     | dev-kit                     |
+/// | `0x00203000` | `0x20`      | Debug interface (see [`PresetBoard::debug`])    | `basic_debug`        | dev-kit                     |
+///
+/// The program starts at address `0x00000000` in supervisor mode. Each mode gets its own stack at the end of the RAM,
+/// and the stack pointers are initialized to the top of them as stacks grow downwards:
+///
+/// | Address      | Size        | Region                                                         |
+/// | ------------ | ----------- | -------------------------------------------------------------- |
+/// | `0x00100000` | `0xF0000`   | Free RAM, followed by the user stack (`usp` = `0x001F0000`, see [`Preset::user_stack_top`]) |
+/// | `0x001F0000` | `0x10000`   | Supervisor stack (`ssp` = `0x00200000`, see [`Preset::supervisor_stack_top`]) |
+///
+/// The user stack is not bounded, so it grows towards the free RAM and may overwrite the program's data.
+/// Note that a `RESET` instruction run by the program clears all registers, including the stack pointers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Preset {
+    /// BootROM, RAM and display
+    Minimal,
+    /// Minimal board with a keyboard
+    Console,
+    /// Console board with a realtime clock and a debug interface
+    DevKit,
+}
+
+/// A region of a preset's memory map
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PresetRegion {
+    /// Name of the component
+    pub name: &'static str,
+    /// Type of the component, as known by the [`ComponentRegistry`]
+    pub typ: &'static str,
+    /// Mapping address
+    pub addr: u32,
+    /// Size, in bytes
+    pub size: u32,
+}
+
+impl PresetRegion {
+    /// Get the address of the region's last word
+    pub fn end_addr(&self) -> u32 {
+        self.addr + self.size - 4
+    }
+}
+
+/// Size of the supervisor stack, at the end of the RAM
+const SUPERVISOR_STACK_SIZE: u32 = 0x0001_0000;
+
+/// Memory map shared by all presets, in mapping order
+static REGIONS: [PresetRegion; 6] = [
+    PresetRegion {
+        name: "BootROM",
+        typ: "bootrom",
+        addr: 0x0000_0000,
+        size: 0x0001_0000,
+    },
+    PresetRegion {
+        name: "RAM",
+        typ: "ram",
+        addr: 0x0010_0000,
+        size: 0x0010_0000,
+    },
+    PresetRegion {
+        name: "Display",
+        typ: "buffered_display",
+        addr: 0x0020_0000,
+        size: 0x100,
+    },
+    PresetRegion {
+        name: "Keyboard",
+        typ: "sync_line_keyboard",
+        addr: 0x0020_1000,
+        size: 0x100,
+    },
+    PresetRegion {
+        name: "Clock",
+        typ: "realtime_clock",
+        addr: 0x0020_2000,
+        size: 0x18,
+    },
+    PresetRegion {
+        name: "Debug",
+        typ: "basic_debug",
+        addr: 0x0020_3000,
+        size: 0x20,
+    },
+];
+
+impl Preset {
+    /// List all presets
+    pub fn all() -> [Self; 3] {
+        [Self::Minimal, Self::Console, Self::DevKit]
+    }
+
+    /// Get the preset's name
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Minimal => "minimal",
+            Self::Console => "console",
+            Self::DevKit => "dev-kit",
+        }
+    }
+
+    /// Get the preset's memory map, in mapping order (which is also the order of the components' IDs)
+    pub fn memory_map(self) -> &'static [PresetRegion] {
+        match self {
+            Self::Minimal => &REGIONS[..3],
+            Self::Console => &REGIONS[..4],
+            Self::DevKit => &REGIONS,
+        }
+    }
+
+    /// Get the initial value of the supervisor stack pointer (the address right after the end of the RAM)
+    pub fn supervisor_stack_top(self) -> u32 {
+        REGIONS[1].addr + REGIONS[1].size
+    }
+
+    /// Get the initial value of the user stack pointer (the address right before the supervisor stack)
+    pub fn user_stack_top(self) -> u32 {
+        self.supervisor_stack_top() - SUPERVISOR_STACK_SIZE
+    }
+
+    /// Get the maximum size of a program, in bytes
+    pub fn max_program_size(self) -> u32 {
+        REGIONS[0].size
+    }
+
+    /// Build the board with the provided program: components are created from the registry,
+    /// then mapped, and the motherboard is reset with the stack pointers initialized.
+    pub fn build(
+        self,
+        program: Vec<u32>,
+        registry: &ComponentRegistry,
+    ) -> Result<PresetBoard, ConfigError> {
+        let display = OutputCapture::new();
+        let keyboard = InputScript::new();
+        let debug = OutputCapture::new();
+
+        let motherboard = self.build_with_targets(
+            program,
+            registry,
+            OutputTarget::Capture(display.clone()),
+            InputTarget::Script(keyboard.clone()),
+            OutputTarget::Capture(debug.clone()),
+        )?;
+
+        Ok(PresetBoard::new(
+            self,
+            motherboard,
+            display,
+            match self {
+                Self::Minimal => None,
+                Self::Console | Self::DevKit => Some(keyboard),
+            },
+            match self {
+                Self::Minimal | Self::Console => None,
+                Self::DevKit => Some(debug),
+            },
+        ))
+    }
+
+    /// Build the board like [`Preset::build`], with the display and the debug interface writing to the standard output
+    /// and the keyboard reading from the standard input, for interactive use.
+    pub fn build_with_stdio(
+        self,
+        program: Vec<u32>,
+        registry: &ComponentRegistry,
+    ) -> Result<MotherBoard, ConfigError> {
+        self.build_with_targets(
+            program,
+            registry,
+            OutputTarget::Stdout,
+            InputTarget::Stdin,
+            OutputTarget::Stdout,
+        )
+    }
+
+    /// (Internal) Build the board with the provided targets for the display, the keyboard and the debug interface
+    fn build_with_targets(
+        self,
+        program: Vec<u32>,
+        registry: &ComponentRegistry,
+        display: OutputTarget,
+        keyboard: InputTarget,
+        debug: OutputTarget,
+    ) -> Result<MotherBoard, ConfigError> {
+        if program.len() as u64 * 4 > self.max_program_size() as u64 {
+            return Err(ConfigError::machine(format!(
+                "Program is too large for the '{}' preset ({} bytes max.)",
+                self.name(),
+                self.max_program_size()
+            )));
+        }
+
+        let components = self
+            .memory_map()
+            .iter()
+            .map(|region| {
+                let mut component = ComponentConfig::new(region.typ);
+                component.addr = Some(region.addr);
+
+                match region.typ {
+                    "bootrom" => {
+                        component.size = Some(region.size);
+                        component.boot = Some(true);
+                    }
+                    "ram" => component.size = Some(region.size),
+                    "buffered_display" => {
+                        component.size = Some(region.size);
+                        component.output_target = Some(display.clone());
+                    }
+                    "sync_line_keyboard" => {
+                        component.size = Some(region.size);
+                        component.input_target = Some(keyboard.clone());
+                    }
+                    "basic_debug" => component.output_target = Some(debug.clone()),
+                    _ => {}
+                }
+
+                component
+            })
+            .collect();
+
+        let config = MachineConfig {
+            boot: None,
+            components,
+        };
+
+        let mut motherboard = config.build_with_boot_image(registry, ".", &program)?;
+
+        self.init_registers(&mut motherboard);
+
+        Ok(motherboard)
+    }
+
+    /// Initialize the registers of a freshly reset motherboard
+    pub(super) fn init_registers(self, motherboard: &mut MotherBoard) {
+        let cpu = motherboard.cpu();
+        cpu.regs.ssp = self.supervisor_stack_top();
+        cpu.regs.usp = self.user_stack_top();
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Preset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::all()
+            .into_iter()
+            .find(|preset| preset.name() == s)
+            .ok_or_else(|| {
+                format!(
+                    "Unknown preset '{}' (available presets: minimal, console, dev-kit)",
+                    s
+                )
+            })
+    }
+}
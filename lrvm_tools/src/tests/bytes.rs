@@ -0,0 +1,19 @@
+use crate::bytes::{bytes_to_words, words_to_bytes};
+
+#[test]
+fn bytes_to_words_partial_word() {
+    assert_eq!(
+        bytes_to_words([0x01, 0x02, 0x03, 0x04, 0x05, 0x06]),
+        vec![0x01020304, 0x05060000]
+    );
+
+    assert_eq!(bytes_to_words([0xAB]), vec![0xAB000000]);
+    assert_eq!(bytes_to_words([0x01, 0x02, 0x03]), vec![0x01020300]);
+    assert_eq!(bytes_to_words([]), Vec::<u32>::new());
+}
+
+#[test]
+fn bytes_to_words_round_trip() {
+    let bytes = (0..=255).collect::<Vec<u8>>();
+    assert_eq!(words_to_bytes(bytes_to_words(&bytes)), bytes);
+}
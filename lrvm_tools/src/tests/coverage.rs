@@ -0,0 +1,64 @@
+use super::vm::run_code;
+use crate::asm::{cst, Instr, Program, Reg};
+use crate::debug::{BranchCoverage, Coverage};
+use crate::lasm::assemble_with_map;
+
+static SOURCE: &str = "main:
+    cmp a0, 0
+    ifeq
+    add a1, 1
+    cmp a0, 1
+    ifeq
+    add a2, 1
+    halt
+";
+
+fn program() -> Program {
+    Program::from_instr(vec![
+        Instr::Cmp(Reg::a0, 0u8.into()),
+        Instr::If(cst::ZF.into()),
+        Instr::Add(Reg::a1, 1u8.into()),
+        Instr::Cmp(Reg::a0, 1u8.into()),
+        Instr::If(cst::ZF.into()),
+        Instr::Add(Reg::a2, 1u8.into()),
+        Instr::Halt(),
+    ])
+}
+
+#[test]
+fn coverage() {
+    let (code, map) = assemble_with_map(SOURCE).unwrap();
+    assert_eq!(code, program().encode());
+
+    let mut coverage = Coverage::with_source_map(map);
+    run_code(&code, |cpu, step| coverage.record(cpu, step));
+
+    assert_eq!(coverage.executed_addrs().count(), 6);
+    assert_eq!(coverage.hits(0x14), 0);
+
+    assert_eq!(
+        coverage.branches().get(&0x04),
+        Some(&BranchCoverage {
+            taken: 1,
+            skipped: 0
+        })
+    );
+    assert_eq!(
+        coverage.branches().get(&0x10),
+        Some(&BranchCoverage {
+            taken: 0,
+            skipped: 1
+        })
+    );
+
+    let lcov = coverage.to_lcov("coverage", "src/main.lasm").unwrap();
+
+    assert!(lcov.starts_with("TN:coverage\nSF:src/main.lasm\nFN:1,main\nFNDA:1,main\n"));
+    assert!(lcov.contains("BRDA:3,0,0,1\nBRDA:3,0,1,0\nBRDA:6,0,0,0\nBRDA:6,0,1,1\nBRF:4\nBRH:2\n"));
+    assert!(lcov.contains("DA:7,0\nDA:8,1\nLF:7\nLH:6\nend_of_record\n"));
+
+    let summary = coverage.format_summary();
+
+    assert!(summary.contains("Lines:     6/7 (85.71%)"));
+    assert!(summary.contains("Lines never run: 7"));
+}
@@ -0,0 +1,84 @@
+use lrvm::board::{
+    Bus, MotherBoard, DEVICE_TABLE_ADDR, DEVICE_TABLE_ENTRY_SIZE, DEVICE_TABLE_HEADER_SIZE,
+    DEVICE_TABLE_MAGIC, DEVICE_TABLE_NOT_MAPPED, DEVICE_TABLE_VERSION,
+};
+
+use crate::{
+    bytes::words_to_bytes,
+    metadata::{DeviceCategory, StorageType},
+};
+
+use super::vm::TestRam;
+
+#[test]
+fn device_table() {
+    let mut motherboard = MotherBoard::with_device_table(
+        vec![
+            Box::new(TestRam {
+                storage: vec![0; 16],
+            }) as Box<dyn Bus>,
+            Box::new(TestRam {
+                storage: vec![0; 32],
+            }),
+        ],
+        DEVICE_TABLE_ADDR,
+    )
+    .unwrap();
+
+    assert_eq!(motherboard.count(), 3);
+
+    // Map the first RAM only, the table must be updated
+    motherboard.map(|mem| mem.map(0x1000, 0).unwrap());
+
+    motherboard.map(|mem| {
+        let mut read = |addr: u32| {
+            let mut ex = 0;
+            let word = mem.read(addr, &mut ex);
+            assert_eq!(ex, 0, "Exception while reading address {:#010X}", addr);
+            word
+        };
+
+        assert_eq!(read(DEVICE_TABLE_ADDR), DEVICE_TABLE_MAGIC);
+        assert_eq!(read(DEVICE_TABLE_ADDR + 0x04), DEVICE_TABLE_VERSION);
+        assert_eq!(read(DEVICE_TABLE_ADDR + 0x08), 3);
+        assert_eq!(read(DEVICE_TABLE_ADDR + 0x0C), DEVICE_TABLE_ENTRY_SIZE);
+
+        let entry = |read: &mut dyn FnMut(u32) -> u32, aux_id: u32| -> Vec<u32> {
+            let addr =
+                DEVICE_TABLE_ADDR + DEVICE_TABLE_HEADER_SIZE + aux_id * DEVICE_TABLE_ENTRY_SIZE;
+            (0..DEVICE_TABLE_ENTRY_SIZE / 4)
+                .map(|i| read(addr + i * 4))
+                .collect()
+        };
+
+        let ram = entry(&mut read, 0);
+        assert_eq!(ram[0], 0);
+        assert_eq!(ram[6], 0x1000);
+        assert_eq!(ram[7], 0x1000 + 16 * 4 - 1);
+        assert_eq!(
+            String::from_utf8(words_to_bytes(&ram[8..])).unwrap(),
+            format!("{:\0<32}", "Test RAM")
+        );
+
+        let unmapped = entry(&mut read, 1);
+        assert_eq!(unmapped[0], 1);
+        assert_eq!(unmapped[6], DEVICE_TABLE_NOT_MAPPED);
+        assert_eq!(unmapped[7], DEVICE_TABLE_NOT_MAPPED);
+
+        let table = entry(&mut read, 2);
+        assert_eq!(table[6], DEVICE_TABLE_ADDR);
+        assert_eq!(
+            table[7],
+            DEVICE_TABLE_ADDR + DEVICE_TABLE_HEADER_SIZE + 3 * DEVICE_TABLE_ENTRY_SIZE - 1
+        );
+        assert!(matches!(
+            DeviceCategory::decode(((table[3] as u64) << 32) + table[4] as u64),
+            Ok(DeviceCategory::Storage(StorageType::DeviceTable))
+        ));
+
+        // The table is read-only
+        let mut ex = 0;
+        mem.write(DEVICE_TABLE_ADDR, 0, &mut ex);
+        assert_ne!(ex, 0);
+    });
+}
@@ -18,3 +18,65 @@ fn lasm_test() {
         "Bad assembly output"
     );
 }
+
+static MAPPED_ASM: &str = "COUNT = 2
+
+main:
+    cpy a0, COUNT
+.loop:
+    sub a0, 1
+    cmp a0, 0
+    ifnq
+    jp .loop
+    halt
+
+#d \"hi!\"
+#align 32
+
+data_end:
+    halt
+";
+
+#[test]
+fn lasm_source_map() {
+    let (bytes, map) = lasm::assemble_with_map(MAPPED_ASM)
+        .unwrap_or_else(|r| panic!("Failed to assemble mapped program: {}", r));
+
+    assert_eq!(map.size() as usize, bytes.len());
+    assert_eq!(map.size(), 0x20);
+
+    let labels = map
+        .labels()
+        .iter()
+        .map(|label| (label.name.as_str(), label.addr, label.local, label.line))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        labels,
+        vec![
+            ("main", 0x00, false, 3),
+            ("main.loop", 0x04, true, 5),
+            ("data_end", 0x1C, false, 15)
+        ]
+    );
+
+    assert_eq!(
+        map.instr_lines().collect::<Vec<_>>(),
+        vec![
+            (0x00, 4),
+            (0x04, 6),
+            (0x08, 7),
+            (0x0C, 8),
+            (0x10, 9),
+            (0x14, 10),
+            (0x1C, 16)
+        ]
+    );
+
+    assert_eq!(map.mnemonic_of(0x0C), Some("ifnq"));
+    assert_eq!(map.mnemonic_of(0x18), None);
+    assert_eq!(
+        map.function_of(0x18).map(|label| label.name.as_str()),
+        Some("main")
+    );
+}
@@ -1,3 +1,8 @@
 mod asm;
+mod bytes;
+mod coverage;
+mod device_table;
 mod ids;
 mod lasm;
+mod profile;
+mod vm;
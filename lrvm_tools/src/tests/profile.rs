@@ -0,0 +1,95 @@
+use super::vm::{run_code, run_program};
+use crate::asm::{Instr, Program, Reg};
+use crate::debug::{ProfileMode, Profiler};
+use crate::lasm::assemble_with_map;
+
+static SOURCE: &str = "
+main:
+    cpy ssp, 0x100
+    call func
+    call func
+    halt
+
+func:
+    add a0, 1
+    call leaf
+    ret
+
+leaf:
+    add a1, 1 ; leaf function
+    ret
+";
+
+fn program() -> Program {
+    Program::from_instr(vec![
+        Instr::Cpy(Reg::ssp, 0x100u16.into()),
+        Instr::Call(0x10u16.into()),
+        Instr::Call(0x10u16.into()),
+        Instr::Halt(),
+        Instr::Add(Reg::a0, 1u8.into()),
+        Instr::Call(0x1Cu16.into()),
+        Instr::Pop(Reg::pc),
+        Instr::Add(Reg::a1, 1u8.into()),
+        Instr::Pop(Reg::pc),
+    ])
+}
+
+fn profile(profiler: &mut Profiler) {
+    run_program(&program(), |cpu, step| profiler.record(cpu, step));
+}
+
+#[test]
+fn source_map() {
+    let (code, map) = assemble_with_map(SOURCE).unwrap();
+
+    assert_eq!(code, program().encode());
+    assert_eq!(map.size(), 0x24);
+    assert_eq!(map.label("func").map(|label| label.addr), Some(0x10));
+    assert_eq!(
+        map.function_of(0x20).map(|label| label.name.as_str()),
+        Some("leaf")
+    );
+    assert_eq!(map.line_of(0x1C), Some(14));
+}
+
+#[test]
+fn profile_counts() {
+    let (code, map) = assemble_with_map(SOURCE).unwrap();
+
+    let mut profiler = Profiler::with_source_map(ProfileMode::Count, map);
+    run_code(&code, |cpu, step| profiler.record(cpu, step));
+
+    assert_eq!(profiler.instructions(), 14);
+    assert_eq!(profiler.samples(), 14);
+    assert_eq!(profiler.call_depth(), 0);
+    assert_eq!(profiler.hot_spots()[0].1, 2);
+
+    let functions = profiler.functions();
+    let leaf = functions.iter().find(|func| func.name == "leaf").unwrap();
+    let func = functions.iter().find(|func| func.name == "func").unwrap();
+
+    assert_eq!(
+        (leaf.calls, leaf.self_samples, leaf.total_samples),
+        (2, 4, 4)
+    );
+    assert_eq!(
+        (func.calls, func.self_samples, func.total_samples),
+        (2, 6, 10)
+    );
+
+    assert_eq!(
+        profiler.folded_stacks(),
+        "main 4\nmain;func 6\nmain;func;leaf 4\n"
+    );
+}
+
+#[test]
+fn profile_sampling_without_labels() {
+    let mut profiler = Profiler::new(ProfileMode::Sample(2));
+    profile(&mut profiler);
+
+    assert_eq!(profiler.instructions(), 14);
+    assert_eq!(profiler.samples(), 7);
+    assert!(profiler.folded_stacks().starts_with("[root] "));
+    assert!(profiler.format_report().contains("0x0000001C"));
+}
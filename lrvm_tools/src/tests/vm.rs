@@ -0,0 +1,54 @@
+use lrvm::board::{Bus, ResetKind};
+use lrvm::cpu::Cpu;
+
+use crate::asm::Program;
+use crate::bytes::bytes_to_words;
+use crate::debug::{prepare_vm, run_vm_with_hook, ExecStep, RunConfig};
+use crate::metadata::{DeviceMetadata, MemoryType};
+
+/// Minimal RAM used to run test programs
+pub struct TestRam {
+    pub storage: Vec<u32>,
+}
+
+impl Bus for TestRam {
+    fn name(&self) -> &'static str {
+        "Test RAM"
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        DeviceMetadata::new(
+            0,
+            self.storage.len() as u32 * 4,
+            MemoryType::Ram.into(),
+            None,
+            None,
+        )
+        .encode()
+    }
+
+    fn read(&mut self, addr: u32, _ex: &mut u16) -> u32 {
+        self.storage[addr as usize / 4]
+    }
+
+    fn write(&mut self, addr: u32, word: u32, _ex: &mut u16) {
+        self.storage[addr as usize / 4] = word;
+    }
+
+    fn reset(&mut self, _kind: ResetKind) {}
+}
+
+/// Run a program loaded at address 0 of a 256-byte RAM, until it halts or raises an exception
+pub fn run_program(program: &Program, hook: impl FnMut(&Cpu, ExecStep)) {
+    run_code(&program.encode(), hook);
+}
+
+/// Run a machine code loaded at address 0 of a 256-byte RAM, until it halts or raises an exception
+pub fn run_code(code: &[u8], hook: impl FnMut(&Cpu, ExecStep)) {
+    let mut storage = bytes_to_words(code);
+    storage.resize(64, 0);
+
+    let mut motherboard = prepare_vm(vec![Box::new(TestRam { storage })]);
+
+    run_vm_with_hook(motherboard.cpu(), RunConfig::halt_on_ex(), hook);
+}